@@ -11,23 +11,136 @@ extern "C" {
 
 typedef void* bridge_engine_t;
 
+typedef struct {
+    float frame_rate;
+    uint32_t width;       // stage size in pixels
+    uint32_t height;
+    uint32_t num_frames;
+    uint32_t swf_version;
+} bridge_movie_info_t;
+
+typedef struct {
+    uint64_t frame;                 // engine ticks so far
+    uint32_t movie_loaded;
+    uint32_t swf_version;
+    uint32_t shapes_registered;
+    uint32_t bitmaps_registered;
+    uint32_t frames_submitted;
+    uint32_t frames_render_skipped;
+    uint32_t last_cmds_total;       // last_*: most recently submitted frame
+    uint32_t last_cmds_shapes;
+    uint32_t last_cmds_bitmaps;
+    uint32_t last_cmds_other;
+    uint32_t last_tris;
+    uint64_t tess_ms_fills;         // tess_*: totals since the movie loaded
+    uint64_t tess_ms_strokes;
+    uint64_t tess_ms_max_shape;
+    uint64_t input_counter;
+    float fps;                      // same as bridge_engine_get_fps
+    float fps_min;                  // slowest / fastest tick in the fps window
+    float fps_max;
+} bridge_diag_t;
+
+// Button ids for bridge_engine_button; order matches the `button_<name>` keys in renderer.cfg.
+enum {
+    BRIDGE_BUTTON_A = 0,
+    BRIDGE_BUTTON_B,
+    BRIDGE_BUTTON_X,
+    BRIDGE_BUTTON_Y,
+    BRIDGE_BUTTON_L,
+    BRIDGE_BUTTON_R,
+    BRIDGE_BUTTON_START,
+    BRIDGE_BUTTON_SELECT,
+    BRIDGE_BUTTON_DUP,
+    BRIDGE_BUTTON_DDOWN,
+    BRIDGE_BUTTON_DLEFT,
+    BRIDGE_BUTTON_DRIGHT,
+    BRIDGE_BUTTON_ZL,
+    BRIDGE_BUTTON_ZR,
+};
+
+// getURL hook: (user, url, target), or (user, command, args) for fscommand/javascript URLs.
+// Strings are only valid during the call.
+typedef void (*bridge_navigate_cb)(void* user, const char* url, const char* target);
+// Text-entry hook: write NUL-terminated UTF-8 into out (cap bytes); return nonzero if confirmed.
+typedef uint32_t (*bridge_text_input_cb)(void* user, const char* initial, char* out, size_t cap);
+
+// A movie that can't be loaded (unreadable, rejected, AS3) still yields a handle in error mode:
+// each tick draws the message on the top screen, and bridge_engine_last_error returns it.
+// bridge_engine_create_playlist returns NULL only for an empty list.
 bridge_engine_t bridge_engine_create(const char* swf_path, int screen_w, int screen_h);
+bridge_engine_t bridge_engine_create_playlist(const char* const* paths, uint32_t count, int screen_w, int screen_h, uint32_t timeout_ms);
+uint32_t bridge_engine_next(bridge_engine_t handle);
+uint32_t bridge_engine_prev(bridge_engine_t handle);
 void bridge_engine_destroy(bridge_engine_t handle);
-void bridge_engine_tick(bridge_engine_t handle, uint32_t dt_ms);
+// A panic inside the tick is caught: the engine switches to error mode and shows it on screen.
+uint32_t bridge_engine_tick(bridge_engine_t handle, uint32_t dt_ms);
 void bridge_engine_mouse_move(bridge_engine_t handle, int x, int y);
 void bridge_engine_mouse_button(bridge_engine_t handle, int button, bool down);
+void bridge_engine_mouse_wheel(bridge_engine_t handle, int delta); // lines, positive = up
+void bridge_engine_touch(bridge_engine_t handle, int x, int y, bool down);
+void bridge_engine_button(bridge_engine_t handle, uint32_t button, bool down);
+void bridge_engine_pad_axis(bridge_engine_t handle, int dx, int dy);
+void bridge_engine_set_pad_mode(bridge_engine_t handle, uint32_t mode); // 0 mouse, 1 arrow keys, 2 off
 void bridge_engine_key(bridge_engine_t handle, int keycode, bool down);
 uint32_t bridge_engine_last_error(char* out, uint32_t out_len);
 
 uint32_t bridge_runlog_drain(char* out, uint32_t out_len);
+uint32_t bridge_runlog_recent_warnings(char* out, uint32_t out_len); // drains the last <=8 warnings, newline-separated
+uint32_t bridge_avm_trace_drain(char* out, uint32_t out_len); // drains the last <=32 AS trace() lines, newline-separated
+
+// Render toggles for bridge_set_render_config (args: 1 = on, 0 = off, <0 = unchanged).
+#define BRIDGE_RENDER_TEXTURED_BITMAPS       (1u << 0)
+#define BRIDGE_RENDER_MASKS                  (1u << 1)
+#define BRIDGE_RENDER_SUBPIXEL_SHAPES        (1u << 2)
+#define BRIDGE_RENDER_UNSUPPORTED_FILL_HATCH (1u << 3)
+#define BRIDGE_RENDER_BILINEAR               (1u << 4)
+#define BRIDGE_RENDER_AA_EDGES               (1u << 5)
+uint32_t bridge_set_render_config(int32_t textured_bitmaps, int32_t masks_enabled, int32_t subpixel_shapes,
+                                  int32_t unsupported_fill_hatch, int32_t bilinear, int32_t aa_edges);
+uint32_t bridge_reload_render_config(void); // re-read renderer.cfg; returns BRIDGE_RENDER_* bits
 void bridge_print_status(bridge_engine_t handle);
 void bridge_write_status_snapshot_ctx(bridge_engine_t handle);
 void bridge_request_command_dump_ctx(bridge_engine_t handle);
 uint32_t bridge_toggle_affine_debug_overlay_ctx(bridge_engine_t handle);
+uint32_t bridge_toggle_bounds_overlay_ctx(bridge_engine_t handle); // outline drawn shapes' bounds
+uint32_t bridge_toggle_tri_heatmap_ctx(bridge_engine_t handle); // tint shapes by triangle count
 void bridge_toggle_wireframe_once_ctx(bridge_engine_t handle);
 void bridge_set_wireframe_hold_ctx(bridge_engine_t handle, int enabled);
 uint32_t bridge_renderer_ready_ctx(bridge_engine_t handle);
 size_t bridge_get_status_text(bridge_engine_t handle, char* out, size_t cap);
+uint32_t bridge_engine_virtual_keyboard_open(bridge_engine_t handle);
+void bridge_engine_virtual_keyboard_dismissed(bridge_engine_t handle);
+uint32_t bridge_engine_toggle_stats_overlay(bridge_engine_t handle);
+uint32_t bridge_engine_record_input(bridge_engine_t handle, const char* path);
+uint32_t bridge_engine_replay_input(bridge_engine_t handle, const char* path);
+uint32_t bridge_engine_start_record(bridge_engine_t handle, const char* path); // render command trace
+void bridge_engine_stop_record(bridge_engine_t handle);
+uint32_t bridge_engine_replay_record(bridge_engine_t handle, const char* path); // draws the trace instead of ticking
+uint32_t bridge_engine_inject_event(bridge_engine_t handle, const char* event); // "M x y", "B button 0/1", "K keycode 0/1", "W delta"
+uint32_t bridge_engine_dump_diagnostics_json(bridge_engine_t handle, const char* path);
+uint32_t bridge_engine_capture_screenshot(bridge_engine_t handle, const char* path); // PNG of the top screen; bare name/NULL -> run bundle
+uint32_t bridge_engine_set_shape_cache_budget(bridge_engine_t handle, uint32_t bytes);
+uint32_t bridge_engine_set_bitmap_cache_budget(bridge_engine_t handle, uint32_t bytes);
+uint32_t bridge_engine_set_buffer_count(bridge_engine_t handle, uint32_t count);
+float bridge_engine_set_global_alpha(bridge_engine_t handle, float alpha);
+float bridge_engine_set_stereo_depth(bridge_engine_t handle, float depth);
+uint32_t bridge_engine_stereo_enabled(bridge_engine_t handle);
+void bridge_engine_set_background_color(bridge_engine_t handle, uint32_t enabled, uint32_t rgb); // rgb = 0xRRGGBB
+uint32_t bridge_engine_get_movie_info(bridge_engine_t handle, bridge_movie_info_t* out);
+uint32_t bridge_engine_get_diagnostics(bridge_engine_t handle, bridge_diag_t* out);
+float bridge_engine_get_fps(bridge_engine_t handle); // rolling average of the dt_ms passed to bridge_engine_tick
+uint32_t bridge_engine_reload(bridge_engine_t handle);
+void bridge_engine_set_paused(bridge_engine_t handle, uint32_t paused);
+uint32_t bridge_engine_step(bridge_engine_t handle);
+float bridge_engine_set_speed(bridge_engine_t handle, float speed);
+uint32_t bridge_engine_is_paused(bridge_engine_t handle);
+float bridge_engine_set_volume(bridge_engine_t handle, float volume);
+void bridge_engine_set_muted(bridge_engine_t handle, uint32_t muted);
+void bridge_engine_set_text_input_callback(bridge_engine_t handle, bridge_text_input_cb cb, void* user);
+// Only exported when the bridge is built with the `net` feature.
+void bridge_engine_set_navigate_callback(bridge_engine_t handle, bridge_navigate_cb cb, void* user);
+void bridge_engine_set_fscommand_callback(bridge_engine_t handle, bridge_navigate_cb cb, void* user);
 
 #ifdef __cplusplus
 }
@@ -0,0 +1,634 @@
+//! Render command trace: record the `FramePacket` handed to the renderer each frame and play it
+//! back later without ticking the player, to tell renderer bugs apart from player behavior.
+//!
+//! Trace format (text, one line per command, `#` comments allowed):
+//! - `frame <r> <g> <b>`: starts a packet with its clear color
+//! - one line per `RenderCmd`, tagged by kind: `rect`, `fill`, `text`, `stroke`, `mask_rect`,
+//!   `mask_shape`, `pop_mask`, `blend`, `pop_blend`, `bitmap`, `stats`, `loading`, `error`,
+//!   `bounds`, `heat`, `affine`
+//!
+//! Matrices are written `a b c d tx ty`, rects `x y w h`, flags `0|1`, and optional fields as
+//! `-` when absent. Overlay text is the rest of the line with `\n` and `\\` escaped.
+//!
+//! Shape and bitmap keys are cache handles from the session that recorded them, so the trace
+//! carries what they pointed at. Before a frame's commands, each key the frame references is
+//! defined the first time it's seen and again whenever its cache entry changed:
+//! - `shape_def <key> <tess_failed> <tess_partial> <stroke_failed> <stroke_partial> <hex>`:
+//!   the meshes as a `persist::encode_shape` record
+//! - `bitmap_def <key> <w> <h> <source_w> <source_h> <premultiplied> <opaque> <hex rgba>`
+//! - `shape_def <key> -` / `bitmap_def <key> -`: the key was not cached (evicted, say)
+//!
+//! Replay loads these into caches of its own, so a trace draws the same in any session.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::str::{FromStr, SplitWhitespace};
+
+use crate::render::cache::bitmaps::{AlphaMode, BitmapKey, BitmapSurface};
+use crate::render::cache::persist;
+use crate::render::cache::shapes::{FillPaint, ShapeKey};
+use crate::render::{ClearColor, ColorTransform, DrawBlend, FramePacket, Matrix2D, RectI, RenderCmd, SharedCaches, TexUvRect};
+
+pub struct CmdRecorder {
+    out: BufWriter<File>,
+    path: String,
+    frames: u32,
+    /// Cache revision last written for each key (`None`: written as not cached).
+    shape_revisions: HashMap<ShapeKey, Option<u64>>,
+    bitmap_revisions: HashMap<BitmapKey, Option<u64>>,
+    /// Bitmaps referenced by the frame being recorded.
+    frame_bitmaps: Vec<BitmapKey>,
+}
+
+impl CmdRecorder {
+    pub fn create(path: &str) -> Result<Self, String> {
+        let file = File::create(path).map_err(|e| format!("cmd trace create failed: {} ({})", path, e))?;
+        let mut out = BufWriter::new(file);
+        let _ = writeln!(out, "# ruffle3ds render command trace v2");
+        Ok(Self {
+            out,
+            path: path.to_string(),
+            frames: 0,
+            shape_revisions: HashMap::new(),
+            bitmap_revisions: HashMap::new(),
+            frame_bitmaps: Vec::new(),
+        })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Append `packet`, defining the shapes and bitmaps it draws from `caches` as needed.
+    pub fn record(&mut self, packet: &FramePacket, caches: &SharedCaches) {
+        let c = packet.clear;
+        let _ = writeln!(self.out, "frame {} {} {}", c.r, c.g, c.b);
+        self.define_shapes(packet, caches);
+        self.define_bitmaps(caches);
+        for cmd in &packet.cmds {
+            let _ = writeln!(self.out, "{}", format_cmd(cmd));
+        }
+        self.frames = self.frames.saturating_add(1);
+    }
+
+    pub fn finish(mut self) {
+        let _ = self.out.flush();
+    }
+
+    /// Write `shape_def`s for referenced shapes whose revision the trace doesn't hold yet, and
+    /// collect the bitmaps the packet draws (directly or through bitmap fills).
+    fn define_shapes(&mut self, packet: &FramePacket, caches: &SharedCaches) {
+        self.frame_bitmaps.clear();
+        let shapes = caches.shapes.lock().unwrap();
+        for cmd in &packet.cmds {
+            if let RenderCmd::BlitBitmap { bitmap_key, .. } = cmd {
+                self.frame_bitmaps.push(*bitmap_key);
+            }
+            let Some(key) = shape_key(cmd) else {
+                continue;
+            };
+            for fill in (0..shapes.fill_count(key)).filter_map(|i| shapes.get_fill_mesh(key, i)) {
+                if let FillPaint::Bitmap { bitmap_key: Some(bitmap_key), .. } = fill.paint {
+                    self.frame_bitmaps.push(bitmap_key);
+                }
+            }
+            let revision = shapes.revision(key);
+            if self.shape_revisions.insert(key, revision) == Some(revision) {
+                continue;
+            }
+            let _ = match shapes.encode_entry(key) {
+                Some(record) => writeln!(
+                    self.out,
+                    "shape_def {} {} {} {} {} {}",
+                    key,
+                    shapes.is_tess_failed(key) as u8,
+                    shapes.is_tess_partial(key) as u8,
+                    shapes.is_stroke_failed(key) as u8,
+                    shapes.is_stroke_partial(key) as u8,
+                    hex(&record)
+                ),
+                None => writeln!(self.out, "shape_def {} -", key),
+            };
+        }
+    }
+
+    fn define_bitmaps(&mut self, caches: &SharedCaches) {
+        self.frame_bitmaps.sort_unstable();
+        self.frame_bitmaps.dedup();
+        let bitmaps = caches.bitmaps.lock().unwrap();
+        for &key in &self.frame_bitmaps {
+            let revision = bitmaps.revision(key);
+            if self.bitmap_revisions.insert(key, revision) == Some(revision) {
+                continue;
+            }
+            let _ = match bitmaps.get(key) {
+                Some(surface) => writeln!(
+                    self.out,
+                    "bitmap_def {} {} {} {} {} {} {} {}",
+                    key,
+                    surface.width,
+                    surface.height,
+                    surface.source_width,
+                    surface.source_height,
+                    (surface.alpha_mode == AlphaMode::Premultiplied) as u8,
+                    surface.is_opaque as u8,
+                    hex(&surface.rgba)
+                ),
+                None => writeln!(self.out, "bitmap_def {} -", key),
+            };
+        }
+    }
+}
+
+/// Streams packets back from a trace, one frame per `next_packet`.
+pub struct CmdReplay {
+    lines: Lines<BufReader<File>>,
+    line_no: usize,
+    /// Clear color of the next packet, read while finishing the previous one.
+    pending_clear: Option<ClearColor>,
+    path: String,
+    frames: u32,
+    /// Shapes and bitmaps defined by the trace; replayed packets draw from these.
+    caches: SharedCaches,
+}
+
+impl CmdReplay {
+    pub fn open(path: &str) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("cmd trace open failed: {} ({})", path, e))?;
+        Ok(Self {
+            lines: BufReader::new(file).lines(),
+            line_no: 0,
+            pending_clear: None,
+            path: path.to_string(),
+            frames: 0,
+            caches: replay_caches(),
+        })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Frames replayed so far.
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Caches to render replayed packets with (see `Renderer::render_with_caches`).
+    pub fn caches(&self) -> &SharedCaches {
+        &self.caches
+    }
+
+    /// Fill `out` with the next recorded frame; `Ok(false)` once the trace is exhausted.
+    pub fn next_packet(&mut self, out: &mut FramePacket) -> Result<bool, String> {
+        out.cmds.clear();
+        let mut started = match self.pending_clear.take() {
+            Some(clear) => {
+                out.clear = clear;
+                true
+            }
+            None => false,
+        };
+        while let Some(line) = self.lines.next() {
+            let line = line.map_err(|e| format!("cmd trace read failed: {} ({})", self.path, e))?;
+            self.line_no += 1;
+            let line_no = self.line_no;
+            let parse_err = || format!("cmd trace parse error line={} '{}'", line_no, line.trim());
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            if let Some(rest) = trimmed.strip_prefix("shape_def ") {
+                self.define_shape(rest).ok_or_else(parse_err)?;
+                continue;
+            }
+            if let Some(rest) = trimmed.strip_prefix("bitmap_def ") {
+                self.define_bitmap(rest).ok_or_else(parse_err)?;
+                continue;
+            }
+            if let Some(rest) = trimmed.strip_prefix("frame ") {
+                let clear = parse_clear(rest).ok_or_else(parse_err)?;
+                if started {
+                    self.pending_clear = Some(clear);
+                    self.frames = self.frames.saturating_add(1);
+                    return Ok(true);
+                }
+                out.clear = clear;
+                started = true;
+                continue;
+            }
+            if !started {
+                return Err(parse_err());
+            }
+            out.cmds.push(parse_cmd(line.trim_end()).ok_or_else(parse_err)?);
+        }
+        if started {
+            self.frames = self.frames.saturating_add(1);
+        }
+        Ok(started)
+    }
+
+    fn define_shape(&self, text: &str) -> Option<()> {
+        let mut f = Fields(text.split_whitespace());
+        let key: ShapeKey = f.num()?;
+        let mut shapes = self.caches.shapes.lock().unwrap();
+        if f.absent()? {
+            shapes.remove(key);
+            return Some(());
+        }
+        let (tess_failed, tess_partial, stroke_failed, stroke_partial) = (f.flag()?, f.flag()?, f.flag()?, f.flag()?);
+        let shape = persist::decode_shape(&unhex(f.0.next()?)?)?;
+        shapes.insert_meshes(
+            key,
+            0,
+            shape.bounds,
+            shape.fills,
+            tess_failed,
+            tess_partial,
+            shape.strokes,
+            stroke_failed,
+            stroke_partial,
+            shape.is_text,
+        );
+        Some(())
+    }
+
+    fn define_bitmap(&self, text: &str) -> Option<()> {
+        let mut f = Fields(text.split_whitespace());
+        let key: BitmapKey = f.num()?;
+        let mut bitmaps = self.caches.bitmaps.lock().unwrap();
+        if f.absent()? {
+            bitmaps.remove(key);
+            return Some(());
+        }
+        let surface = BitmapSurface {
+            width: f.num()?,
+            height: f.num()?,
+            source_width: f.num()?,
+            source_height: f.num()?,
+            alpha_mode: if f.flag()? { AlphaMode::Premultiplied } else { AlphaMode::Straight },
+            is_opaque: f.flag()?,
+            rgba: unhex(f.0.next()?)?,
+        };
+        if !surface.is_valid() {
+            return None;
+        }
+        bitmaps.insert(key, surface);
+        Some(())
+    }
+}
+
+/// Definitions are written once per revision, so replay caches must never evict one.
+fn replay_caches() -> SharedCaches {
+    let caches = SharedCaches::new();
+    caches.shapes.lock().unwrap().set_budget(usize::MAX / 2);
+    caches.bitmaps.lock().unwrap().set_budget(usize::MAX / 2);
+    caches
+}
+
+/// The shape cache key a command draws from, if any.
+fn shape_key(cmd: &RenderCmd) -> Option<ShapeKey> {
+    match cmd {
+        RenderCmd::DrawShapeSolidFill { shape_key, .. }
+        | RenderCmd::DrawTextSolidFill { shape_key, .. }
+        | RenderCmd::DrawShapeStroke { shape_key, .. }
+        | RenderCmd::PushMaskShape { shape_key, .. }
+        | RenderCmd::DebugTriHeatmap { shape_key, .. } => Some(*shape_key),
+        _ => None,
+    }
+}
+
+fn format_cmd(cmd: &RenderCmd) -> String {
+    match cmd {
+        RenderCmd::FillRect { rect, color_key, wireframe } => {
+            format!("rect {} {} {}", fmt_rect(rect), color_key, *wireframe as u8)
+        }
+        RenderCmd::DrawShapeSolidFill { shape_key, fill_idx, transform, solid_rgba, color_transform, color_key, wireframe } => format!(
+            "fill {} {} {} {} {} {} {}",
+            shape_key,
+            fill_idx,
+            fmt_matrix(transform),
+            fmt_rgba(solid_rgba),
+            fmt_color_transform(color_transform),
+            color_key,
+            *wireframe as u8
+        ),
+        RenderCmd::DrawTextSolidFill { shape_key, fill_idx, transform, solid_rgba, color_transform, color_key, wireframe } => format!(
+            "text {} {} {} {} {} {} {}",
+            shape_key,
+            fill_idx,
+            fmt_matrix(transform),
+            fmt_rgba(solid_rgba),
+            fmt_color_transform(color_transform),
+            color_key,
+            *wireframe as u8
+        ),
+        RenderCmd::DrawShapeStroke { shape_key, stroke_idx, transform, r, g, b, wireframe } => format!(
+            "stroke {} {} {} {} {} {} {}",
+            shape_key,
+            stroke_idx,
+            fmt_matrix(transform),
+            r,
+            g,
+            b,
+            *wireframe as u8
+        ),
+        RenderCmd::PushMaskRect { rect } => format!("mask_rect {}", fmt_rect(rect)),
+        RenderCmd::PushMaskShape { shape_key, transform } => format!("mask_shape {} {}", shape_key, fmt_matrix(transform)),
+        RenderCmd::PopMask => "pop_mask".to_string(),
+        RenderCmd::PushBlend { blend } => format!(
+            "blend {}",
+            match blend {
+                DrawBlend::Normal => "normal",
+                DrawBlend::Add => "add",
+                DrawBlend::Multiply => "multiply",
+            }
+        ),
+        RenderCmd::PopBlend => "pop_blend".to_string(),
+        RenderCmd::BlitBitmap { bitmap_key, transform, uv, color_transform, smoothing } => format!(
+            "bitmap {} {} {} {} {} {} {} {}",
+            bitmap_key,
+            fmt_matrix(transform),
+            uv.u0,
+            uv.v0,
+            uv.u1,
+            uv.v1,
+            fmt_color_transform(color_transform),
+            *smoothing as u8
+        ),
+        RenderCmd::DebugStatsOverlay { text } => format!("stats {}", escape(text)),
+        RenderCmd::DebugLoadingIndicator { percent } => match percent {
+            Some(p) => format!("loading {}", p),
+            None => "loading -".to_string(),
+        },
+        RenderCmd::DebugErrorScreen { text } => format!("error {}", escape(text)),
+        RenderCmd::DebugBoundsRect { rect, color_key } => format!("bounds {} {}", fmt_rect(rect), color_key),
+        RenderCmd::DebugTriHeatmap { shape_key, transform } => format!("heat {} {}", shape_key, fmt_matrix(transform)),
+        RenderCmd::DebugAffineRect { transform, r, g, b } => format!("affine {} {} {} {}", fmt_matrix(transform), r, g, b),
+    }
+}
+
+fn parse_cmd(line: &str) -> Option<RenderCmd> {
+    let (tag, rest) = line.split_once(' ').unwrap_or((line, ""));
+    // Text payloads take the rest of the line verbatim.
+    match tag {
+        "stats" => return Some(RenderCmd::DebugStatsOverlay { text: unescape(rest) }),
+        "error" => return Some(RenderCmd::DebugErrorScreen { text: unescape(rest) }),
+        _ => {}
+    }
+    let mut f = Fields(rest.split_whitespace());
+    let cmd = match tag {
+        "rect" => RenderCmd::FillRect { rect: f.rect()?, color_key: f.num()?, wireframe: f.flag()? },
+        "fill" | "text" => {
+            let (shape_key, fill_idx, transform) = (f.num()?, f.num()?, f.matrix()?);
+            let (solid_rgba, color_transform) = (f.rgba()?, f.color_transform()?);
+            let (color_key, wireframe) = (f.num()?, f.flag()?);
+            if tag == "fill" {
+                RenderCmd::DrawShapeSolidFill { shape_key, fill_idx, transform, solid_rgba, color_transform, color_key, wireframe }
+            } else {
+                RenderCmd::DrawTextSolidFill { shape_key, fill_idx, transform, solid_rgba, color_transform, color_key, wireframe }
+            }
+        }
+        "stroke" => RenderCmd::DrawShapeStroke {
+            shape_key: f.num()?,
+            stroke_idx: f.num()?,
+            transform: f.matrix()?,
+            r: f.num()?,
+            g: f.num()?,
+            b: f.num()?,
+            wireframe: f.flag()?,
+        },
+        "mask_rect" => RenderCmd::PushMaskRect { rect: f.rect()? },
+        "mask_shape" => RenderCmd::PushMaskShape { shape_key: f.num()?, transform: f.matrix()? },
+        "pop_mask" => RenderCmd::PopMask,
+        "blend" => RenderCmd::PushBlend {
+            blend: match f.0.next()? {
+                "normal" => DrawBlend::Normal,
+                "add" => DrawBlend::Add,
+                "multiply" => DrawBlend::Multiply,
+                _ => return None,
+            },
+        },
+        "pop_blend" => RenderCmd::PopBlend,
+        "bitmap" => RenderCmd::BlitBitmap {
+            bitmap_key: f.num()?,
+            transform: f.matrix()?,
+            uv: TexUvRect { u0: f.num()?, v0: f.num()?, u1: f.num()?, v1: f.num()? },
+            color_transform: f.color_transform()?,
+            smoothing: f.flag()?,
+        },
+        "loading" => RenderCmd::DebugLoadingIndicator {
+            percent: match f.0.next()? {
+                "-" => None,
+                p => Some(p.parse().ok()?),
+            },
+        },
+        "bounds" => RenderCmd::DebugBoundsRect { rect: f.rect()?, color_key: f.num()? },
+        "heat" => RenderCmd::DebugTriHeatmap { shape_key: f.num()?, transform: f.matrix()? },
+        "affine" => RenderCmd::DebugAffineRect { transform: f.matrix()?, r: f.num()?, g: f.num()?, b: f.num()? },
+        _ => return None,
+    };
+    Some(cmd)
+}
+
+fn parse_clear(text: &str) -> Option<ClearColor> {
+    let mut f = Fields(text.split_whitespace());
+    Some(ClearColor { r: f.num()?, g: f.num()?, b: f.num()? })
+}
+
+/// Whitespace-separated fields of one command line.
+struct Fields<'a>(SplitWhitespace<'a>);
+
+impl Fields<'_> {
+    fn num<T: FromStr>(&mut self) -> Option<T> {
+        self.0.next()?.parse().ok()
+    }
+
+    fn flag(&mut self) -> Option<bool> {
+        self.num::<u8>().map(|v| v != 0)
+    }
+
+    fn rect(&mut self) -> Option<RectI> {
+        Some(RectI { x: self.num()?, y: self.num()?, w: self.num()?, h: self.num()? })
+    }
+
+    fn matrix(&mut self) -> Option<Matrix2D> {
+        Some(Matrix2D { a: self.num()?, b: self.num()?, c: self.num()?, d: self.num()?, tx: self.num()?, ty: self.num()? })
+    }
+
+    /// Consume an absent (`-`) value; `Some(false)` leaves the next field in place.
+    fn absent(&mut self) -> Option<bool> {
+        if self.0.clone().next()? == "-" {
+            self.0.next();
+            return Some(true);
+        }
+        Some(false)
+    }
+
+    /// `Some(None)` for an absent (`-`) value.
+    fn rgba(&mut self) -> Option<Option<[u8; 4]>> {
+        if self.absent()? {
+            return Some(None);
+        }
+        Some(Some([self.num()?, self.num()?, self.num()?, self.num()?]))
+    }
+
+    fn color_transform(&mut self) -> Option<Option<ColorTransform>> {
+        if self.absent()? {
+            return Some(None);
+        }
+        let mut mul = [0.0f32; 4];
+        let mut add = [0.0f32; 4];
+        for v in mul.iter_mut().chain(add.iter_mut()) {
+            *v = self.num()?;
+        }
+        Some(Some(ColorTransform { mul, add }))
+    }
+}
+
+fn fmt_rect(r: &RectI) -> String {
+    format!("{} {} {} {}", r.x, r.y, r.w, r.h)
+}
+
+fn fmt_matrix(m: &Matrix2D) -> String {
+    format!("{} {} {} {} {} {}", m.a, m.b, m.c, m.d, m.tx, m.ty)
+}
+
+fn fmt_rgba(rgba: &Option<[u8; 4]>) -> String {
+    match rgba {
+        Some([r, g, b, a]) => format!("{} {} {} {}", r, g, b, a),
+        None => "-".to_string(),
+    }
+}
+
+fn fmt_color_transform(ct: &Option<ColorTransform>) -> String {
+    match ct {
+        Some(ct) => {
+            let [mr, mg, mb, ma] = ct.mul;
+            let [ar, ag, ab, aa] = ct.add;
+            format!("{} {} {} {} {} {} {} {}", mr, mg, mb, ma, ar, ag, ab, aa)
+        }
+        None => "-".to_string(),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for &b in bytes {
+        out.push(DIGITS[(b >> 4) as usize] as char);
+        out.push(DIGITS[(b & 0xF) as usize] as char);
+    }
+    out
+}
+
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    let digit = |c: u8| (c as char).to_digit(16).map(|d| d as u8);
+    let pairs = text.as_bytes().chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+    pairs.map(|pair| Some(digit(pair[0])? << 4 | digit(pair[1])?)).collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::cache::shapes::{FillMesh, Vertex2};
+
+    const IDENTITY: Matrix2D = Matrix2D { a: 1.0, b: 0.0, c: 0.0, d: 1.0, tx: 0.0, ty: 0.0 };
+
+    fn draw(shape_key: ShapeKey) -> RenderCmd {
+        RenderCmd::DrawShapeSolidFill {
+            shape_key,
+            fill_idx: 0,
+            transform: IDENTITY,
+            solid_rgba: None,
+            color_transform: None,
+            color_key: 0,
+            wireframe: false,
+        }
+    }
+
+    #[test]
+    fn trace_replays_meshes_and_bitmaps_into_fresh_caches() {
+        let caches = SharedCaches::new();
+        let mesh = FillMesh {
+            verts: vec![Vertex2 { x: 0, y: 0 }, Vertex2 { x: 4, y: 0 }, Vertex2 { x: 0, y: 4 }],
+            indices: vec![0, 1, 2],
+            paint: FillPaint::Bitmap { bitmap_id: 3, bitmap_key: Some(0xB17), matrix: IDENTITY, smoothing: true, repeating: false },
+        };
+        let bounds = RectI { x: 0, y: 0, w: 4, h: 4 };
+        caches.shapes.lock().unwrap().insert_meshes(0x5A9E, 1, bounds, vec![mesh], false, true, Vec::new(), false, false, false);
+        let surface = BitmapSurface {
+            width: 2,
+            height: 1,
+            rgba: vec![1, 2, 3, 255, 4, 5, 6, 255],
+            is_opaque: true,
+            alpha_mode: AlphaMode::Straight,
+            source_width: 2,
+            source_height: 1,
+        };
+        caches.bitmaps.lock().unwrap().insert(0xB17, surface);
+
+        let mut packet = FramePacket::new();
+        packet.cmds.push(draw(0x5A9E));
+        packet.cmds.push(draw(0xDEAD));
+        let path = std::env::temp_dir().join(format!("cmd_trace_test_{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut recorder = CmdRecorder::create(path).unwrap();
+        recorder.record(&packet, &caches);
+        recorder.record(&packet, &caches);
+        recorder.finish();
+
+        let text = std::fs::read_to_string(path).unwrap();
+        // Defined once: the second frame reuses the first frame's definitions.
+        assert_eq!(text.lines().filter(|l| l.starts_with("shape_def ")).count(), 2);
+        assert_eq!(text.lines().filter(|l| l.starts_with("bitmap_def ")).count(), 1);
+
+        let mut replay = CmdReplay::open(path).unwrap();
+        let mut out = FramePacket::new();
+        assert!(replay.next_packet(&mut out).unwrap());
+        assert_eq!(out.cmds.len(), 2);
+        {
+            let shapes = replay.caches().shapes.lock().unwrap();
+            let fill = shapes.get_fill_mesh(0x5A9E, 0).unwrap();
+            assert_eq!(fill.indices, vec![0, 1, 2]);
+            assert!(matches!(fill.paint, FillPaint::Bitmap { bitmap_id: 3, bitmap_key: Some(0xB17), smoothing: true, .. }));
+            assert!(shapes.is_tess_partial(0x5A9E));
+            assert!(!shapes.is_tess_failed(0x5A9E));
+            assert_eq!(shapes.fill_count(0xDEAD), 0);
+            let bitmaps = replay.caches().bitmaps.lock().unwrap();
+            assert_eq!(bitmaps.get(0xB17).unwrap().rgba, vec![1, 2, 3, 255, 4, 5, 6, 255]);
+        }
+        assert!(replay.next_packet(&mut out).unwrap());
+        assert!(!replay.next_packet(&mut out).unwrap());
+        let _ = std::fs::remove_file(path);
+    }
+}
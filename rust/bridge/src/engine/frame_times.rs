@@ -0,0 +1,53 @@
+//! Rolling window of tick deltas, the single source for the engine's measured frame rate.
+
+/// Ticks kept in the window (about one second at 60 fps).
+const WINDOW: usize = 60;
+
+pub struct FrameTimes {
+    dt_ms: [u32; WINDOW],
+    len: usize,
+    next: usize,
+    sum_ms: u64,
+}
+
+impl FrameTimes {
+    pub fn new() -> Self {
+        Self { dt_ms: [0; WINDOW], len: 0, next: 0, sum_ms: 0 }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Record one tick delta; zero deltas (no measurement) are ignored.
+    pub fn push(&mut self, dt_ms: u32) {
+        if dt_ms == 0 {
+            return;
+        }
+        if self.len == WINDOW {
+            self.sum_ms -= self.dt_ms[self.next] as u64;
+        } else {
+            self.len += 1;
+        }
+        self.dt_ms[self.next] = dt_ms;
+        self.sum_ms += dt_ms as u64;
+        self.next = (self.next + 1) % WINDOW;
+    }
+
+    /// Average frames per second over the window (0 before any tick).
+    pub fn fps(&self) -> f32 {
+        if self.sum_ms == 0 {
+            return 0.0;
+        }
+        self.len as f32 * 1000.0 / self.sum_ms as f32
+    }
+
+    /// `(min, max)` instantaneous frame rate in the window: the slowest and fastest tick.
+    pub fn fps_range(&self) -> (f32, f32) {
+        let window = &self.dt_ms[..self.len];
+        match (window.iter().max(), window.iter().min()) {
+            (Some(&slowest), Some(&fastest)) => (1000.0 / slowest as f32, 1000.0 / fastest as f32),
+            _ => (0.0, 0.0),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Input trace capture/replay for reproducible bug reports.
+//!
+//! Trace format (text, one event per line, `#` comments allowed):
+//! - `F <frame> <dt_ms>`: frame tick with its delta (replayed as-is for deterministic timing)
+//! - `M <frame> <x> <y>`: mouse move (screen pixels)
+//! - `B <frame> <button> <0|1>`: mouse button up/down
+//! - `K <frame> <keycode> <0|1>`: key up/down
+//! - `W <frame> <delta>`: mouse wheel (lines, positive scrolls up)
+//!
+//! `<frame>` is the engine frame counter at the time the event arrived; replay feeds an
+//! event right before the tick that follows it, same as live input.
+//!
+//! `bridge_engine_inject_event` takes the same lines minus `<frame>` (see `parse_event`).
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+#[derive(Clone, Copy, Debug)]
+pub enum TraceEvent {
+    Frame { dt_ms: u32 },
+    MouseMove { x: i32, y: i32 },
+    MouseButton { button: i32, down: bool },
+    Key { keycode: i32, down: bool },
+    Wheel { delta: i32 },
+}
+
+pub struct InputRecorder {
+    out: BufWriter<File>,
+    path: String,
+    events: u32,
+}
+
+impl InputRecorder {
+    pub fn create(path: &str) -> Result<Self, String> {
+        let file = File::create(path).map_err(|e| format!("input trace create failed: {} ({})", path, e))?;
+        let mut out = BufWriter::new(file);
+        let _ = writeln!(out, "# ruffle3ds input trace v1");
+        Ok(Self { out, path: path.to_string(), events: 0 })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn events(&self) -> u32 {
+        self.events
+    }
+
+    pub fn record(&mut self, frame: u64, event: TraceEvent) {
+        let _ = match event {
+            TraceEvent::Frame { dt_ms } => writeln!(self.out, "F {} {}", frame, dt_ms),
+            TraceEvent::MouseMove { x, y } => writeln!(self.out, "M {} {} {}", frame, x, y),
+            TraceEvent::MouseButton { button, down } => writeln!(self.out, "B {} {} {}", frame, button, down as u8),
+            TraceEvent::Key { keycode, down } => writeln!(self.out, "K {} {} {}", frame, keycode, down as u8),
+            TraceEvent::Wheel { delta } => writeln!(self.out, "W {} {}", frame, delta),
+        };
+        if !matches!(event, TraceEvent::Frame { .. }) {
+            self.events = self.events.saturating_add(1);
+        }
+    }
+
+    pub fn finish(mut self) {
+        let _ = self.out.flush();
+    }
+}
+
+pub struct InputReplay {
+    entries: Vec<(u64, TraceEvent)>,
+    pos: usize,
+    path: String,
+}
+
+impl InputReplay {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("input trace open failed: {} ({})", path, e))?;
+        let mut entries = Vec::new();
+        for (line_no, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| format!("input trace read failed: {} ({})", path, e))?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = parse_line(line).ok_or_else(|| format!("input trace parse error line={} '{}'", line_no + 1, line))?;
+            entries.push(entry);
+        }
+        Ok(Self { entries, pos: 0, path: path.to_string() })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pos >= self.entries.len()
+    }
+
+    /// Pop the next input event recorded before `frame`.
+    ///
+    /// Frame markers are skipped here; use `take_frame_dt` for timing.
+    pub fn next_input_before(&mut self, frame: u64) -> Option<TraceEvent> {
+        while let Some(&(f, event)) = self.entries.get(self.pos) {
+            if f >= frame {
+                return None;
+            }
+            self.pos += 1;
+            if !matches!(event, TraceEvent::Frame { .. }) {
+                return Some(event);
+            }
+        }
+        None
+    }
+
+    /// Recorded delta for `frame`, if the trace has one at the cursor.
+    pub fn take_frame_dt(&mut self, frame: u64) -> Option<u32> {
+        match self.entries.get(self.pos) {
+            Some(&(f, TraceEvent::Frame { dt_ms })) if f == frame => {
+                self.pos += 1;
+                Some(dt_ms)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<(u64, TraceEvent)> {
+    let mut parts = line.split_whitespace();
+    let tag = parts.next()?;
+    let frame: u64 = parts.next()?.parse().ok()?;
+    let event = parse_fields(tag, parts)?;
+    Some((frame, event))
+}
+
+/// Parse an injected event: a trace line without its frame field (`M 120 80`, `B 0 1`,
+/// `K 32 0`, `W -1`). Frame markers are rejected; timing comes from the live tick.
+pub fn parse_event(text: &str) -> Option<TraceEvent> {
+    let mut parts = text.split_whitespace();
+    let tag = parts.next()?;
+    if tag == "F" {
+        return None;
+    }
+    parse_fields(tag, parts)
+}
+
+fn parse_fields<'a>(tag: &str, mut parts: impl Iterator<Item = &'a str>) -> Option<TraceEvent> {
+    let a: i32 = parts.next()?.parse().ok()?;
+    let b: Option<i32> = parts.next().and_then(|v| v.parse().ok());
+    let event = match tag {
+        "F" => TraceEvent::Frame { dt_ms: a.max(0) as u32 },
+        "M" => TraceEvent::MouseMove { x: a, y: b? },
+        "B" => TraceEvent::MouseButton { button: a, down: b? != 0 },
+        "K" => TraceEvent::Key { keycode: a, down: b? != 0 },
+        "W" => TraceEvent::Wheel { delta: a },
+        _ => return None,
+    };
+    Some(event)
+}
@@ -1,7 +1,15 @@
+mod cmd_trace;
+mod frame_times;
+mod input_trace;
+
+use core::ffi::{c_char, c_void};
+#[cfg(feature = "net")]
+use std::ffi::CString;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 use ruffle_core::{Player, PlayerBuilder, PlayerEvent};
-use ruffle_core::events::{KeyDescriptor, KeyLocation, LogicalKey, MouseButton, NamedKey, PhysicalKey};
+use ruffle_core::events::{KeyDescriptor, KeyLocation, LogicalKey, MouseButton, MouseWheelDelta, NamedKey, PhysicalKey};
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::Color;
 
@@ -10,11 +18,42 @@ use ruffle_core::backend::audio::NullAudioBackend;
 use ruffle_video::null::NullVideoBackend;
 
 use crate::ffi::fileio::read_file_bytes;
-use crate::ruffle_adapter::ThreeDSBackend;
+#[cfg(feature = "net")]
+use crate::ffi::types::NavigateCallback;
+use crate::ffi::types::TextInputCallback;
+use crate::ruffle_adapter::{DiagCounters, ThreeDSBackend};
+#[cfg(feature = "audio")]
+use crate::ruffle_adapter::DspAudioBackend;
 use crate::render::{FramePacket, RenderCmd, Renderer, SharedCaches};
 #[cfg(debug_assertions)]
 use crate::render::Matrix2D;
 use crate::runlog;
+use crate::util::config::{self, ButtonAction, PadMode};
+use crate::util::png;
+use crate::util::text;
+use cmd_trace::{CmdRecorder, CmdReplay};
+use frame_times::FrameTimes;
+use input_trace::{InputRecorder, InputReplay, TraceEvent};
+
+/// Buffer handed to the host text-entry callback (swkbd allows 256 UTF-16 units).
+const TEXT_INPUT_MAX_BYTES: usize = 1024;
+
+/// Playback speed limits for `set_speed`.
+const SPEED_MIN: f32 = 0.1;
+const SPEED_MAX: f32 = 8.0;
+
+/// Bottom-screen touch panel size in pixels.
+const TOUCH_W: i32 = 320;
+const TOUCH_H: i32 = 240;
+
+/// Circle pad: raw units at full tilt, dead zone, cursor speed (px/frame at full tilt),
+/// and the tilt (0..1) that holds an arrow key in `PadMode::Keys`.
+const PAD_RANGE: f32 = 156.0;
+const PAD_DEAD_ZONE: f32 = 20.0;
+const PAD_CURSOR_SPEED: f32 = 5.0;
+const PAD_KEY_THRESHOLD: f32 = 0.5;
+/// Arrow keycodes in `pad_keys` order: left, up, right, down.
+const PAD_KEYCODES: [i32; 4] = [37, 38, 39, 40];
 
 /// High-level engine state, owned by the C-side handle.
 ///
@@ -28,11 +67,92 @@ pub struct Engine {
     root_path: String,
     #[allow(dead_code)]
     root_file_url: String,
+    /// Playlist mode (empty when playing a single SWF).
+    playlist: Vec<String>,
+    playlist_index: usize,
+    playlist_timeout_ms: u32,
+    /// Header facts about the root movie, captured at load.
+    movie_info: MovieInfo,
+    /// Error mode: the root movie failed to load, so this message is drawn instead of ticking.
+    error_message: Option<String>,
+    movie_elapsed_ms: u32,
+    movie_last_frame: u16,
+    movie_at_end_ms: u32,
     frame_counter: u64,
     last_heartbeat_ms: u64,
     pending_snapshot: Option<String>,
+    /// Last pointer position in screen pixels (as sent by C).
     mouse_x: i32,
     mouse_y: i32,
+    /// Touchscreen is pressed (left button held via `touch`).
+    touch_down: bool,
+    /// Circle pad mapping, sub-pixel cursor carry, and arrow keys held in `Keys` mode
+    /// (left, up, right, down).
+    pad_mode: PadMode,
+    pad_carry_x: f32,
+    pad_carry_y: f32,
+    pad_keys: [bool; 4],
+    /// Paused by the host: input and rendering continue, the player doesn't tick.
+    paused: bool,
+    /// Host-forced clear color; `None` uses the movie's stage color.
+    background_override: Option<Color>,
+    /// Playback speed multiplier applied to the tick delta (1.0 = real time).
+    speed: f32,
+    /// `step_frame` requested one frame; consumed by the next `tick_and_render`.
+    step_pending: bool,
+    /// Keys currently held, for `key_repeat`.
+    held_keys: Vec<HeldKey>,
+    screen_w: u32,
+    screen_h: u32,
+    /// Input trace capture/replay (never both at once).
+    input_recorder: Option<InputRecorder>,
+    input_replay: Option<InputReplay>,
+    /// `frame_counter` when the current trace started; trace frames are relative to it.
+    trace_frame_base: u64,
+    /// Render command trace capture/replay (never both at once); a replay stands in for the player.
+    cmd_recorder: Option<CmdRecorder>,
+    cmd_replay: Option<CmdReplay>,
+    /// Recent tick deltas as passed in by C, for `fps` and the stats overlay.
+    frame_times: FrameTimes,
+    /// Adaptive frame-skip: consecutive slow rendered frames and renders skipped in a row.
+    slow_frames: u32,
+    skipped_in_row: u32,
+    last_tick_rendered: bool,
+    /// Master volume in `[0.0, 1.0]` and mute flag; applied together through `Player::set_volume`.
+    master_volume: f32,
+    muted: bool,
+    /// Host text-entry dialog (swkbd), shown when a text field asks for the keyboard.
+    text_input_cb: TextInputCallback,
+    text_input_user: *mut c_void,
+    /// Host callbacks for `getURL` (plain URLs) and `fscommand:`/`javascript:` URLs.
+    #[cfg(feature = "net")]
+    navigate_cb: HostCallback,
+    #[cfg(feature = "net")]
+    fscommand_cb: HostCallback,
+}
+
+/// A C callback plus the user pointer it was registered with.
+#[cfg(feature = "net")]
+#[derive(Clone, Copy)]
+struct HostCallback {
+    func: NavigateCallback,
+    user: *mut c_void,
+}
+
+#[cfg(feature = "net")]
+impl HostCallback {
+    const NONE: Self = Self { func: None, user: core::ptr::null_mut() };
+
+    fn call(&self, a: &str, b: &str) -> bool {
+        let Some(func) = self.func else {
+            return false;
+        };
+        let (Ok(a), Ok(b)) = (CString::new(a), CString::new(b)) else {
+            return false;
+        };
+        unsafe { func(self.user, a.as_ptr(), b.as_ptr()) };
+        true
+    }
 }
 
 impl Engine {
@@ -43,73 +163,30 @@ impl Engine {
         runlog::init_for_swf(&root_path);
         runlog::log_important(&format!("Engine::new begin root_path={}", root_path));
 
-        let movie_bytes = read_file_bytes(&root_path)
-            .ok_or_else(|| format!("Could not read file: {}", root_path))?;
-        runlog::log_important("Engine::new read_file ok");
-
         // Shared CPU-side caches (bitmaps now, shapes/mesh later).
         let caches = SharedCaches::new();
 
         // Backend shared between renderer/navigator/ui/log/storage.
-        let backend = ThreeDSBackend::new(caches.clone());
-
-        runlog::log_important("init: player_builder");
-        let mut builder = PlayerBuilder::new()
-            .with_viewport_dimensions(screen_w, screen_h, 1.0);
-        runlog::log_important("init: renderer backend");
-        builder = builder.with_renderer(backend.clone());
-        runlog::log_important("init: audio backend");
-        builder = builder.with_audio(NullAudioBackend::new());
-        #[cfg(feature = "net")]
-        {
-            runlog::log_important("init: navigator backend");
-            builder = builder.with_navigator(backend.clone());
-        }
-        #[cfg(not(feature = "net"))]
-        {
-            runlog::log_important("init: navigator backend disabled");
-        }
-        #[cfg(feature = "storage")]
-        {
-            runlog::log_important("init: storage backend");
-            builder = builder.with_storage(Box::new(backend.clone()));
-        }
-        #[cfg(not(feature = "storage"))]
-        {
-            runlog::log_important("init: storage backend disabled");
-        }
-        #[cfg(feature = "video")]
-        {
-            runlog::log_important("init: video backend");
-            builder = builder.with_video(NullVideoBackend::new());
-        }
-        runlog::log_important("init: log backend");
-        builder = builder.with_log(backend.clone());
-        runlog::log_important("init: ui backend");
-        let player = builder.with_ui(backend.clone()).build();
-
-        // Load SWF.
-        match SwfMovie::from_data(&movie_bytes, root_file_url.clone(), None) {
-            Ok(movie) => {
-                if movie.is_action_script_3() {
-                    let msg = "AS3 not supported yet (AS2 only).";
-                    backend.set_fatal_error(msg.to_string());
-                    runlog::warn_line(msg);
-                    return Err(msg.to_string());
-                }
-                backend.mark_movie_loaded(movie.version());
-                runlog::log_important(&format!("Engine::new SwfMovie ok version={}", movie.version()));
-                player.lock().unwrap().mutate_with_update_context(|uc| {
-                    uc.set_root_movie(movie);
-                });
-                player.lock().unwrap().set_is_playing(true);
-            }
-            Err(e) => {
-                backend.set_fatal_error(format!("Ruffle refused SWF: {e:?}"));
-                runlog::warn_line(&format!("fatal: Ruffle refused SWF: {e:?}"));
-                return Err(format!("Ruffle refused SWF: {e:?}"));
+        let backend = ThreeDSBackend::new(caches.clone(), screen_w, screen_h);
+
+        let player = build_player(&backend, screen_w, screen_h);
+
+        // Load SWF. Failures don't fail construction; the engine shows the message on screen.
+        let loaded = read_file_bytes(&root_path)
+            .ok_or_else(|| format!("Could not read file: {}", root_path))
+            .and_then(|movie_bytes| {
+                runlog::log_important("Engine::new read_file ok");
+                let movie = parse_root_movie(&movie_bytes, &root_file_url)?;
+                Ok(start_root_movie(&player, &backend, movie, &movie_bytes, &root_file_url))
+            });
+        let (movie_info, error_message) = match loaded {
+            Ok(info) => (info, None),
+            Err(err) => {
+                backend.set_fatal_error(err.clone());
+                runlog::warn_line(&format!("Engine::new error mode: {}", err));
+                (MovieInfo::default(), Some(err))
             }
-        }
+        };
 
         Ok(Self {
             player,
@@ -123,15 +200,227 @@ impl Engine {
             pending_snapshot: None,
             mouse_x: 0,
             mouse_y: 0,
+            touch_down: false,
+            pad_mode: config::pad_mode(),
+            pad_carry_x: 0.0,
+            pad_carry_y: 0.0,
+            pad_keys: [false; 4],
+            held_keys: Vec::new(),
+            paused: false,
+            step_pending: false,
+            speed: 1.0,
+            background_override: None,
+            screen_w: screen_w.max(1),
+            screen_h: screen_h.max(1),
+            input_recorder: None,
+            input_replay: None,
+            trace_frame_base: 0,
+            cmd_recorder: None,
+            cmd_replay: None,
+            frame_times: FrameTimes::new(),
+            slow_frames: 0,
+            skipped_in_row: 0,
+            last_tick_rendered: true,
+            master_volume: 1.0,
+            muted: false,
+            text_input_cb: None,
+            text_input_user: core::ptr::null_mut(),
+            #[cfg(feature = "net")]
+            navigate_cb: HostCallback::NONE,
+            #[cfg(feature = "net")]
+            fscommand_cb: HostCallback::NONE,
+            playlist: Vec::new(),
+            playlist_index: 0,
+            playlist_timeout_ms: 0,
+            movie_info,
+            error_message,
+            movie_elapsed_ms: 0,
+            movie_last_frame: 0,
+            movie_at_end_ms: 0,
         })
     }
 
+    /// Create an engine that plays `paths` in order, looping at the end.
+    ///
+    /// Advances when the root timeline reaches its last frame (then wraps or holds there), or
+    /// after `timeout_ms` of playback when non-zero.
+    pub fn new_playlist(paths: Vec<String>, screen_w: u32, screen_h: u32, timeout_ms: u32) -> Result<Self, String> {
+        let first = paths.first().cloned().ok_or_else(|| "Empty playlist".to_string())?;
+        let mut engine = Self::new(&first, screen_w, screen_h)?;
+        runlog::log_important(&format!("playlist entries={} timeout_ms={}", paths.len(), timeout_ms));
+        engine.playlist = paths;
+        engine.playlist_timeout_ms = timeout_ms;
+        if let Some(err) = engine.error_message.clone() {
+            // A bad first entry shouldn't strand the whole playlist on the error screen.
+            runlog::warn_line(&format!("playlist skip index=0 err={}", err));
+            engine.playlist_next();
+        }
+        Ok(engine)
+    }
+
+    /// Replace the root movie in the running player (no player/backend rebuild). Like `reload`,
+    /// the shape/bitmap caches and held input are cleared so nothing from the old movie leaks in.
+    ///
+    /// An unreadable or refused movie returns the error with the current movie still playing.
+    pub fn load_movie(&mut self, path: &str) -> Result<(), String> {
+        runlog::log_important(&format!("load_movie begin path={}", path));
+        let movie_bytes = read_file_bytes(path).ok_or_else(|| format!("Could not read file: {}", path))?;
+        let file_url = format!("file:///{}", path);
+        let movie = parse_root_movie(&movie_bytes, &file_url)?;
+        self.backend.save_shape_cache();
+        self.backend.reset_for_reload();
+        self.movie_info = start_root_movie(&self.player, &self.backend, movie, &movie_bytes, &file_url);
+        self.root_path = path.to_string();
+        self.root_file_url = file_url;
+        self.error_message = None;
+        self.movie_elapsed_ms = 0;
+        self.movie_last_frame = 0;
+        self.movie_at_end_ms = 0;
+        // A freshly loaded movie starts playing.
+        self.paused = false;
+        self.step_pending = false;
+        self.held_keys.clear();
+        self.touch_down = false;
+        self.pad_keys = [false; 4];
+        self.frame_times.clear();
+        Ok(())
+    }
+
+    /// Restart the current movie from `root_path` with a fresh player, keeping the handle and
+    /// run-log bundle. Shape/bitmap caches and diagnostics are cleared so no stale keys survive.
+    ///
+    /// The file is re-read and parsed before the old player is torn down; if that fails the
+    /// error is returned with the current player untouched.
+    pub fn reload(&mut self) -> Result<(), String> {
+        runlog::log_important(&format!("reload begin path={}", self.root_path));
+        let movie_bytes = read_file_bytes(&self.root_path)
+            .ok_or_else(|| format!("Could not read file: {}", self.root_path))?;
+        let movie = parse_root_movie(&movie_bytes, &self.root_file_url)?;
+        self.backend.save_shape_cache();
+
+        // Pause the old player's audio so its mixer is idle while the new one opens the DSP.
+        self.player.lock().unwrap().set_is_playing(false);
+        self.player = build_player(&self.backend, self.screen_w, self.screen_h);
+        self.backend.reset_for_reload();
+
+        self.movie_info = start_root_movie(&self.player, &self.backend, movie, &movie_bytes, &self.root_file_url);
+        self.error_message = None;
+        self.movie_elapsed_ms = 0;
+        self.movie_last_frame = 0;
+        self.movie_at_end_ms = 0;
+        self.paused = false;
+        self.step_pending = false;
+        self.held_keys.clear();
+        self.touch_down = false;
+        self.pad_keys = [false; 4];
+        self.slow_frames = 0;
+        self.skipped_in_row = 0;
+        // The reload stall would otherwise sit in the window as one very slow frame.
+        self.frame_times.clear();
+        self.last_tick_rendered = true;
+        self.apply_volume();
+        runlog::log_important("reload ok");
+        Ok(())
+    }
+
+    /// Switch to the next playlist entry (wrapping). Returns false if there is no playlist.
+    pub fn playlist_next(&mut self) -> bool {
+        self.playlist_step(1)
+    }
+
+    /// Switch to the previous playlist entry (wrapping). Returns false if there is no playlist.
+    pub fn playlist_prev(&mut self) -> bool {
+        self.playlist_step(-1)
+    }
+
+    fn playlist_step(&mut self, delta: isize) -> bool {
+        let len = self.playlist.len();
+        if len == 0 {
+            return false;
+        }
+        // Skip unreadable/refused entries, but give up after one full lap.
+        for _ in 0..len {
+            let next = (self.playlist_index as isize + delta).rem_euclid(len as isize) as usize;
+            self.playlist_index = next;
+            let path = self.playlist[next].clone();
+            match self.load_movie(&path) {
+                Ok(()) => {
+                    runlog::log_important(&format!("playlist index={} path={}", next, path));
+                    return true;
+                }
+                Err(err) => runlog::warn_line(&format!("playlist skip index={} err={}", next, err)),
+            }
+        }
+        false
+    }
+
+    fn update_playlist(&mut self, dt_ms: u32) {
+        if self.playlist.len() < 2 {
+            return;
+        }
+        self.movie_elapsed_ms = self.movie_elapsed_ms.saturating_add(dt_ms);
+        if self.playlist_timeout_ms > 0 && self.movie_elapsed_ms >= self.playlist_timeout_ms {
+            self.playlist_next();
+            return;
+        }
+        if self.movie_info.num_frames <= 1 {
+            return;
+        }
+        let frame = self.player.lock().unwrap().current_frame().unwrap_or(0);
+        let wrapped = frame < self.movie_last_frame && self.movie_last_frame >= self.movie_info.num_frames;
+        if frame >= self.movie_info.num_frames {
+            self.movie_at_end_ms = self.movie_at_end_ms.saturating_add(dt_ms);
+        } else {
+            self.movie_at_end_ms = 0;
+        }
+        self.movie_last_frame = frame;
+        if wrapped || self.movie_at_end_ms >= PLAYLIST_END_HOLD_MS {
+            self.playlist_next();
+        }
+    }
+
+    /// Error mode with a playlist: show the message for `PLAYLIST_ERROR_HOLD_MS` (or the playlist
+    /// timeout, if shorter), then move on. The next entry gets a fresh player since the old one
+    /// may have been left mid-frame by a panic.
+    fn update_error_playlist(&mut self, dt_ms: u32) {
+        if self.playlist.len() < 2 {
+            return;
+        }
+        self.movie_elapsed_ms = self.movie_elapsed_ms.saturating_add(dt_ms);
+        let hold_ms = match self.playlist_timeout_ms {
+            0 => PLAYLIST_ERROR_HOLD_MS,
+            timeout => timeout.min(PLAYLIST_ERROR_HOLD_MS),
+        };
+        if self.movie_elapsed_ms < hold_ms {
+            return;
+        }
+        self.player.lock().unwrap().set_is_playing(false);
+        self.player = build_player(&self.backend, self.screen_w, self.screen_h);
+        self.apply_volume();
+        if !self.playlist_next() {
+            // Every entry failed; try another lap after the next hold.
+            self.movie_elapsed_ms = 0;
+        }
+    }
+
     /// Tick Ruffle and render the latest submitted frame to the top framebuffer.
     ///
     /// This keeps the existing C-side loop unchanged: C calls `bridge_tick`, then swaps buffers.
-    pub fn tick_and_render(&mut self, dt_ms: u32) {
+    /// Returns false when frame-skip dropped the render (the framebuffer was not touched).
+    pub fn tick_and_render(&mut self, dt_ms: u32) -> bool {
         self.frame_counter = self.frame_counter.wrapping_add(1);
+        // Measure the real delta, before a replayed trace substitutes its recorded one.
+        self.frame_times.push(dt_ms);
+        let dt_ms = self.apply_input_trace(dt_ms);
         runlog::tick();
+        if self.cmd_replay.is_some() && self.replay_cmd_frame() {
+            return true;
+        }
+        if self.error_message.is_some() {
+            self.render_error_screen();
+            self.update_error_playlist(dt_ms);
+            return true;
+        }
         if runlog::is_verbose() {
             let now = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -148,20 +437,45 @@ impl Engine {
                 ));
             }
         }
+        if !self.paused {
+            self.update_playlist(dt_ms);
+            self.update_key_repeat(dt_ms);
+        }
+
         // Poll any async-ish tasks queued by Ruffle backends.
         self.backend.poll_tasks();
 
         // Tick using the provided delta (fallback to ~60Hz).
-        {
+        let tick_start = Instant::now();
+        if !self.paused {
             let mut player = self.player.lock().unwrap();
             let dt = if dt_ms == 0 { 1.0 / 60.0 } else { (dt_ms as f64) / 1000.0 };
-            player.tick(dt);
+            player.tick(dt * self.speed as f64);
+        } else if self.step_pending {
+            self.step_pending = false;
+            let mut player = self.player.lock().unwrap();
+            player.run_frame();
+            runlog::log_line(&format!("step_frame -> frame {}", player.current_frame().unwrap_or(0)));
+        }
+        let tick_us = elapsed_us(tick_start);
+        #[cfg(feature = "net")]
+        self.dispatch_navigations();
+        self.run_text_input();
+
+        if !self.paused && self.should_skip_render(dt_ms) {
+            self.backend.record_render_skip();
+            runlog::stage("render_skipped", self.frame_counter);
+            self.log_frame_times(dt_ms, tick_us, 0);
+            return false;
         }
 
         // Determine desired clear color.
-        let clear = {
-            let mut player = self.player.lock().unwrap();
-            player.background_color().unwrap_or(Color { r: 0, g: 0, b: 0, a: 255 })
+        let clear = match self.background_override {
+            Some(color) => color,
+            None => {
+                let mut player = self.player.lock().unwrap();
+                player.background_color().unwrap_or(Color { r: 0, g: 0, b: 0, a: 255 })
+            }
         };
 
         self.backend.begin_frame();
@@ -172,6 +486,7 @@ impl Engine {
         }
 
         // Trigger Ruffle rendering; this will call our backend hooks.
+        let render_start = Instant::now();
         {
             runlog::stage("player.render", self.frame_counter);
             let mut player = self.player.lock().unwrap();
@@ -183,7 +498,8 @@ impl Engine {
 
         // Loading indicator until we see actual draw commands.
         if !self.backend.has_seen_real_draw() {
-            self.scratch_packet.cmds.push(RenderCmd::DebugLoadingIndicator);
+            let percent = self.load_progress();
+            self.scratch_packet.cmds.push(RenderCmd::DebugLoadingIndicator { percent });
         }
 
         #[cfg(debug_assertions)]
@@ -198,9 +514,94 @@ impl Engine {
             self.scratch_packet.cmds.push(RenderCmd::DebugAffineRect { transform: shear, r: 220, g: 200, b: 80 });
         }
 
+        if self.backend.stats_overlay_enabled() {
+            let fps_x10 = (self.frame_times.fps() * 10.0).round() as u32;
+            let text = self.backend.stats_overlay_text(fps_x10);
+            self.scratch_packet.cmds.push(RenderCmd::DebugStatsOverlay { text });
+        }
+
+        if let Some(recorder) = self.cmd_recorder.as_mut() {
+            recorder.record(&self.scratch_packet, self.backend.caches());
+        }
         runlog::stage("renderer.render", self.frame_counter);
         self.renderer.render(&self.scratch_packet);
         runlog::stage("present", self.frame_counter);
+        self.log_frame_times(dt_ms, tick_us, elapsed_us(render_start));
+        true
+    }
+
+    /// Error mode frame: the player isn't ticked; the fatal message is drawn on a dark background.
+    fn render_error_screen(&mut self) {
+        let Some(msg) = self.error_message.as_deref() else {
+            return;
+        };
+        let text = format!("Movie error\n{}\n\n{}", self.root_path, msg);
+        self.scratch_packet.reset(Color { r: 48, g: 0, b: 0, a: 255 });
+        self.scratch_packet.cmds.push(RenderCmd::DebugErrorScreen { text });
+        runlog::stage("renderer.render", self.frame_counter);
+        self.renderer.render(&self.scratch_packet);
+        runlog::stage("present", self.frame_counter);
+    }
+
+    /// Percent of the root movie's bytes the preloader has processed (`None` when it can't be
+    /// tracked, e.g. a movie without shapes).
+    pub fn load_progress(&self) -> Option<u8> {
+        self.backend.load_progress()
+    }
+
+    /// Switch to error mode after a caught panic: unpoison the locks a panic may have left
+    /// behind, record `msg` as the fatal error and stop ticking the player until a reload.
+    pub fn enter_error_mode(&mut self, msg: String) {
+        self.player.clear_poison();
+        self.backend.clear_poison();
+        self.backend.set_fatal_error(msg.clone());
+        runlog::warn_line(&format!("fatal: {}", msg));
+        self.error_message = Some(msg);
+    }
+
+    /// Fatal error while in error mode (see `Engine::new` and `enter_error_mode`).
+    pub fn error_message(&self) -> Option<&str> {
+        self.error_message.as_deref()
+    }
+
+    /// One `frame_times.csv` row (`frame_times_csv` config).
+    fn log_frame_times(&self, dt_ms: u32, tick_us: u32, render_us: u32) {
+        if config::frame_times_csv_enabled() {
+            let tris = self.backend.diag_counters().last_tris;
+            runlog::frame_times_row(self.frame_counter, dt_ms, tick_us, render_us, tris);
+        }
+    }
+
+    /// Adaptive frame-skip policy (`frame_skip` config).
+    ///
+    /// `dt_ms` measures the previous tick, so only ticks that followed a render count towards
+    /// the slow streak. After `FRAME_SKIP_SLOW_STREAK` slow renders, up to `frame_skip_max`
+    /// renders are dropped in a row; player logic and input keep running every tick.
+    fn should_skip_render(&mut self, dt_ms: u32) -> bool {
+        let max_skip = config::frame_skip_max();
+        if !config::frame_skip_enabled() || max_skip == 0 || !self.backend.has_seen_real_draw() {
+            self.slow_frames = 0;
+            self.skipped_in_row = 0;
+            self.last_tick_rendered = true;
+            return false;
+        }
+        if self.last_tick_rendered {
+            let frame_rate = self.player.lock().unwrap().frame_rate();
+            let target_ms = if frame_rate > 0.0 { 1000.0 / frame_rate } else { 1000.0 / 30.0 };
+            if dt_ms as f64 > target_ms * 1.25 {
+                self.slow_frames = self.slow_frames.saturating_add(1);
+            } else {
+                self.slow_frames = 0;
+            }
+        }
+        let skip = self.slow_frames >= FRAME_SKIP_SLOW_STREAK && self.skipped_in_row < max_skip;
+        if skip {
+            self.skipped_in_row += 1;
+        } else {
+            self.skipped_in_row = 0;
+        }
+        self.last_tick_rendered = !skip;
+        skip
     }
 
     /// Append a short status snapshot to the SD run bundle.
@@ -210,8 +611,43 @@ impl Engine {
         }
     }
 
+    /// Write diagnostics, cache stats and draw stats as JSON to `path` (for tooling).
+    pub fn dump_diagnostics_json(&self, path: &str) -> Result<(), String> {
+        let json = self.backend.diagnostics_json(self.frame_counter);
+        std::fs::write(path, json).map_err(|e| format!("diagnostics json write failed: {} ({})", path, e))?;
+        runlog::log_line(&format!("diagnostics json -> {}", path));
+        Ok(())
+    }
+
+    /// Write the top framebuffer as a PNG and return the path written.
+    ///
+    /// A bare file name (or `""` for `screenshot_<frame>.png`) lands in the run bundle; anything
+    /// with a directory is used as-is. Call between `tick` and the launcher's buffer swap so the
+    /// framebuffer still holds the frame just rendered.
+    pub fn capture_screenshot(&self, path: &str) -> Result<String, String> {
+        let path = if path.contains('/') {
+            path.to_string()
+        } else {
+            let dir = runlog::run_dir().unwrap_or_else(|| "sdmc:/flash/_runs".to_string());
+            let name = if path.is_empty() { format!("screenshot_{}.png", self.frame_counter) } else { path.to_string() };
+            format!("{}/{}", dir, name)
+        };
+        let mut rgba = Vec::new();
+        let (w, h) = self
+            .renderer
+            .read_pixels(&mut rgba)
+            .ok_or_else(|| "screenshot failed: no framebuffer".to_string())?;
+        let png = png::encode_rgba(w, h, &rgba).ok_or_else(|| "screenshot failed: bad pixel buffer".to_string())?;
+        std::fs::write(&path, png).map_err(|e| format!("screenshot write failed: {} ({})", path, e))?;
+        runlog::log_important(&format!("screenshot {}x{} -> {}", w, h, path));
+        Ok(path)
+    }
+
     /// Graceful shutdown hook (flush run bundle files).
     pub fn shutdown(&mut self) {
+        self.stop_input_recording();
+        self.stop_cmd_recording();
+        self.backend.save_shape_cache();
         runlog::log_line("Engine shutdown");
         runlog::shutdown();
     }
@@ -232,44 +668,535 @@ impl Engine {
         self.backend.toggle_debug_affine_overlay()
     }
 
+    /// Outline each drawn shape's transformed bounds; returns the new state.
+    pub fn toggle_bounds_overlay(&mut self) -> bool {
+        self.backend.toggle_bounds_overlay()
+    }
+
+    /// Tint each drawn shape green→red by triangle count; returns the new state.
+    pub fn toggle_tri_heatmap(&mut self) -> bool {
+        self.backend.toggle_tri_heatmap()
+    }
+
+    /// Whether content currently wants the virtual keyboard shown.
+    pub fn virtual_keyboard_open(&self) -> bool {
+        self.backend.virtual_keyboard_open()
+    }
+
+    pub fn virtual_keyboard_dismissed(&mut self) {
+        self.backend.virtual_keyboard_dismissed();
+    }
+
+    pub fn toggle_stats_overlay(&mut self) -> bool {
+        self.backend.toggle_stats_overlay()
+    }
+
+    /// Adjust the shape mesh cache budget; returns the clamped value in bytes.
+    pub fn set_shape_cache_budget(&mut self, bytes: usize) -> usize {
+        self.backend.set_shape_cache_budget(bytes)
+    }
+
+    /// Adjust the bitmap surface cache budget; returns the clamped value in bytes.
+    pub fn set_bitmap_cache_budget(&mut self, bytes: usize) -> usize {
+        self.backend.set_bitmap_cache_budget(bytes)
+    }
+
+    /// Fade the rendered movie toward black for launcher transitions (1.0 = opaque).
+    pub fn set_global_alpha(&mut self, alpha: f32) -> f32 {
+        self.renderer.set_global_alpha(alpha)
+    }
+
+    /// Stereo depth in `[0.0, 1.0]` (the 3D slider); returns the applied value.
+    pub fn set_stereo_depth(&mut self, depth: f32) -> f32 {
+        self.renderer.set_stereo_depth(depth)
+    }
+
+    /// Force the clear color behind the stage (e.g. black instead of a white stage); `None`
+    /// reverts to the movie's background color.
+    pub fn set_background_color(&mut self, color: Option<Color>) {
+        self.background_override = color;
+    }
+
+    pub fn movie_info(&self) -> MovieInfo {
+        self.movie_info
+    }
+
+    /// Numeric counters behind the status text, for launcher-drawn overlays.
+    pub fn diagnostics(&self) -> DiagCounters {
+        self.backend.diag_counters()
+    }
+
+    /// Frames per second averaged over the last ~60 ticks (0 until the first measured tick).
+    pub fn fps(&self) -> f32 {
+        self.frame_times.fps()
+    }
+
+    /// Slowest and fastest instantaneous frame rate over the same window as `fps`.
+    pub fn fps_range(&self) -> (f32, f32) {
+        self.frame_times.fps_range()
+    }
+
+    /// Ticks run since the engine was created.
+    pub fn frame_counter(&self) -> u64 {
+        self.frame_counter
+    }
+
+    /// Pause or resume playback. While paused the last frame keeps rendering and input is
+    /// still delivered (so an on-stage resume button works), but the player doesn't tick and
+    /// audio is paused.
+    pub fn set_paused(&mut self, paused: bool) {
+        if paused == self.paused {
+            return;
+        }
+        self.paused = paused;
+        self.step_pending = false;
+        self.player.lock().unwrap().set_is_playing(!paused);
+        runlog::log_important(if paused { "engine paused" } else { "engine resumed" });
+    }
+
+    /// Advance exactly one movie frame on the next tick (which then renders it). Only valid
+    /// while paused; returns false (and logs a warning) otherwise.
+    pub fn step_frame(&mut self) -> bool {
+        if !self.paused {
+            runlog::warn_line("step_frame ignored: not paused");
+            return false;
+        }
+        self.step_pending = true;
+        true
+    }
+
+    /// Playback speed multiplier (fast-forward above 1.0, slow motion below), clamped to
+    /// `[0.1, 8.0]`; returns the applied value.
+    pub fn set_speed(&mut self, speed: f32) -> f32 {
+        self.speed = if speed.is_finite() { speed.clamp(SPEED_MIN, SPEED_MAX) } else { 1.0 };
+        runlog::log_line(&format!("playback speed x{:.2}", self.speed));
+        self.speed
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Master audio volume, clamped to `[0.0, 1.0]`; returns the applied value.
+    pub fn set_master_volume(&mut self, volume: f32) -> f32 {
+        self.master_volume = if volume.is_finite() { volume.clamp(0.0, 1.0) } else { 1.0 };
+        self.apply_volume();
+        self.master_volume
+    }
+
+    /// Silence audio without losing the master volume.
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        self.apply_volume();
+    }
+
+    fn apply_volume(&mut self) {
+        let volume = if self.muted { 0.0 } else { self.master_volume };
+        self.player.lock().unwrap().set_volume(volume);
+    }
+
+    /// Register the host text-entry callback (`None` unregisters and leaves keyboard requests
+    /// to the `virtual_keyboard_open` poll).
+    pub fn set_text_input_callback(&mut self, func: TextInputCallback, user: *mut c_void) {
+        self.text_input_cb = func;
+        self.text_input_user = user;
+    }
+
+    /// If a focused text field asked for the keyboard, show the host dialog and type the
+    /// confirmed text into the player. Runs outside the player lock; the dialog blocks.
+    fn run_text_input(&mut self) {
+        let Some(func) = self.text_input_cb else {
+            return;
+        };
+        if !self.backend.virtual_keyboard_open() {
+            return;
+        }
+        let mut out = vec![0u8; TEXT_INPUT_MAX_BYTES];
+        let accepted = unsafe { func(self.text_input_user, c"".as_ptr(), out.as_mut_ptr() as *mut c_char, out.len()) } != 0;
+        self.backend.virtual_keyboard_dismissed();
+        if !accepted {
+            return;
+        }
+        out[TEXT_INPUT_MAX_BYTES - 1] = 0;
+        let len = out.iter().position(|&b| b == 0).unwrap_or(0);
+        let text = String::from_utf8_lossy(&out[..len]).into_owned();
+        runlog::log_line(&format!("text_input chars={}", text.chars().count()));
+        let mut player = self.player.lock().unwrap();
+        for codepoint in text.chars() {
+            player.handle_event(PlayerEvent::TextInput { codepoint });
+        }
+    }
+
+    /// Register the host callback for `getURL` navigations (`None` unregisters).
+    #[cfg(feature = "net")]
+    pub fn set_navigate_callback(&mut self, func: NavigateCallback, user: *mut c_void) {
+        self.navigate_cb = HostCallback { func, user };
+    }
+
+    /// Register the host callback for `fscommand:`/`javascript:` URLs (`None` unregisters).
+    #[cfg(feature = "net")]
+    pub fn set_fscommand_callback(&mut self, func: NavigateCallback, user: *mut c_void) {
+        self.fscommand_cb = HostCallback { func, user };
+    }
+
+    /// Forward queued `getURL` calls to the host, outside the player lock so callbacks may
+    /// call back into the bridge.
+    #[cfg(feature = "net")]
+    fn dispatch_navigations(&mut self) {
+        for (url, target) in self.backend.take_navigations() {
+            let lower = url.to_ascii_lowercase();
+            let delivered = if lower.starts_with("fscommand:") {
+                self.fscommand_cb.call(&url["fscommand:".len()..], &target)
+            } else if lower.starts_with("javascript:") {
+                self.fscommand_cb.call(&url, &target)
+            } else {
+                self.navigate_cb.call(&url, &target)
+            };
+            if !delivered {
+                runlog::log_line(&format!("navigate unhandled url={} target={}", url, target));
+            }
+        }
+    }
+
+    /// True when `renderer.cfg` enables stereo, so the launcher should turn on 3D mode.
+    pub fn stereo_enabled(&self) -> bool {
+        config::stereo_depth() > 0.0
+    }
+
+    /// Hint how many framebuffers the launcher rotates through; returns the clamped count.
+    pub fn set_buffer_count(&mut self, count: u32) -> u32 {
+        self.renderer.set_buffer_count(count as usize) as u32
+    }
+
     pub fn is_ready(&self) -> bool {
         self.backend.is_ready()
     }
 
+    /// Start writing every live input event (plus per-frame dt) to `path`.
+    ///
+    /// Replaces any recording in progress; refused while a replay is running.
+    pub fn start_input_recording(&mut self, path: &str) -> Result<(), String> {
+        if self.input_replay.is_some() {
+            return Err("input replay active".to_string());
+        }
+        self.stop_input_recording();
+        let recorder = InputRecorder::create(path)?;
+        runlog::log_important(&format!("input_trace record begin path={} frame={}", path, self.frame_counter));
+        self.input_recorder = Some(recorder);
+        self.trace_frame_base = self.frame_counter;
+        Ok(())
+    }
+
+    pub fn stop_input_recording(&mut self) {
+        if let Some(recorder) = self.input_recorder.take() {
+            runlog::log_important(&format!(
+                "input_trace record end path={} events={} frame={}",
+                recorder.path(),
+                recorder.events(),
+                self.frame_counter
+            ));
+            recorder.finish();
+        }
+    }
+
+    /// Feed a recorded trace back through the engine; live input is ignored until it ends.
+    pub fn start_input_replay(&mut self, path: &str) -> Result<(), String> {
+        self.stop_input_recording();
+        let replay = InputReplay::load(path)?;
+        runlog::log_important(&format!("input_trace replay begin path={} entries={}", path, replay.len()));
+        self.input_replay = Some(replay);
+        self.trace_frame_base = self.frame_counter;
+        Ok(())
+    }
+
+    /// Start writing every frame's render commands to `path` (see `cmd_trace`).
+    ///
+    /// Replaces any recording in progress; refused while a command replay is running.
+    pub fn start_cmd_recording(&mut self, path: &str) -> Result<(), String> {
+        if self.cmd_replay.is_some() {
+            return Err("cmd replay active".to_string());
+        }
+        self.stop_cmd_recording();
+        let recorder = CmdRecorder::create(path)?;
+        runlog::log_important(&format!("cmd_trace record begin path={} frame={}", path, self.frame_counter));
+        self.cmd_recorder = Some(recorder);
+        Ok(())
+    }
+
+    pub fn stop_cmd_recording(&mut self) {
+        if let Some(recorder) = self.cmd_recorder.take() {
+            runlog::log_important(&format!(
+                "cmd_trace record end path={} frames={} frame={}",
+                recorder.path(),
+                recorder.frames(),
+                self.frame_counter
+            ));
+            recorder.finish();
+        }
+    }
+
+    /// Draw a recorded command trace frame by frame instead of ticking the player; normal
+    /// playback resumes where it stopped once the trace runs out.
+    pub fn start_cmd_replay(&mut self, path: &str) -> Result<(), String> {
+        self.stop_cmd_recording();
+        let replay = CmdReplay::open(path)?;
+        runlog::log_important(&format!("cmd_trace replay begin path={} frame={}", path, self.frame_counter));
+        self.cmd_replay = Some(replay);
+        Ok(())
+    }
+
+    /// Render the next replayed packet. False when the trace ended (or failed) this frame.
+    fn replay_cmd_frame(&mut self) -> bool {
+        let Some(mut replay) = self.cmd_replay.take() else {
+            return false;
+        };
+        match replay.next_packet(&mut self.scratch_packet) {
+            Ok(true) => {
+                runlog::stage("renderer.render", self.frame_counter);
+                self.renderer.render_with_caches(&self.scratch_packet, replay.caches());
+                runlog::stage("present", self.frame_counter);
+                self.cmd_replay = Some(replay);
+                return true;
+            }
+            Ok(false) => {}
+            Err(err) => runlog::warn_line(&err),
+        }
+        runlog::log_important(&format!("cmd_trace replay end path={} frames={}", replay.path(), replay.frames()));
+        false
+    }
+
+    /// Dispatch replayed events due before this frame and return the dt to tick with.
+    fn apply_input_trace(&mut self, dt_ms: u32) -> u32 {
+        let frame = self.frame_counter.wrapping_sub(self.trace_frame_base);
+        if let Some(mut replay) = self.input_replay.take() {
+            while let Some(event) = replay.next_input_before(frame) {
+                self.dispatch_trace_event(event);
+            }
+            let dt = replay.take_frame_dt(frame).unwrap_or(dt_ms);
+            if replay.is_finished() {
+                runlog::log_important(&format!("input_trace replay end path={} frame={}", replay.path(), frame));
+            } else {
+                self.input_replay = Some(replay);
+            }
+            return dt;
+        }
+        if let Some(recorder) = self.input_recorder.as_mut() {
+            recorder.record(frame, TraceEvent::Frame { dt_ms });
+        }
+        dt_ms
+    }
+
+    fn dispatch_trace_event(&mut self, event: TraceEvent) {
+        match event {
+            TraceEvent::Frame { .. } => {}
+            TraceEvent::MouseMove { x, y } => self.dispatch_mouse_move(x, y),
+            TraceEvent::MouseButton { button, down } => self.dispatch_mouse_button(button, down),
+            TraceEvent::Key { keycode, down } => self.dispatch_key_event(keycode, down),
+            TraceEvent::Wheel { delta } => self.dispatch_mouse_wheel(delta),
+        }
+    }
+
+    /// Live input goes through here; dropped while a replay is running so the two never mix.
+    fn accept_live_input(&mut self, event: TraceEvent) -> bool {
+        if self.input_replay.is_some() {
+            return false;
+        }
+        if let Some(recorder) = self.input_recorder.as_mut() {
+            recorder.record(self.frame_counter.wrapping_sub(self.trace_frame_base), event);
+        }
+        true
+    }
+
+    /// Feed one serialized input event (`input_trace::parse_event`) as if it came from the
+    /// launcher: it is recorded by an active input trace exactly like live input.
+    pub fn inject_event(&mut self, descriptor: &str) -> Result<(), String> {
+        let event = input_trace::parse_event(descriptor)
+            .ok_or_else(|| format!("inject_event: bad descriptor '{}'", descriptor.trim()))?;
+        if !self.accept_live_input(event) {
+            return Err("inject_event: ignored while an input trace is replaying".to_string());
+        }
+        self.dispatch_trace_event(event);
+        Ok(())
+    }
+
     pub fn mouse_move(&mut self, x: i32, y: i32) {
+        if self.accept_live_input(TraceEvent::MouseMove { x, y }) {
+            self.dispatch_mouse_move(x, y);
+        }
+    }
+
+    pub fn mouse_button(&mut self, button: i32, down: bool) {
+        if self.accept_live_input(TraceEvent::MouseButton { button, down }) {
+            self.dispatch_mouse_button(button, down);
+        }
+    }
+
+    /// Scroll by `delta` lines at the cursor; positive scrolls up (away from the user), as in
+    /// Flash's `Mouse.onMouseWheel`.
+    pub fn mouse_wheel(&mut self, delta: i32) {
+        if delta != 0 && self.accept_live_input(TraceEvent::Wheel { delta }) {
+            self.dispatch_mouse_wheel(delta);
+        }
+    }
+
+    fn dispatch_mouse_wheel(&mut self, delta: i32) {
+        self.backend.record_input(format!("W{}", delta));
+        let mut player = self.player.lock().unwrap();
+        player.handle_event(PlayerEvent::MouseWheel { delta: MouseWheelDelta::Lines(delta as f64) });
+    }
+
+    /// Bottom-screen touch in touch pixels (320x240). Maps onto the top-screen stage and emits
+    /// a move, then a press on first contact; release (coordinates ignored) lifts the button at
+    /// the last touched point, so a tap without movement is still a click.
+    pub fn touch(&mut self, x: i32, y: i32, down: bool) {
+        if down {
+            let sx = x.clamp(0, TOUCH_W - 1) * self.screen_w as i32 / TOUCH_W;
+            let sy = y.clamp(0, TOUCH_H - 1) * self.screen_h as i32 / TOUCH_H;
+            if !self.touch_down || sx != self.mouse_x || sy != self.mouse_y {
+                self.mouse_move(sx, sy);
+            }
+            if !self.touch_down {
+                self.mouse_button(0, true);
+                self.touch_down = true;
+            }
+        } else if self.touch_down {
+            self.mouse_button(0, false);
+            self.touch_down = false;
+        }
+    }
+
+    /// 3DS button `id` (index into `config::BUTTON_NAMES`) pressed or released; translated
+    /// through the `button_*` table into a key or a mouse click.
+    pub fn button(&mut self, id: u32, down: bool) {
+        match config::button_action(id as usize) {
+            ButtonAction::None => {}
+            ButtonAction::MouseLeft => self.mouse_button(0, down),
+            ButtonAction::Key(keycode) => self.key_event(keycode, down),
+        }
+    }
+
+    /// Switch what the circle pad drives; releases any arrow keys the pad was holding.
+    pub fn set_pad_mode(&mut self, mode: PadMode) {
+        if mode == self.pad_mode {
+            return;
+        }
+        self.release_pad_keys();
+        self.pad_carry_x = 0.0;
+        self.pad_carry_y = 0.0;
+        self.pad_mode = mode;
+    }
+
+    /// Circle pad position this frame (raw units, about ±156 at full tilt, up positive).
+    pub fn pad_axis(&mut self, dx: i32, dy: i32) {
+        let nx = pad_normalize(dx);
+        let ny = -pad_normalize(dy);
+        match self.pad_mode {
+            PadMode::Off => {}
+            PadMode::Mouse => {
+                if nx == 0.0 && ny == 0.0 {
+                    self.pad_carry_x = 0.0;
+                    self.pad_carry_y = 0.0;
+                    return;
+                }
+                // Quadratic response: fine control near the centre, fast sweeps at full tilt.
+                self.pad_carry_x += nx * nx.abs() * PAD_CURSOR_SPEED;
+                self.pad_carry_y += ny * ny.abs() * PAD_CURSOR_SPEED;
+                let step_x = self.pad_carry_x.trunc();
+                let step_y = self.pad_carry_y.trunc();
+                self.pad_carry_x -= step_x;
+                self.pad_carry_y -= step_y;
+                if step_x == 0.0 && step_y == 0.0 {
+                    return;
+                }
+                let x = (self.mouse_x + step_x as i32).clamp(0, self.screen_w as i32 - 1);
+                let y = (self.mouse_y + step_y as i32).clamp(0, self.screen_h as i32 - 1);
+                if x != self.mouse_x || y != self.mouse_y {
+                    self.mouse_move(x, y);
+                }
+            }
+            PadMode::Keys => {
+                let want = [
+                    nx <= -PAD_KEY_THRESHOLD,
+                    ny <= -PAD_KEY_THRESHOLD,
+                    nx >= PAD_KEY_THRESHOLD,
+                    ny >= PAD_KEY_THRESHOLD,
+                ];
+                for (i, &held) in want.iter().enumerate() {
+                    if held != self.pad_keys[i] {
+                        self.pad_keys[i] = held;
+                        self.key_event(PAD_KEYCODES[i], held);
+                    }
+                }
+            }
+        }
+    }
+
+    fn release_pad_keys(&mut self) {
+        for i in 0..self.pad_keys.len() {
+            if self.pad_keys[i] {
+                self.pad_keys[i] = false;
+                self.key_event(PAD_KEYCODES[i], false);
+            }
+        }
+    }
+
+    pub fn key_event(&mut self, keycode: i32, down: bool) {
+        if self.accept_live_input(TraceEvent::Key { keycode, down }) {
+            self.dispatch_key_event(keycode, down);
+        }
+    }
+
+    fn dispatch_mouse_move(&mut self, x: i32, y: i32) {
         self.mouse_x = x;
         self.mouse_y = y;
         self.backend.record_input(format!("M{:03},{:03}", x, y));
+        let (px, py) = self.screen_to_viewport(x, y);
         let mut player = self.player.lock().unwrap();
-        player.handle_event(PlayerEvent::MouseMove { x: x as f64, y: y as f64 });
+        player.handle_event(PlayerEvent::MouseMove { x: px, y: py });
     }
 
-    pub fn mouse_button(&mut self, button: i32, down: bool) {
+    /// Map screen pixels to the player's viewport space.
+    ///
+    /// Ruffle applies its own stage transform (scale mode, fullscreen, letterboxing) on top of
+    /// viewport coordinates, so we only have to account for any screen↔viewport size mismatch.
+    fn screen_to_viewport(&self, x: i32, y: i32) -> (f64, f64) {
+        scale_to_viewport(x, y, (self.screen_w, self.screen_h), self.backend.viewport_size())
+    }
+
+    fn dispatch_mouse_button(&mut self, button: i32, down: bool) {
         let btn = match button {
             1 => MouseButton::Right,
             2 => MouseButton::Middle,
             _ => MouseButton::Left,
         };
+        let (px, py) = self.screen_to_viewport(self.mouse_x, self.mouse_y);
         let mut player = self.player.lock().unwrap();
         if down {
             self.backend.record_input(format!("MB{} {}", button, "D"));
             player.handle_event(PlayerEvent::MouseDown {
-                x: self.mouse_x as f64,
-                y: self.mouse_y as f64,
+                x: px,
+                y: py,
                 button: btn,
                 index: None,
             });
         } else {
             self.backend.record_input(format!("MB{} {}", button, "U"));
             player.handle_event(PlayerEvent::MouseUp {
-                x: self.mouse_x as f64,
-                y: self.mouse_y as f64,
+                x: px,
+                y: py,
                 button: btn,
             });
         }
     }
 
-    pub fn key_event(&mut self, keycode: i32, down: bool) {
+    fn dispatch_key_event(&mut self, keycode: i32, down: bool) {
+        if config::key_repeat_enabled() {
+            self.held_keys.retain(|held| held.keycode != keycode);
+            if down {
+                let (delay_ms, _) = config::key_repeat_timing_ms();
+                self.held_keys.push(HeldKey { keycode, until_repeat_ms: delay_ms });
+            }
+        }
         if let Some(desc) = key_descriptor_from_keycode(keycode) {
             self.backend.record_input(format!("K{} {}", if down { "D" } else { "U" }, keycode));
             let mut player = self.player.lock().unwrap();
@@ -282,12 +1209,193 @@ impl Engine {
         }
     }
 
+    /// Fire repeat key-downs for keys held past the repeat delay. At most one repeat per key
+    /// per tick, so a long frame doesn't burst several at once.
+    fn update_key_repeat(&mut self, dt_ms: u32) {
+        if self.held_keys.is_empty() {
+            return;
+        }
+        let (_, interval_ms) = config::key_repeat_timing_ms();
+        let mut fire = Vec::new();
+        for held in &mut self.held_keys {
+            if held.until_repeat_ms <= dt_ms {
+                held.until_repeat_ms = interval_ms;
+                fire.push(held.keycode);
+            } else {
+                held.until_repeat_ms -= dt_ms;
+            }
+        }
+        if fire.is_empty() {
+            return;
+        }
+        let mut player = self.player.lock().unwrap();
+        for keycode in fire {
+            if let Some(key) = key_descriptor_from_keycode(keycode) {
+                player.handle_event(PlayerEvent::KeyDown { key });
+            }
+        }
+    }
+
     pub fn status_text(&self) -> String {
         // Keep it short: it will be printed every frame on the bottom screen.
-        self.backend.status_text_short()
+        let line = self.backend.status_text_short();
+        if self.speed == 1.0 {
+            return line;
+        }
+        // Same 32-byte budget as `status_text_short` (the C HUD prepends "FPS:xx ").
+        let line = format!("x{:.1} {}", self.speed, line);
+        text::trim_to(&line, 32).to_string()
     }
 }
 
+/// A held key and the time left until its next synthesized repeat.
+struct HeldKey {
+    keycode: i32,
+    until_repeat_ms: u32,
+}
+
+/// Consecutive slow rendered frames before frame-skip starts dropping renders.
+const FRAME_SKIP_SLOW_STREAK: u32 = 3;
+
+/// How long a playlist movie may sit on its last frame before we advance.
+const PLAYLIST_END_HOLD_MS: u32 = 2000;
+
+/// How long a playlist entry's error screen stays up before the next entry is tried.
+const PLAYLIST_ERROR_HOLD_MS: u32 = 3000;
+
+/// Build a player wired to our backends (renderer, audio, navigator, storage, log, UI).
+fn build_player(backend: &ThreeDSBackend, screen_w: u32, screen_h: u32) -> Arc<Mutex<Player>> {
+    runlog::log_important("init: player_builder");
+    let mut builder = PlayerBuilder::new()
+        .with_viewport_dimensions(screen_w, screen_h, 1.0);
+    runlog::log_important("init: renderer backend");
+    builder = builder.with_renderer(backend.clone());
+    #[cfg(feature = "audio")]
+    {
+        if let Some(audio) = DspAudioBackend::new() {
+            runlog::log_important("init: audio backend (ndsp)");
+            builder = builder.with_audio(audio);
+        } else {
+            runlog::log_important("init: audio backend null (ndsp unavailable)");
+            builder = builder.with_audio(NullAudioBackend::new());
+        }
+    }
+    #[cfg(not(feature = "audio"))]
+    {
+        runlog::log_important("init: audio backend null");
+        builder = builder.with_audio(NullAudioBackend::new());
+    }
+    #[cfg(feature = "net")]
+    {
+        runlog::log_important("init: navigator backend");
+        builder = builder.with_navigator(backend.clone());
+    }
+    #[cfg(not(feature = "net"))]
+    {
+        runlog::log_important("init: navigator backend disabled");
+    }
+    #[cfg(feature = "storage")]
+    {
+        runlog::log_important("init: storage backend");
+        builder = builder.with_storage(Box::new(backend.clone()));
+    }
+    #[cfg(not(feature = "storage"))]
+    {
+        runlog::log_important("init: storage backend disabled");
+    }
+    #[cfg(feature = "video")]
+    {
+        runlog::log_important("init: video backend");
+        builder = builder.with_video(NullVideoBackend::new());
+    }
+    runlog::log_important("init: log backend");
+    builder = builder.with_log(backend.clone());
+    runlog::log_important("init: ui backend");
+    builder.with_ui(backend.clone()).build()
+}
+
+/// SWF header facts for the launcher HUD (`bridge_engine_get_movie_info`).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MovieInfo {
+    pub frame_rate: f32,
+    /// Stage size in pixels.
+    pub width: u32,
+    pub height: u32,
+    pub num_frames: u16,
+    pub swf_version: u8,
+}
+
+/// Parse `movie_bytes` and install it as the player's root movie.
+///
+/// Returns the movie's frame count for playlist end detection.
+/// Parse and vet a root movie without touching the player or backend, so callers can refuse
+/// it and keep whatever is running.
+fn parse_root_movie(movie_bytes: &[u8], file_url: &str) -> Result<SwfMovie, String> {
+    let movie = SwfMovie::from_data(movie_bytes, file_url.to_string(), None).map_err(|e| {
+        runlog::warn_line(&format!("Ruffle refused SWF: {e:?}"));
+        format!("Ruffle refused SWF: {e:?}")
+    })?;
+    if movie.is_action_script_3() {
+        let msg = "AS3 not supported yet (AS2 only).";
+        runlog::warn_line(msg);
+        return Err(msg.to_string());
+    }
+    Ok(movie)
+}
+
+/// Make a parsed movie the player's root and start it playing.
+fn start_root_movie(
+    player: &Arc<Mutex<Player>>,
+    backend: &ThreeDSBackend,
+    movie: SwfMovie,
+    movie_bytes: &[u8],
+    file_url: &str,
+) -> MovieInfo {
+    backend.mark_movie_loaded(movie.version());
+    backend.begin_load_progress(movie.data());
+    backend.warm_shape_cache(movie_bytes);
+    #[cfg(feature = "storage")]
+    backend.set_storage_movie(file_url);
+    #[cfg(feature = "net")]
+    backend.set_base_url(file_url);
+    #[cfg(not(any(feature = "storage", feature = "net")))]
+    let _ = file_url;
+    runlog::log_important(&format!("Engine::new SwfMovie ok version={}", movie.version()));
+    let info = MovieInfo {
+        frame_rate: f64::from(movie.frame_rate()) as f32,
+        width: movie.width().to_pixels().max(0.0) as u32,
+        height: movie.height().to_pixels().max(0.0) as u32,
+        num_frames: movie.num_frames(),
+        swf_version: movie.version(),
+    };
+    runlog::log_important(&format!(
+        "movie info {:.1}fps {}x{} frames={}",
+        info.frame_rate, info.width, info.height, info.num_frames
+    ));
+    player.lock().unwrap().mutate_with_update_context(|uc| {
+        uc.set_root_movie(movie);
+    });
+    player.lock().unwrap().set_is_playing(true);
+    info
+}
+
+fn elapsed_us(start: Instant) -> u32 {
+    start.elapsed().as_micros().min(u32::MAX as u128) as u32
+}
+
+/// Scale a `screen`-sized pixel position into a `viewport`-sized one.
+fn scale_to_viewport(x: i32, y: i32, screen: (u32, u32), viewport: (u32, u32)) -> (f64, f64) {
+    let sx = viewport.0 as f64 / screen.0 as f64;
+    let sy = viewport.1 as f64 / screen.1 as f64;
+    (x as f64 * sx, y as f64 * sy)
+}
+
+/// Raw circle pad axis to -1..1 with the dead zone removed.
+fn pad_normalize(v: i32) -> f32 {
+    let mag = (v.unsigned_abs() as f32 - PAD_DEAD_ZONE).max(0.0) / (PAD_RANGE - PAD_DEAD_ZONE);
+    mag.min(1.0).copysign(v as f32)
+}
+
 fn key_descriptor_from_keycode(keycode: i32) -> Option<KeyDescriptor> {
     let logical = match keycode {
         8 => LogicalKey::Named(NamedKey::Backspace),
@@ -295,6 +1403,9 @@ fn key_descriptor_from_keycode(keycode: i32) -> Option<KeyDescriptor> {
         19 => LogicalKey::Named(NamedKey::Pause),
         27 => LogicalKey::Named(NamedKey::Escape),
         32 => LogicalKey::Character(' '),
+        9 => LogicalKey::Named(NamedKey::Tab),
+        16 => LogicalKey::Named(NamedKey::Shift),
+        17 => LogicalKey::Named(NamedKey::Control),
         37 => LogicalKey::Named(NamedKey::ArrowLeft),
         38 => LogicalKey::Named(NamedKey::ArrowUp),
         39 => LogicalKey::Named(NamedKey::ArrowRight),
@@ -309,3 +1420,45 @@ fn key_descriptor_from_keycode(keycode: i32) -> Option<KeyDescriptor> {
         key_location: KeyLocation::Standard,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruffle_core::backend::ui::UiBackend;
+    use ruffle_render::backend::{RenderBackend, ViewportDimensions};
+
+    /// Ruffle's `ShowAll` stage transform: uniform scale, centered with letterboxing.
+    fn viewport_to_stage(p: (f64, f64), viewport: (u32, u32), stage: (f64, f64)) -> (f64, f64) {
+        let scale = (viewport.0 as f64 / stage.0).min(viewport.1 as f64 / stage.1);
+        let ox = (viewport.0 as f64 - stage.0 * scale) / 2.0;
+        let oy = (viewport.1 as f64 - stage.1 * scale) / 2.0;
+        ((p.0 - ox) / scale, (p.1 - oy) / scale)
+    }
+
+    #[test]
+    fn fullscreen_toggle_keeps_touch_on_same_stage_point() {
+        let screen = (400, 240);
+        let stage = (400.0, 240.0);
+        let mut backend = ThreeDSBackend::new(SharedCaches::new(), screen.0, screen.1);
+        let touch = (100, 60);
+        let stage_point = |backend: &ThreeDSBackend| {
+            let viewport = backend.viewport_size();
+            let p = scale_to_viewport(touch.0, touch.1, screen, viewport);
+            (p, viewport_to_stage(p, viewport, stage))
+        };
+
+        let (windowed, before) = stage_point(&backend);
+        assert_eq!(windowed, (100.0, 60.0));
+
+        // Entering fullscreen, the player resizes the viewport (here to a 2x framebuffer).
+        backend.set_fullscreen(true).unwrap();
+        backend.set_viewport_dimensions(ViewportDimensions { width: 800, height: 480, scale_factor: 2.0 });
+        let (full, during) = stage_point(&backend);
+        assert_eq!(full, (200.0, 120.0));
+        assert_eq!(during, before);
+
+        backend.set_fullscreen(false).unwrap();
+        backend.set_viewport_dimensions(ViewportDimensions { width: 400, height: 240, scale_factor: 1.0 });
+        assert_eq!(stage_point(&backend), (windowed, before));
+    }
+}
@@ -0,0 +1,40 @@
+//! PCM output to the DSP (implemented in `source/audio_ndsp.c`).
+//!
+//! The C side owns a small ring of ndsp wave buffers; Rust only asks how many are
+//! free and hands over interleaved stereo i16 frames to fill the next one.
+
+extern "C" {
+    fn bridge_audio_open(sample_rate: u32, frames_per_buffer: u32) -> i32;
+    fn bridge_audio_free_buffers() -> u32;
+    fn bridge_audio_queue(samples: *const i16, frames: u32) -> i32;
+    fn bridge_audio_set_paused(paused: u32);
+    fn bridge_audio_close();
+}
+
+/// Initialise ndsp and allocate the wave buffers. Returns false when the DSP is
+/// unavailable (e.g. missing `dspfirm.cdc`).
+pub fn open(sample_rate: u32, frames_per_buffer: u32) -> bool {
+    unsafe { bridge_audio_open(sample_rate, frames_per_buffer) == 0 }
+}
+
+/// Number of wave buffers the DSP has finished playing and can be refilled.
+pub fn free_buffers() -> u32 {
+    unsafe { bridge_audio_free_buffers() }
+}
+
+/// Queue interleaved stereo frames into the next free wave buffer.
+pub fn queue(samples: &[i16]) -> bool {
+    let frames = (samples.len() / 2) as u32;
+    if frames == 0 {
+        return false;
+    }
+    unsafe { bridge_audio_queue(samples.as_ptr(), frames) == 0 }
+}
+
+pub fn set_paused(paused: bool) {
+    unsafe { bridge_audio_set_paused(paused as u32) }
+}
+
+pub fn close() {
+    unsafe { bridge_audio_close() }
+}
@@ -1,9 +1,15 @@
-use core::ffi::c_char;
+use core::ffi::{c_char, c_void};
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::{Mutex, OnceLock};
 
+use ruffle_core::Color;
+
 use crate::engine::Engine;
-use crate::ffi::types::{cstr_to_string, write_c_string};
+use crate::ffi::types::{cstr_to_string, write_c_string, BridgeDiag, BridgeMovieInfo, TextInputCallback};
+#[cfg(feature = "net")]
+use crate::ffi::types::NavigateCallback;
 use crate::runlog;
+use crate::util::config::{self, PadMode};
 
 #[no_mangle]
 pub extern "C" fn bridge_runlog_drain(out: *mut c_char, out_len: u32) -> u32 {
@@ -20,6 +26,84 @@ pub extern "C" fn bridge_runlog_drain(out: *mut c_char, out_len: u32) -> u32 {
     n as u32
 }
 
+/// Drain the recent-warnings ring (newest last, at most the last 8) into `out` as
+/// newline-separated lines, like `bridge_runlog_drain`. Returns bytes written.
+#[no_mangle]
+pub extern "C" fn bridge_runlog_recent_warnings(out: *mut c_char, out_len: u32) -> u32 {
+    if out.is_null() || out_len == 0 { return 0; }
+    // Safety: caller provides valid buffer.
+    let buf = unsafe { core::slice::from_raw_parts_mut(out as *mut u8, out_len as usize) };
+    let n = runlog::drain_recent_warnings(buf);
+    if n < buf.len() {
+        buf[n] = 0;
+    } else if !buf.is_empty() {
+        buf[buf.len()-1] = 0;
+    }
+    n as u32
+}
+
+/// Drain the last AS `trace()` lines (at most 32, oldest first) into `out` as newline-separated
+/// text, like `bridge_runlog_drain`. Returns bytes written.
+#[no_mangle]
+pub extern "C" fn bridge_avm_trace_drain(out: *mut c_char, out_len: u32) -> u32 {
+    if out.is_null() || out_len == 0 { return 0; }
+    // Safety: caller provides valid buffer.
+    let buf = unsafe { core::slice::from_raw_parts_mut(out as *mut u8, out_len as usize) };
+    let n = runlog::drain_avm_trace(buf);
+    if n < buf.len() {
+        buf[n] = 0;
+    } else if !buf.is_empty() {
+        buf[buf.len()-1] = 0;
+    }
+    n as u32
+}
+
+/// Re-read `renderer.cfg` from the SD card and apply it (e.g. after editing it over FTP).
+/// Overrides from `bridge_set_render_config` are replaced by the file's values. Returns the
+/// resulting `BRIDGE_RENDER_*` bits.
+#[no_mangle]
+pub extern "C" fn bridge_reload_render_config() -> u32 {
+    let cfg = config::reload_render_config();
+    let flags = config::live_flags();
+    runlog::log_important(&format!("render_config reload flags=0x{:02x}", flags));
+    runlog::log_line(&format!("render_config {:?}", cfg));
+    flags
+}
+
+/// Change render toggles at runtime (process-wide, applies from the next frame). Each argument
+/// is 1 = on, 0 = off, negative = keep the current value; initial values come from
+/// `renderer.cfg`. Returns the resulting `BRIDGE_RENDER_*` bits.
+#[no_mangle]
+pub extern "C" fn bridge_set_render_config(
+    textured_bitmaps: i32,
+    masks_enabled: i32,
+    subpixel_shapes: i32,
+    unsupported_fill_hatch: i32,
+    bilinear: i32,
+    aa_edges: i32,
+) -> u32 {
+    let mut mask = 0;
+    let mut values = 0;
+    for (arg, bit) in [
+        (textured_bitmaps, config::LIVE_TEXTURED_BITMAPS),
+        (masks_enabled, config::LIVE_MASKS),
+        (subpixel_shapes, config::LIVE_SUBPIXEL_SHAPES),
+        (unsupported_fill_hatch, config::LIVE_UNSUPPORTED_FILL_HATCH),
+        (bilinear, config::LIVE_BILINEAR),
+        (aa_edges, config::LIVE_AA_EDGES),
+    ] {
+        if arg >= 0 {
+            mask |= bit;
+            if arg > 0 {
+                values |= bit;
+            }
+        }
+    }
+    let flags = config::set_live_flags(mask, values);
+    runlog::log_important(&format!("render_config flags=0x{:02x}", flags));
+    flags
+}
+
 
 /// Opaque handle passed to C.
 ///
@@ -59,6 +143,15 @@ fn normalize_sd_path(mut p: String) -> String {
     p
 }
 
+/// Box `engine` for C. Engines in error mode still get a handle; their message is also left
+/// in `bridge_engine_last_error`.
+fn engine_handle(engine: Engine) -> *mut BridgeContext {
+    if let Some(msg) = engine.error_message() {
+        set_last_error(msg.to_string());
+    }
+    Box::into_raw(Box::new(BridgeContext { engine }))
+}
+
 #[no_mangle]
 pub extern "C" fn bridge_player_create_with_url(url: *const c_char) -> *mut BridgeContext {
     crate::util::logging::init_logger();
@@ -73,7 +166,7 @@ pub extern "C" fn bridge_player_create_with_url(url: *const c_char) -> *mut Brid
     let root_path = normalize_sd_path(root);
 
     match Engine::new(&root_path, 400, 240) {
-        Ok(engine) => Box::into_raw(Box::new(BridgeContext { engine })),
+        Ok(engine) => engine_handle(engine),
         Err(err) => {
             set_last_error(err);
             core::ptr::null_mut()
@@ -97,7 +190,43 @@ pub extern "C" fn bridge_engine_create(swf_path: *const c_char, screen_w: i32, s
     let height = screen_h.max(1) as u32;
 
     match Engine::new(&root_path, width, height) {
-        Ok(engine) => Box::into_raw(Box::new(BridgeContext { engine })),
+        Ok(engine) => engine_handle(engine),
+        Err(err) => {
+            set_last_error(err);
+            core::ptr::null_mut()
+        }
+    }
+}
+
+/// Create an engine that plays `count` SWFs in order and loops at the end.
+///
+/// `timeout_ms == 0` advances only when a movie reaches its last frame.
+#[no_mangle]
+pub extern "C" fn bridge_engine_create_playlist(
+    paths: *const *const c_char,
+    count: u32,
+    screen_w: i32,
+    screen_h: i32,
+    timeout_ms: u32,
+) -> *mut BridgeContext {
+    crate::util::logging::init_logger();
+
+    if paths.is_null() || count == 0 {
+        set_last_error("Empty playlist".to_string());
+        return core::ptr::null_mut();
+    }
+    let raw = unsafe { core::slice::from_raw_parts(paths, count as usize) };
+    let list: Vec<String> = raw
+        .iter()
+        .filter_map(|p| cstr_to_string(*p))
+        .filter(|p| !p.trim().is_empty())
+        .map(normalize_sd_path)
+        .collect();
+
+    let width = screen_w.max(1) as u32;
+    let height = screen_h.max(1) as u32;
+    match Engine::new_playlist(list, width, height, timeout_ms) {
+        Ok(engine) => engine_handle(engine),
         Err(err) => {
             set_last_error(err);
             core::ptr::null_mut()
@@ -105,22 +234,31 @@ pub extern "C" fn bridge_engine_create(swf_path: *const c_char, screen_w: i32, s
     }
 }
 
+#[no_mangle]
+pub extern "C" fn bridge_engine_next(ctx: *mut BridgeContext) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    if ctx.engine.playlist_next() { 1 } else { 0 }
+}
+
+#[no_mangle]
+pub extern "C" fn bridge_engine_prev(ctx: *mut BridgeContext) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    if ctx.engine.playlist_prev() { 1 } else { 0 }
+}
+
 #[no_mangle]
 pub extern "C" fn bridge_engine_last_error(out: *mut c_char, out_len: u32) -> u32 {
     if out.is_null() || out_len == 0 {
         return 0;
     }
     let msg = take_last_error().unwrap_or_else(|| "Unknown error".to_string());
-    let buf = unsafe { core::slice::from_raw_parts_mut(out as *mut u8, out_len as usize) };
-    let mut written = 0usize;
-    let bytes = msg.as_bytes();
-    let copy_len = bytes.len().min(buf.len().saturating_sub(1));
-    if copy_len > 0 {
-        buf[..copy_len].copy_from_slice(&bytes[..copy_len]);
-        written = copy_len;
-    }
-    buf[written] = 0;
-    written as u32
+    write_c_string(out, out_len as usize, &msg) as u32
 }
 
 #[no_mangle]
@@ -146,16 +284,33 @@ pub extern "C" fn bridge_tick(ctx: *mut BridgeContext) {
         return;
     }
     let ctx = unsafe { &mut *ctx };
-    ctx.engine.tick_and_render(16);
+    guarded_tick(ctx, 16);
 }
 
+/// Tick and render. Returns 0 when frame-skip dropped the render (nothing new to present).
 #[no_mangle]
-pub extern "C" fn bridge_engine_tick(ctx: *mut BridgeContext, dt_ms: u32) {
+pub extern "C" fn bridge_engine_tick(ctx: *mut BridgeContext, dt_ms: u32) -> u32 {
     if ctx.is_null() {
-        return;
+        return 0;
     }
     let ctx = unsafe { &mut *ctx };
-    ctx.engine.tick_and_render(dt_ms);
+    if guarded_tick(ctx, dt_ms) { 1 } else { 0 }
+}
+
+/// Tick without letting a panic unwind into C: a caught panic puts the engine in error mode,
+/// which draws the message on screen from the next tick.
+fn guarded_tick(ctx: &mut BridgeContext, dt_ms: u32) -> bool {
+    let engine = &mut ctx.engine;
+    match panic::catch_unwind(AssertUnwindSafe(|| engine.tick_and_render(dt_ms))) {
+        Ok(rendered) => rendered,
+        Err(payload) => {
+            let msg = format!("Crash: {}", crate::util::logging::panic_message(payload.as_ref()));
+            set_last_error(msg.clone());
+            // Best effort: entering error mode must not unwind into C either.
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| engine.enter_error_mode(msg)));
+            false
+        }
+    }
 }
 
 #[no_mangle]
@@ -176,6 +331,62 @@ pub extern "C" fn bridge_engine_mouse_button(ctx: *mut BridgeContext, button: i3
     ctx.engine.mouse_button(button, down);
 }
 
+/// Mouse wheel by `delta` lines (positive = up, Flash convention).
+#[no_mangle]
+pub extern "C" fn bridge_engine_mouse_wheel(ctx: *mut BridgeContext, delta: i32) {
+    if ctx.is_null() {
+        return;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.mouse_wheel(delta);
+}
+
+/// Bottom-screen touch at (x, y) in touch pixels; `down` false releases (x, y ignored).
+#[no_mangle]
+pub extern "C" fn bridge_engine_touch(ctx: *mut BridgeContext, x: i32, y: i32, down: bool) {
+    if ctx.is_null() {
+        return;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.touch(x, y, down);
+}
+
+/// 3DS button press/release by id (`BRIDGE_BUTTON_*`); mapped through the `button_*` config table.
+#[no_mangle]
+pub extern "C" fn bridge_engine_button(ctx: *mut BridgeContext, button: u32, down: bool) {
+    if ctx.is_null() {
+        return;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.button(button, down);
+}
+
+/// Circle pad position (raw `hidCircleRead` units); call once per frame.
+#[no_mangle]
+pub extern "C" fn bridge_engine_pad_axis(ctx: *mut BridgeContext, dx: i32, dy: i32) {
+    if ctx.is_null() {
+        return;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.pad_axis(dx, dy);
+}
+
+/// Circle pad mapping: 0 = virtual mouse, 1 = arrow keys, 2 = off. Unknown values are ignored.
+#[no_mangle]
+pub extern "C" fn bridge_engine_set_pad_mode(ctx: *mut BridgeContext, mode: u32) {
+    if ctx.is_null() {
+        return;
+    }
+    let ctx = unsafe { &mut *ctx };
+    let mode = match mode {
+        0 => PadMode::Mouse,
+        1 => PadMode::Keys,
+        2 => PadMode::Off,
+        _ => return,
+    };
+    ctx.engine.set_pad_mode(mode);
+}
+
 #[no_mangle]
 pub extern "C" fn bridge_engine_key(ctx: *mut BridgeContext, keycode: i32, down: bool) {
     if ctx.is_null() {
@@ -262,3 +473,467 @@ pub extern "C" fn bridge_toggle_affine_debug_overlay_ctx(ctx: *mut BridgeContext
     let ctx = unsafe { &mut *ctx };
     if ctx.engine.toggle_debug_affine_overlay() { 1 } else { 0 }
 }
+
+/// Toggle outlines around every drawn shape's transformed bounds; returns the new state.
+#[no_mangle]
+pub extern "C" fn bridge_toggle_bounds_overlay_ctx(ctx: *mut BridgeContext) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    if ctx.engine.toggle_bounds_overlay() { 1 } else { 0 }
+}
+
+/// Toggle the triangle-count heatmap over drawn shapes; returns the new state.
+#[no_mangle]
+pub extern "C" fn bridge_toggle_tri_heatmap_ctx(ctx: *mut BridgeContext) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    if ctx.engine.toggle_tri_heatmap() { 1 } else { 0 }
+}
+
+/// Returns 1 while content has requested the virtual keyboard and it hasn't closed yet.
+#[no_mangle]
+pub extern "C" fn bridge_engine_virtual_keyboard_open(ctx: *mut BridgeContext) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    if ctx.engine.virtual_keyboard_open() { 1 } else { 0 }
+}
+
+/// Tell the engine the launcher closed the keyboard dialog.
+#[no_mangle]
+pub extern "C" fn bridge_engine_virtual_keyboard_dismissed(ctx: *mut BridgeContext) {
+    if ctx.is_null() {
+        return;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.virtual_keyboard_dismissed();
+}
+
+#[no_mangle]
+pub extern "C" fn bridge_engine_toggle_stats_overlay(ctx: *mut BridgeContext) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    if ctx.engine.toggle_stats_overlay() { 1 } else { 0 }
+}
+
+/// Start recording input to `path` (NULL or empty stops recording). Returns 1 on success.
+#[no_mangle]
+pub extern "C" fn bridge_engine_record_input(ctx: *mut BridgeContext, path: *const c_char) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    match cstr_to_string(path).filter(|p| !p.trim().is_empty()) {
+        Some(p) => match ctx.engine.start_input_recording(&normalize_sd_path(p)) {
+            Ok(()) => 1,
+            Err(err) => {
+                runlog::warn_line(&err);
+                0
+            }
+        },
+        None => {
+            ctx.engine.stop_input_recording();
+            1
+        }
+    }
+}
+
+/// Replay an input trace recorded with `bridge_engine_record_input`. Returns 1 on success.
+#[no_mangle]
+pub extern "C" fn bridge_engine_replay_input(ctx: *mut BridgeContext, path: *const c_char) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    let Some(p) = cstr_to_string(path).filter(|p| !p.trim().is_empty()) else {
+        return 0;
+    };
+    match ctx.engine.start_input_replay(&normalize_sd_path(p)) {
+        Ok(()) => 1,
+        Err(err) => {
+            runlog::warn_line(&err);
+            0
+        }
+    }
+}
+
+/// Start recording the render commands of every frame to `path`. Returns 1 on success; the
+/// reason for a failure is available from `bridge_engine_last_error`.
+#[no_mangle]
+pub extern "C" fn bridge_engine_start_record(ctx: *mut BridgeContext, path: *const c_char) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    let Some(p) = cstr_to_string(path).filter(|p| !p.trim().is_empty()) else {
+        set_last_error("start_record: empty path".to_string());
+        return 0;
+    };
+    match ctx.engine.start_cmd_recording(&normalize_sd_path(p)) {
+        Ok(()) => 1,
+        Err(err) => {
+            runlog::warn_line(&err);
+            set_last_error(err);
+            0
+        }
+    }
+}
+
+/// Stop a recording started with `bridge_engine_start_record` and flush the file.
+#[no_mangle]
+pub extern "C" fn bridge_engine_stop_record(ctx: *mut BridgeContext) {
+    if ctx.is_null() {
+        return;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.stop_cmd_recording();
+}
+
+/// Draw a trace recorded with `bridge_engine_start_record` instead of ticking the player,
+/// one recorded frame per tick. Returns 1 on success.
+#[no_mangle]
+pub extern "C" fn bridge_engine_replay_record(ctx: *mut BridgeContext, path: *const c_char) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    let Some(p) = cstr_to_string(path).filter(|p| !p.trim().is_empty()) else {
+        set_last_error("replay_record: empty path".to_string());
+        return 0;
+    };
+    match ctx.engine.start_cmd_replay(&normalize_sd_path(p)) {
+        Ok(()) => 1,
+        Err(err) => {
+            runlog::warn_line(&err);
+            set_last_error(err);
+            0
+        }
+    }
+}
+
+/// Inject one input event, e.g. `"M 120 80"` (move), `"B 0 1"` / `"B 0 0"` (left button
+/// down/up), `"K 32 1"` (key down by Flash keycode), `"W -1"` (wheel). Returns 1 if the event
+/// was delivered; otherwise the reason is available from `bridge_engine_last_error`.
+#[no_mangle]
+pub extern "C" fn bridge_engine_inject_event(ctx: *mut BridgeContext, event: *const c_char) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    let Some(text) = cstr_to_string(event) else {
+        return 0;
+    };
+    match ctx.engine.inject_event(&text) {
+        Ok(()) => 1,
+        Err(err) => {
+            set_last_error(err);
+            0
+        }
+    }
+}
+
+/// Write a JSON diagnostics dump (schema version in `"v"`) to `path`. Returns 1 on success.
+#[no_mangle]
+pub extern "C" fn bridge_engine_dump_diagnostics_json(ctx: *mut BridgeContext, path: *const c_char) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    let Some(p) = cstr_to_string(path).filter(|p| !p.trim().is_empty()) else {
+        return 0;
+    };
+    match ctx.engine.dump_diagnostics_json(&normalize_sd_path(p)) {
+        Ok(()) => 1,
+        Err(err) => {
+            runlog::warn_line(&err);
+            0
+        }
+    }
+}
+
+/// Write the top framebuffer as a PNG. A bare file name (or null/empty for an auto-named file)
+/// goes into the run bundle. Returns 1 on success; on failure the reason is available from
+/// `bridge_engine_last_error`.
+#[no_mangle]
+pub extern "C" fn bridge_engine_capture_screenshot(ctx: *mut BridgeContext, path: *const c_char) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    let p = cstr_to_string(path).map(|p| p.trim().to_string()).unwrap_or_default();
+    let p = if p.contains('/') { normalize_sd_path(p) } else { p };
+    match ctx.engine.capture_screenshot(&p) {
+        Ok(_) => 1,
+        Err(err) => {
+            runlog::warn_line(&err);
+            set_last_error(err);
+            0
+        }
+    }
+}
+
+/// Set the shape mesh cache budget in bytes; returns the budget actually applied.
+#[no_mangle]
+pub extern "C" fn bridge_engine_set_shape_cache_budget(ctx: *mut BridgeContext, bytes: u32) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.set_shape_cache_budget(bytes as usize).min(u32::MAX as usize) as u32
+}
+
+/// Set the bitmap surface cache budget in bytes; returns the budget actually applied.
+#[no_mangle]
+pub extern "C" fn bridge_engine_set_bitmap_cache_budget(ctx: *mut BridgeContext, bytes: u32) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.set_bitmap_cache_budget(bytes as usize).min(u32::MAX as usize) as u32
+}
+
+/// Tell the renderer how many framebuffers the launcher swaps between (0 = always full clear).
+/// Returns the count actually applied.
+#[no_mangle]
+pub extern "C" fn bridge_engine_set_buffer_count(ctx: *mut BridgeContext, count: u32) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.set_buffer_count(count)
+}
+
+/// Fade the whole rendered frame (0.0 = black, 1.0 = opaque). Returns the alpha actually applied.
+#[no_mangle]
+pub extern "C" fn bridge_engine_set_global_alpha(ctx: *mut BridgeContext, alpha: f32) -> f32 {
+    if ctx.is_null() {
+        return 1.0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.set_global_alpha(alpha)
+}
+
+/// Set the stereo depth (0.0 = flat, 1.0 = full `stereo_depth` separation). Returns the value applied.
+#[no_mangle]
+pub extern "C" fn bridge_engine_set_stereo_depth(ctx: *mut BridgeContext, depth: f32) -> f32 {
+    if ctx.is_null() {
+        return 0.0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.set_stereo_depth(depth)
+}
+
+/// Override the background color with `rgb` (0xRRGGBB) when `enabled` != 0; 0 restores the
+/// movie's own stage color.
+#[no_mangle]
+pub extern "C" fn bridge_engine_set_background_color(ctx: *mut BridgeContext, enabled: u32, rgb: u32) {
+    if ctx.is_null() {
+        return;
+    }
+    let ctx = unsafe { &mut *ctx };
+    let color = (enabled != 0).then(|| Color {
+        r: (rgb >> 16) as u8,
+        g: (rgb >> 8) as u8,
+        b: rgb as u8,
+        a: 255,
+    });
+    ctx.engine.set_background_color(color);
+}
+
+/// Fill `out` with the root movie's frame rate, stage size, frame count and SWF version.
+/// Returns 1 on success.
+#[no_mangle]
+pub extern "C" fn bridge_engine_get_movie_info(ctx: *mut BridgeContext, out: *mut BridgeMovieInfo) -> u32 {
+    if ctx.is_null() || out.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    let info = ctx.engine.movie_info();
+    // Safety: caller provides a writable `bridge_movie_info_t`.
+    unsafe {
+        *out = BridgeMovieInfo {
+            frame_rate: info.frame_rate,
+            width: info.width,
+            height: info.height,
+            num_frames: info.num_frames as u32,
+            swf_version: info.swf_version as u32,
+        };
+    }
+    1
+}
+
+/// Frames per second measured from the `dt_ms` passed to `bridge_engine_tick`, averaged over the last
+/// ~60 ticks (0 until the first tick with a nonzero delta).
+#[no_mangle]
+pub extern "C" fn bridge_engine_get_fps(ctx: *mut BridgeContext) -> f32 {
+    if ctx.is_null() {
+        return 0.0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.fps()
+}
+
+/// Fill `out` with the numeric counters behind the status text. Returns 1 on success.
+#[no_mangle]
+pub extern "C" fn bridge_engine_get_diagnostics(ctx: *mut BridgeContext, out: *mut BridgeDiag) -> u32 {
+    if ctx.is_null() || out.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    let d = ctx.engine.diagnostics();
+    let (fps_min, fps_max) = ctx.engine.fps_range();
+    // Safety: caller provides a writable `bridge_diag_t`.
+    unsafe {
+        *out = BridgeDiag {
+            frame: ctx.engine.frame_counter(),
+            movie_loaded: d.movie_loaded as u32,
+            swf_version: d.swf_version as u32,
+            shapes_registered: d.shapes_registered,
+            bitmaps_registered: d.bitmaps_registered,
+            frames_submitted: d.frames_submitted,
+            frames_render_skipped: d.frames_render_skipped,
+            last_cmds_total: d.last_cmds_total,
+            last_cmds_shapes: d.last_cmds_shapes,
+            last_cmds_bitmaps: d.last_cmds_bitmaps,
+            last_cmds_other: d.last_cmds_other,
+            last_tris: d.last_tris,
+            tess_ms_fills: d.total_tess_ms_fills,
+            tess_ms_strokes: d.total_tess_ms_strokes,
+            tess_ms_max_shape: d.max_tess_ms_single_shape,
+            input_counter: d.input_counter,
+            fps: ctx.engine.fps(),
+            fps_min,
+            fps_max,
+        };
+    }
+    1
+}
+
+/// Restart the current movie in place (fresh player, cleared caches). Returns 1 on success;
+/// on failure the reason is available from `bridge_engine_last_error`.
+#[no_mangle]
+pub extern "C" fn bridge_engine_reload(ctx: *mut BridgeContext) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    match ctx.engine.reload() {
+        Ok(()) => 1,
+        Err(e) => {
+            set_last_error(e);
+            0
+        }
+    }
+}
+
+/// Pause (paused != 0) or resume playback; rendering and input continue while paused.
+#[no_mangle]
+pub extern "C" fn bridge_engine_set_paused(ctx: *mut BridgeContext, paused: u32) {
+    if ctx.is_null() {
+        return;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.set_paused(paused != 0);
+}
+
+/// Advance one frame while paused (rendered by the next `bridge_engine_tick`). Returns 0 if not paused.
+#[no_mangle]
+pub extern "C" fn bridge_engine_step(ctx: *mut BridgeContext) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.step_frame() as u32
+}
+
+/// Playback speed multiplier (clamped to 0.1..=8.0). Returns the value applied.
+#[no_mangle]
+pub extern "C" fn bridge_engine_set_speed(ctx: *mut BridgeContext, speed: f32) -> f32 {
+    if ctx.is_null() {
+        return 1.0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.set_speed(speed)
+}
+
+/// 1 while playback is paused.
+#[no_mangle]
+pub extern "C" fn bridge_engine_is_paused(ctx: *mut BridgeContext) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.is_paused() as u32
+}
+
+/// Set the master audio volume (clamped to 0.0..=1.0). Returns the value applied.
+#[no_mangle]
+pub extern "C" fn bridge_engine_set_volume(ctx: *mut BridgeContext, volume: f32) -> f32 {
+    if ctx.is_null() {
+        return 1.0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.set_master_volume(volume)
+}
+
+/// Mute (muted != 0) or unmute audio; the master volume is kept.
+#[no_mangle]
+pub extern "C" fn bridge_engine_set_muted(ctx: *mut BridgeContext, muted: u32) {
+    if ctx.is_null() {
+        return;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.set_muted(muted != 0);
+}
+
+/// Register `cb(user, initial, out, cap)` to show a text-entry dialog (swkbd) whenever a text
+/// field asks for the keyboard; pass NULL to unregister. Called from `bridge_engine_tick`.
+#[no_mangle]
+pub extern "C" fn bridge_engine_set_text_input_callback(ctx: *mut BridgeContext, cb: TextInputCallback, user: *mut c_void) {
+    if ctx.is_null() {
+        return;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.set_text_input_callback(cb, user);
+}
+
+/// Register `cb(user, url, target)` for `getURL` navigations; pass NULL to unregister.
+/// Called from `bridge_engine_tick`, after the player ticks.
+#[cfg(feature = "net")]
+#[no_mangle]
+pub extern "C" fn bridge_engine_set_navigate_callback(ctx: *mut BridgeContext, cb: NavigateCallback, user: *mut c_void) {
+    if ctx.is_null() {
+        return;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.set_navigate_callback(cb, user);
+}
+
+/// Register `cb(user, command, args)` for `fscommand:` (prefix stripped) and `javascript:`
+/// (full URL) navigations; pass NULL to unregister.
+#[cfg(feature = "net")]
+#[no_mangle]
+pub extern "C" fn bridge_engine_set_fscommand_callback(ctx: *mut BridgeContext, cb: NavigateCallback, user: *mut c_void) {
+    if ctx.is_null() {
+        return;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.set_fscommand_callback(cb, user);
+}
+
+/// 1 if stereo rendering is configured and the launcher should enable 3D mode.
+#[no_mangle]
+pub extern "C" fn bridge_engine_stereo_enabled(ctx: *mut BridgeContext) -> u32 {
+    if ctx.is_null() {
+        return 0;
+    }
+    let ctx = unsafe { &mut *ctx };
+    ctx.engine.stereo_enabled() as u32
+}
@@ -0,0 +1,71 @@
+//! Blocking HTTP(S) GET through libctru's httpc (implemented in `source/http_fetch.c`).
+
+use core::ffi::c_char;
+use std::ffi::CString;
+use std::sync::Mutex;
+
+extern "C" {
+    fn bridge_http_get(
+        url: *const c_char,
+        max_len: usize,
+        out_ptr: *mut *mut u8,
+        out_len: *mut usize,
+        out_status: *mut u32,
+        out_url: *mut c_char,
+        out_url_cap: usize,
+    ) -> i32;
+    fn bridge_free_file(ptr: *mut u8, len: usize);
+}
+
+/// httpc isn't reentrant; fetch threads take turns.
+static HTTP_LOCK: Mutex<()> = Mutex::new(());
+
+/// `bridge_http_get` code for a body larger than `max_len`.
+pub const HTTP_ERR_TOO_LARGE: i32 = -10;
+
+/// Size of the buffer receiving the post-redirect URL (matches the C side's URL buffer).
+const HTTP_URL_CAP: usize = 1024;
+
+/// GET `url`, following redirects. Returns the final status, the URL the body came from (the
+/// last `Location`, possibly relative; equal to `url` when there was no redirect) and the
+/// body, or the C error code.
+pub fn http_get(url: &str, max_len: usize) -> Result<(u16, String, Vec<u8>), i32> {
+    let c_url = CString::new(url).map_err(|_| -1)?;
+    let mut out_ptr: *mut u8 = core::ptr::null_mut();
+    let mut out_len: usize = 0;
+    let mut status: u32 = 0;
+    let mut final_url = [0u8; HTTP_URL_CAP];
+
+    let rc = {
+        let _guard = HTTP_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        unsafe {
+            bridge_http_get(
+                c_url.as_ptr(),
+                max_len,
+                &mut out_ptr,
+                &mut out_len,
+                &mut status,
+                final_url.as_mut_ptr() as *mut c_char,
+                final_url.len(),
+            )
+        }
+    };
+    if rc != 0 {
+        return Err(rc);
+    }
+
+    let bytes = if out_ptr.is_null() || out_len == 0 {
+        Vec::new()
+    } else {
+        unsafe { core::slice::from_raw_parts(out_ptr as *const u8, out_len) }.to_vec()
+    };
+    if !out_ptr.is_null() {
+        unsafe { bridge_free_file(out_ptr, out_len) };
+    }
+    let final_len = final_url.iter().position(|&b| b == 0).unwrap_or(final_url.len());
+    let final_url = match String::from_utf8_lossy(&final_url[..final_len]) {
+        u if u.is_empty() => url.to_string(),
+        u => u.into_owned(),
+    };
+    Ok((status.min(u16::MAX as u32) as u16, final_url, bytes))
+}
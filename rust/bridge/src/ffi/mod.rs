@@ -1,3 +1,9 @@
+#[cfg(feature = "audio")]
+pub mod audio;
 pub mod exports;
 pub mod fileio;
+#[cfg(feature = "net")]
+pub mod http;
+#[cfg(feature = "net")]
+pub mod soc;
 pub mod types;
@@ -0,0 +1,14 @@
+//! soc:U service bring-up for `std::net` (implemented in `source/net_soc.c`).
+
+use std::sync::OnceLock;
+
+extern "C" {
+    fn bridge_soc_init() -> i32;
+}
+
+static SOC_READY: OnceLock<bool> = OnceLock::new();
+
+/// Start soc:U on first use. Returns false if the service (or its 1 MiB buffer) is unavailable.
+pub fn ensure_soc() -> bool {
+    *SOC_READY.get_or_init(|| unsafe { bridge_soc_init() == 0 })
+}
@@ -1,4 +1,6 @@
-use core::ffi::c_char;
+use core::ffi::{c_char, c_void};
+
+use crate::util::text::trim_to;
 
 /// Copy a C string into a Rust `String`.
 pub fn cstr_to_string(ptr: *const c_char) -> Option<String> {
@@ -12,13 +14,15 @@ pub fn cstr_to_string(ptr: *const c_char) -> Option<String> {
 
 /// Write a Rust string into a C buffer (NUL-terminated).
 /// Returns the number of bytes written (excluding the final NUL).
+///
+/// Truncation backs off to a char boundary so the output never ends in a partial UTF-8 sequence.
 pub fn write_c_string(out: *mut c_char, cap: usize, s: &str) -> usize {
     if out.is_null() || cap == 0 {
         return 0;
     }
 
-    let bytes = s.as_bytes();
-    let n = bytes.len().min(cap.saturating_sub(1));
+    let bytes = trim_to(s, cap - 1).as_bytes();
+    let n = bytes.len();
 
     // Safety: caller provided writable memory for `cap` bytes.
     unsafe {
@@ -28,3 +32,90 @@ pub fn write_c_string(out: *mut c_char, cap: usize, s: &str) -> usize {
 
     n
 }
+
+/// Host callback for `getURL` navigations: `(user, url, target)`, both NUL-terminated UTF-8.
+///
+/// Also used for `fscommand:`/`javascript:` URLs, which go to a separate registration as
+/// `(user, command, args)`. Strings are only valid for the duration of the call.
+pub type NavigateCallback = Option<unsafe extern "C" fn(user: *mut c_void, url: *const c_char, target: *const c_char)>;
+
+/// Host text-entry callback: `(user, initial, out, cap) -> accepted`.
+///
+/// Writes NUL-terminated UTF-8 into `out` (at most `cap` bytes) and returns nonzero if the
+/// user confirmed; zero means cancelled. `initial` is only valid during the call.
+pub type TextInputCallback =
+    Option<unsafe extern "C" fn(user: *mut c_void, initial: *const c_char, out: *mut c_char, cap: usize) -> u32>;
+
+/// Movie header facts written by `bridge_engine_get_movie_info` (mirrors `bridge_movie_info_t`).
+#[repr(C)]
+pub struct BridgeMovieInfo {
+    pub frame_rate: f32,
+    pub width: u32,
+    pub height: u32,
+    pub num_frames: u32,
+    pub swf_version: u32,
+}
+
+/// Renderer counters written by `bridge_engine_get_diagnostics` (mirrors `bridge_diag_t`).
+///
+/// `last_*` cover the most recently submitted frame; `tess_*` are totals since the movie loaded.
+#[repr(C)]
+pub struct BridgeDiag {
+    pub frame: u64,
+    pub movie_loaded: u32,
+    pub swf_version: u32,
+    pub shapes_registered: u32,
+    pub bitmaps_registered: u32,
+    pub frames_submitted: u32,
+    pub frames_render_skipped: u32,
+    pub last_cmds_total: u32,
+    pub last_cmds_shapes: u32,
+    pub last_cmds_bitmaps: u32,
+    pub last_cmds_other: u32,
+    pub last_tris: u32,
+    pub tess_ms_fills: u64,
+    pub tess_ms_strokes: u64,
+    pub tess_ms_max_shape: u64,
+    pub input_counter: u64,
+    /// Same value as `bridge_engine_get_fps`, with the slowest/fastest tick in its window.
+    pub fps: f32,
+    pub fps_min: f32,
+    pub fps_max: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written(cap: usize, s: &str) -> (usize, Vec<u8>) {
+        let mut buf = vec![0x7Fu8; cap.max(1)];
+        let n = write_c_string(buf.as_mut_ptr() as *mut c_char, cap, s);
+        (n, buf)
+    }
+
+    #[test]
+    fn write_c_string_backs_off_split_utf8() {
+        // "é" is two bytes at offsets 3..5; a 5-byte buffer holds 4 bytes plus the NUL.
+        let name = "café.swf";
+        let (n, buf) = written(5, name);
+        assert_eq!(n, 3);
+        assert_eq!(&buf[..4], b"caf\0");
+
+        // One more byte fits the whole "é".
+        let (n, buf) = written(6, name);
+        assert_eq!(n, 5);
+        assert_eq!(&buf[..6], "café\0".as_bytes());
+    }
+
+    #[test]
+    fn write_c_string_fits_exactly() {
+        let (n, buf) = written(8, "ñandú");
+        assert_eq!(n, 7);
+        assert_eq!(&buf[..8], "ñandú\0".as_bytes());
+        let (n, buf) = written(7, "ñandú");
+        assert_eq!(n, 5);
+        assert_eq!(&buf[..6], "ñand\0".as_bytes());
+        assert_eq!(written(1, "é").0, 0);
+        assert_eq!(write_c_string(core::ptr::null_mut(), 8, "x"), 0);
+    }
+}
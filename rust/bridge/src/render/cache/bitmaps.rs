@@ -1,4 +1,7 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::runlog;
 
 /// A stable key for bitmap handles.
 ///
@@ -6,64 +9,286 @@ use std::collections::HashMap;
 /// lookups are O(1) and we don't need to depend on Ruffle internals here.
 pub type BitmapKey = usize;
 
+/// Default RGBA byte budget (launchers can override it via `set_budget`).
+const BITMAP_CACHE_BUDGET_BYTES: usize = 12 * 1024 * 1024;
+/// Lower clamp for `set_budget`; one full-screen 400x240 surface is ~375 KiB.
+const BITMAP_CACHE_MIN_BUDGET_BYTES: usize = 512 * 1024;
+
+/// How color channels in `BitmapSurface::rgba` relate to alpha.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlphaMode {
+    /// Color is independent of alpha; blend with `src*a + dst*(1-a)`.
+    Straight,
+    /// Color is already multiplied by alpha; blend with `src + dst*(1-a)`.
+    Premultiplied,
+}
+
 /// CPU-side bitmap surface in RGBA8.
 ///
 /// - `rgba` is row-major, 4 bytes per pixel (R,G,B,A).
-/// - Alpha is *straight* (not pre-multiplied). If upstream provides premultiplied
-///   data, blending will look darker; we'll correct once we confirm the format.
+/// - `alpha_mode` says whether color is premultiplied; devices pick the matching blend.
+/// - `source_width`/`source_height` are the bitmap's size as the movie sees it. They differ
+///   from `width`/`height` only when registration downscaled the pixels (`bitmap_max_dim`);
+///   transforms and fill matrices are in source texels, so UV math divides by these.
 #[derive(Clone, Debug)]
 pub struct BitmapSurface {
     pub width: u32,
     pub height: u32,
     pub rgba: Vec<u8>,
     pub is_opaque: bool,
+    pub alpha_mode: AlphaMode,
+    pub source_width: u32,
+    pub source_height: u32,
 }
 
 impl BitmapSurface {
     pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> Self {
-        Self { width, height, rgba, is_opaque: false }
+        Self {
+            width,
+            height,
+            rgba,
+            is_opaque: false,
+            alpha_mode: AlphaMode::Straight,
+            source_width: width,
+            source_height: height,
+        }
+    }
+
+    pub fn is_downscaled(&self) -> bool {
+        self.width != self.source_width || self.height != self.source_height
+    }
+
+    /// Box-filter the pixels down by an integer factor so neither side exceeds `max_dim`.
+    ///
+    /// No-op for `max_dim == 0`, surfaces already within the limit, or invalid surfaces.
+    /// Averaging keeps premultiplied data premultiplied and opaque data opaque.
+    pub fn downscale_to(&mut self, max_dim: u32) {
+        if max_dim == 0 || (self.width <= max_dim && self.height <= max_dim) || !self.is_valid() {
+            return;
+        }
+        let factor = self.width.max(self.height).div_ceil(max_dim) as usize;
+        let (sw, sh) = (self.width as usize, self.height as usize);
+        let (dw, dh) = (sw.div_ceil(factor), sh.div_ceil(factor));
+        let mut out = vec![0u8; dw * dh * 4];
+        for dy in 0..dh {
+            let y0 = dy * factor;
+            let y1 = (y0 + factor).min(sh);
+            for dx in 0..dw {
+                let x0 = dx * factor;
+                let x1 = (x0 + factor).min(sw);
+                let mut sum = [0u32; 4];
+                for y in y0..y1 {
+                    let row = &self.rgba[4 * (y * sw + x0)..4 * (y * sw + x1)];
+                    for px in row.chunks_exact(4) {
+                        for (acc, &v) in sum.iter_mut().zip(px) {
+                            *acc += v as u32;
+                        }
+                    }
+                }
+                let count = ((x1 - x0) * (y1 - y0)) as u32;
+                let o = 4 * (dy * dw + dx);
+                for (dst, acc) in out[o..o + 4].iter_mut().zip(sum) {
+                    *dst = ((acc + count / 2) / count) as u8;
+                }
+            }
+        }
+        self.rgba = out;
+        self.width = dw as u32;
+        self.height = dh as u32;
     }
 
     pub fn is_valid(&self) -> bool {
         let px = self.width as usize * self.height as usize;
         self.rgba.len() == px * 4
     }
+
+    /// Overwrite the `w`x`h` block at `(x, y)` with tightly packed RGBA8 `pixels` in this
+    /// surface's alpha mode.
+    ///
+    /// Returns false without touching anything when the block doesn't fit, the surface was
+    /// downscaled, or a premultiplied surface would receive non-premultiplied pixels; callers
+    /// then replace the whole surface instead.
+    pub fn write_region(&mut self, x: u32, y: u32, w: u32, h: u32, pixels: &[u8]) -> bool {
+        let fits = x.checked_add(w).is_some_and(|r| r <= self.width) && y.checked_add(h).is_some_and(|b| b <= self.height);
+        if !fits || w == 0 || h == 0 || self.is_downscaled() || pixels.len() != w as usize * h as usize * 4 {
+            return false;
+        }
+        let region_opaque = pixels.chunks_exact(4).all(|px| px[3] == 255);
+        if self.alpha_mode == AlphaMode::Premultiplied
+            && !region_opaque
+            && pixels.chunks_exact(4).any(|px| px[0] > px[3] || px[1] > px[3] || px[2] > px[3])
+        {
+            return false;
+        }
+        let stride = self.width as usize * 4;
+        let row_len = w as usize * 4;
+        for (row, src) in pixels.chunks_exact(row_len).enumerate() {
+            let start = (y as usize + row) * stride + x as usize * 4;
+            self.rgba[start..start + row_len].copy_from_slice(src);
+        }
+        // A translucent block clears opacity; an opaque one restores it only if the rest is opaque.
+        if !region_opaque {
+            self.is_opaque = false;
+        } else if !self.is_opaque {
+            self.is_opaque = self.rgba.chunks_exact(4).all(|px| px[3] == 255);
+        }
+        true
+    }
+}
+
+struct BitmapEntry {
+    surface: BitmapSurface,
+    last_used: AtomicU32,
+    /// Bumped on insert and on every `get_mut`, so command traces can tell when pixels changed.
+    revision: u64,
 }
 
 /// Cache of registered bitmaps.
 ///
-/// Step 3 will evolve this into a more explicit "upload" cache and later into
-/// a GPU texture cache (Citro2D/Citro3D).
+/// Surfaces are charged by `rgba.len()`; once the total passes the budget the least
+/// recently drawn surfaces are dropped. An evicted bitmap simply stops drawing (the
+/// backend reports `miss_bmp`) until Ruffle re-uploads it via `update_texture`.
 pub struct BitmapCache {
-    by_key: HashMap<BitmapKey, BitmapSurface>,
+    by_key: HashMap<BitmapKey, BitmapEntry>,
+    bytes_used: usize,
+    budget_bytes: usize,
+    lru_clock: AtomicU32,
+    evicted_entries: AtomicU32,
+    evicted_bytes: AtomicU32,
+    next_revision: u64,
 }
 
 impl BitmapCache {
     pub fn new() -> Self {
-        Self { by_key: HashMap::new() }
+        Self {
+            by_key: HashMap::new(),
+            bytes_used: 0,
+            budget_bytes: BITMAP_CACHE_BUDGET_BYTES,
+            lru_clock: AtomicU32::new(0),
+            evicted_entries: AtomicU32::new(0),
+            evicted_bytes: AtomicU32::new(0),
+            next_revision: 0,
+        }
     }
 
     pub fn clear(&mut self) {
         self.by_key.clear();
+        self.bytes_used = 0;
+    }
+
+    /// Change the byte budget and evict immediately if we're now over it.
+    ///
+    /// Returns the budget actually applied (clamped to a sane minimum).
+    pub fn set_budget(&mut self, bytes: usize) -> usize {
+        let budget = bytes.max(BITMAP_CACHE_MIN_BUDGET_BYTES);
+        self.budget_bytes = budget;
+        runlog::log_important(&format!(
+            "bitmap_cache_budget requested={} applied={} used={}",
+            bytes, budget, self.bytes_used
+        ));
+        self.evict_if_needed(None);
+        budget
+    }
+
+    fn next_clock(&self) -> u32 {
+        self.lru_clock.fetch_add(1, Ordering::Relaxed).wrapping_add(1)
     }
 
     pub fn insert(&mut self, key: BitmapKey, surface: BitmapSurface) {
-        self.by_key.insert(key, surface);
+        let bytes = surface.rgba.len();
+        if bytes > self.budget_bytes {
+            runlog::warn_line(&format!(
+                "bitmap_cache_oversize {}x{} bytes={} budget={}",
+                surface.width, surface.height, bytes, self.budget_bytes
+            ));
+        }
+        let entry = BitmapEntry { surface, last_used: AtomicU32::new(self.next_clock()), revision: self.take_revision() };
+        if let Some(prev) = self.by_key.insert(key, entry) {
+            self.bytes_used = self.bytes_used.saturating_sub(prev.surface.rgba.len());
+        }
+        self.bytes_used = self.bytes_used.saturating_add(bytes);
+        // The surface being registered is about to be drawn; never evict it on its own insert.
+        self.evict_if_needed(Some(key));
     }
 
+    /// Look up a surface and mark it as recently used.
     pub fn get(&self, key: BitmapKey) -> Option<&BitmapSurface> {
-        self.by_key.get(&key)
+        let entry = self.by_key.get(&key)?;
+        entry.last_used.store(self.next_clock(), Ordering::Relaxed);
+        Some(&entry.surface)
     }
 
     pub fn contains_key(&self, key: BitmapKey) -> bool {
         self.by_key.contains_key(&key)
     }
 
+    /// Mutable access; callers must not change the surface's byte size.
     pub fn get_mut(&mut self, key: BitmapKey) -> Option<&mut BitmapSurface> {
-        self.by_key.get_mut(&key)
+        let clock = self.next_clock();
+        let revision = self.take_revision();
+        let entry = self.by_key.get_mut(&key)?;
+        entry.last_used.store(clock, Ordering::Relaxed);
+        entry.revision = revision;
+        Some(&mut entry.surface)
+    }
+
+    /// Changes whenever `key`'s surface may have changed; `None` if it isn't cached.
+    pub fn revision(&self, key: BitmapKey) -> Option<u64> {
+        self.by_key.get(&key).map(|e| e.revision)
+    }
+
+    pub fn remove(&mut self, key: BitmapKey) {
+        if let Some(entry) = self.by_key.remove(&key) {
+            self.bytes_used = self.bytes_used.saturating_sub(entry.surface.rgba.len());
+        }
+    }
+
+    fn take_revision(&mut self) -> u64 {
+        self.next_revision += 1;
+        self.next_revision
     }
 
     pub fn len(&self) -> usize {
         self.by_key.len()
     }
+
+    /// `(bytes_used, budget_bytes, evicted_entries, evicted_bytes)`; `bytes_used` is the sum of
+    /// `rgba.len()` over the cached surfaces.
+    pub fn mem_stats(&self) -> (usize, usize, u32, u32) {
+        (
+            self.bytes_used,
+            self.budget_bytes,
+            self.evicted_entries.load(Ordering::Relaxed),
+            self.evicted_bytes.load(Ordering::Relaxed),
+        )
+    }
+
+    fn evict_if_needed(&mut self, keep: Option<BitmapKey>) {
+        let mut logged = false;
+        while self.bytes_used > self.budget_bytes {
+            let oldest_key = self
+                .by_key
+                .iter()
+                .filter(|(key, _)| Some(**key) != keep)
+                .min_by_key(|(_, entry)| entry.last_used.load(Ordering::Relaxed))
+                .map(|(key, _)| *key);
+            let Some(key) = oldest_key else {
+                break;
+            };
+            let Some(entry) = self.by_key.remove(&key) else {
+                break;
+            };
+            let bytes = entry.surface.rgba.len();
+            self.bytes_used = self.bytes_used.saturating_sub(bytes);
+            self.evicted_entries.fetch_add(1, Ordering::Relaxed);
+            self.evicted_bytes.fetch_add(bytes as u32, Ordering::Relaxed);
+            if !logged {
+                logged = true;
+                runlog::log_important(&format!(
+                    "bitmap_cache_evict key={} {}x{} bytes={} used={} budget={}",
+                    key, entry.surface.width, entry.surface.height, bytes, self.bytes_used, self.budget_bytes
+                ));
+            }
+        }
+    }
 }
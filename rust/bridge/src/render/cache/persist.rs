@@ -0,0 +1,368 @@
+//! On-disk format for warming the shape cache across launches.
+//!
+//! Shape keys are per-run handle addresses, so files are keyed by shape content hash
+//! instead (see `tessellate::shape_content_hash`). One file per movie holds, little-endian:
+//! a header (magic, version, salt, entry count) then per shape its hash, bounds, text flag,
+//! fill meshes (verts, indices, paint) and stroke meshes. Bitmap paints are never written:
+//! shapes with bitmap fills have no content hash.
+//!
+//! The salt covers the config that changes tessellation output, so editing `renderer.cfg`
+//! invalidates stale meshes instead of drawing them.
+//!
+//! Render command traces embed single shape records (`encode_shape`); those keep bitmap
+//! paints, since a trace carries the referenced bitmaps under the same keys.
+//!
+//! Design rule: this module contains no Ruffle types.
+
+use crate::render::cache::shapes::{FillMesh, FillPaint, GradientPaint, StrokeMesh, Vertex2, MAX_GRADIENT_STOPS};
+use crate::render::frame::{GradientSpread, Matrix2D, RectI};
+use crate::util::config;
+
+const MAGIC: &[u8; 4] = b"SHC1";
+const FORMAT_VERSION: u32 = 1;
+
+const PAINT_SOLID: u8 = 0;
+const PAINT_LINEAR: u8 = 1;
+const PAINT_RADIAL: u8 = 2;
+const PAINT_UNSUPPORTED: u8 = 3;
+/// Trace records only (see `encode_shape`).
+const PAINT_BITMAP: u8 = 4;
+
+/// Meshes for one persisted shape, waiting for a registration with the same content hash.
+pub struct WarmShape {
+    pub bounds: RectI,
+    pub fills: Vec<FillMesh>,
+    pub strokes: Vec<StrokeMesh>,
+    pub is_text: bool,
+}
+
+/// FNV-1a, matching the shape content hash; used for movie bytes and the config salt.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xCBF2_9CE4_8422_2325;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01B3);
+    }
+    hash
+}
+
+/// Hash of the config values that affect tessellation.
+pub fn config_salt() -> u64 {
+    let mut bytes = config::tess_max_scale().to_bits().to_le_bytes().to_vec();
+    for len in config::stroke_dash() {
+        bytes.extend_from_slice(&len.to_le_bytes());
+    }
+    fnv1a(&bytes)
+}
+
+pub struct Writer {
+    buf: Vec<u8>,
+    count: u32,
+    /// Bitmap paints hold per-run bitmap keys: only trace records may carry them.
+    bitmap_paints: bool,
+}
+
+impl Writer {
+    pub fn new(salt: u64) -> Self {
+        let mut buf = Vec::with_capacity(64 * 1024);
+        buf.extend_from_slice(MAGIC);
+        buf.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        buf.extend_from_slice(&salt.to_le_bytes());
+        // Entry count, patched in `finish`.
+        buf.extend_from_slice(&0u32.to_le_bytes());
+        Self { buf, count: 0, bitmap_paints: false }
+    }
+
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Append one shape; returns false (writing nothing) if a paint can't be persisted.
+    pub fn write_shape(&mut self, hash: u64, bounds: RectI, fills: &[FillMesh], strokes: &[StrokeMesh], is_text: bool) -> bool {
+        let start = self.buf.len();
+        self.u64(hash);
+        for v in [bounds.x, bounds.y, bounds.w, bounds.h] {
+            self.i32(v);
+        }
+        self.u8(is_text as u8);
+        self.u32(fills.len() as u32);
+        for fill in fills {
+            self.mesh(&fill.verts, &fill.indices);
+            if !self.paint(&fill.paint) {
+                self.buf.truncate(start);
+                return false;
+            }
+        }
+        self.u32(strokes.len() as u32);
+        for stroke in strokes {
+            self.mesh(&stroke.verts, &stroke.indices);
+            self.buf.extend_from_slice(&[stroke.r, stroke.g, stroke.b, stroke.closed as u8]);
+            self.f32(stroke.width);
+            self.u32(stroke.line.len() as u32);
+            for &(x, y) in &stroke.line {
+                self.f32(x);
+                self.f32(y);
+            }
+        }
+        self.count += 1;
+        true
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        self.buf[16..20].copy_from_slice(&self.count.to_le_bytes());
+        self.buf
+    }
+
+    fn mesh(&mut self, verts: &[Vertex2], indices: &[u16]) {
+        self.u32(verts.len() as u32);
+        for v in verts {
+            self.i32(v.x);
+            self.i32(v.y);
+        }
+        self.u32(indices.len() as u32);
+        for &i in indices {
+            self.buf.extend_from_slice(&i.to_le_bytes());
+        }
+    }
+
+    fn paint(&mut self, paint: &FillPaint) -> bool {
+        match paint {
+            FillPaint::SolidRGBA(r, g, b, a) => {
+                self.u8(PAINT_SOLID);
+                self.buf.extend_from_slice(&[*r, *g, *b, *a]);
+            }
+            FillPaint::LinearGradient(gradient) => {
+                self.u8(PAINT_LINEAR);
+                self.gradient(gradient);
+            }
+            FillPaint::RadialGradient { paint, focal_point } => {
+                self.u8(PAINT_RADIAL);
+                self.gradient(paint);
+                self.u8(focal_point.is_some() as u8);
+                self.f32(focal_point.unwrap_or(0.0));
+            }
+            FillPaint::Unsupported => self.u8(PAINT_UNSUPPORTED),
+            FillPaint::Bitmap { bitmap_id, bitmap_key, matrix, smoothing, repeating } => {
+                if !self.bitmap_paints {
+                    return false;
+                }
+                self.u8(PAINT_BITMAP);
+                self.buf.extend_from_slice(&bitmap_id.to_le_bytes());
+                self.u64(bitmap_key.map_or(u64::MAX, |key| key as u64));
+                for v in [matrix.a, matrix.b, matrix.c, matrix.d, matrix.tx, matrix.ty] {
+                    self.f32(v);
+                }
+                self.buf.extend_from_slice(&[*smoothing as u8, *repeating as u8]);
+            }
+        }
+        true
+    }
+
+    fn gradient(&mut self, gradient: &GradientPaint) {
+        let count = (gradient.stop_count as usize).min(MAX_GRADIENT_STOPS);
+        self.u8(count as u8);
+        for (ratio, rgba) in &gradient.stops[..count] {
+            self.u8(*ratio);
+            self.buf.extend_from_slice(rgba);
+        }
+        self.u8(match gradient.spread {
+            GradientSpread::Pad => 0,
+            GradientSpread::Reflect => 1,
+            GradientSpread::Repeat => 2,
+        });
+        let m = gradient.local_to_gradient;
+        for v in [m.a, m.b, m.c, m.d, m.tx, m.ty] {
+            self.f32(v);
+        }
+    }
+
+    fn u8(&mut self, v: u8) {
+        self.buf.push(v);
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn u64(&mut self, v: u64) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn i32(&mut self, v: i32) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn f32(&mut self, v: f32) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+}
+
+/// Parse a file written by `Writer`. `None` if it's truncated, corrupt, from another format
+/// version, or was written under a different config salt.
+pub fn decode(bytes: &[u8], salt: u64) -> Option<Vec<(u64, WarmShape)>> {
+    let mut r = Reader { bytes, pos: 0 };
+    if r.take(4)? != MAGIC || r.u32()? != FORMAT_VERSION || r.u64()? != salt {
+        return None;
+    }
+    let count = r.u32()? as usize;
+    let mut out = Vec::with_capacity(count.min(bytes.len() / 32));
+    for _ in 0..count {
+        out.push(r.shape()?);
+    }
+    Some(out)
+}
+
+/// One headerless shape record for a render command trace. Bitmap paints are kept.
+pub fn encode_shape(bounds: RectI, fills: &[FillMesh], strokes: &[StrokeMesh], is_text: bool) -> Vec<u8> {
+    let mut writer = Writer { buf: Vec::new(), count: 0, bitmap_paints: true };
+    writer.write_shape(0, bounds, fills, strokes, is_text);
+    writer.buf
+}
+
+/// Parse a record written by `encode_shape`; `None` if it's truncated or corrupt.
+pub fn decode_shape(bytes: &[u8]) -> Option<WarmShape> {
+    let mut r = Reader { bytes, pos: 0 };
+    let (_, shape) = r.shape()?;
+    (r.pos == bytes.len()).then_some(shape)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(n)?;
+        let slice = self.bytes.get(self.pos..end)?;
+        self.pos = end;
+        Some(slice)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        Some(i32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        Some(f32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    /// Element count that must fit in the remaining bytes at `elem_size` each, so a corrupt
+    /// count can't trigger a huge allocation.
+    fn count(&mut self, elem_size: usize) -> Option<usize> {
+        let n = self.u32()? as usize;
+        (n.checked_mul(elem_size)? <= self.bytes.len() - self.pos).then_some(n)
+    }
+
+    fn shape(&mut self) -> Option<(u64, WarmShape)> {
+        let hash = self.u64()?;
+        let bounds = RectI { x: self.i32()?, y: self.i32()?, w: self.i32()?, h: self.i32()? };
+        let is_text = self.u8()? != 0;
+        let fill_count = self.count(1)?;
+        let mut fills = Vec::with_capacity(fill_count);
+        for _ in 0..fill_count {
+            let (verts, indices) = self.mesh()?;
+            let paint = self.paint()?;
+            fills.push(FillMesh { verts, indices, paint });
+        }
+        let stroke_count = self.count(1)?;
+        let mut strokes = Vec::with_capacity(stroke_count);
+        for _ in 0..stroke_count {
+            let (verts, indices) = self.mesh()?;
+            let [red, green, blue, closed] = self.take(4)?.try_into().ok()?;
+            let width = self.f32()?;
+            let line_len = self.count(8)?;
+            let mut line = Vec::with_capacity(line_len);
+            for _ in 0..line_len {
+                line.push((self.f32()?, self.f32()?));
+            }
+            strokes.push(StrokeMesh { verts, indices, r: red, g: green, b: blue, line, width, closed: closed != 0 });
+        }
+        Some((hash, WarmShape { bounds, fills, strokes, is_text }))
+    }
+
+    fn mesh(&mut self) -> Option<(Vec<Vertex2>, Vec<u16>)> {
+        let vert_count = self.count(8)?;
+        let mut verts = Vec::with_capacity(vert_count);
+        for _ in 0..vert_count {
+            verts.push(Vertex2 { x: self.i32()?, y: self.i32()? });
+        }
+        let index_count = self.count(2)?;
+        let mut indices = Vec::with_capacity(index_count);
+        for _ in 0..index_count {
+            let index = u16::from_le_bytes(self.take(2)?.try_into().ok()?);
+            if index as usize >= vert_count {
+                return None;
+            }
+            indices.push(index);
+        }
+        Some((verts, indices))
+    }
+
+    fn paint(&mut self) -> Option<FillPaint> {
+        match self.u8()? {
+            PAINT_SOLID => {
+                let [r, g, b, a] = self.take(4)?.try_into().ok()?;
+                Some(FillPaint::SolidRGBA(r, g, b, a))
+            }
+            PAINT_LINEAR => Some(FillPaint::LinearGradient(self.gradient()?)),
+            PAINT_RADIAL => {
+                let paint = self.gradient()?;
+                let has_focal = self.u8()? != 0;
+                let focal = self.f32()?;
+                Some(FillPaint::RadialGradient { paint, focal_point: has_focal.then_some(focal) })
+            }
+            PAINT_UNSUPPORTED => Some(FillPaint::Unsupported),
+            PAINT_BITMAP => {
+                let bitmap_id = u16::from_le_bytes(self.take(2)?.try_into().ok()?);
+                let key = self.u64()?;
+                let matrix = self.matrix()?;
+                let [smoothing, repeating] = self.take(2)?.try_into().ok()?;
+                Some(FillPaint::Bitmap {
+                    bitmap_id,
+                    bitmap_key: (key != u64::MAX).then_some(key as usize),
+                    matrix,
+                    smoothing: smoothing != 0,
+                    repeating: repeating != 0,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn gradient(&mut self) -> Option<GradientPaint> {
+        let count = self.u8()? as usize;
+        if count > MAX_GRADIENT_STOPS {
+            return None;
+        }
+        let mut stops = [(0u8, [0u8; 4]); MAX_GRADIENT_STOPS];
+        for stop in stops.iter_mut().take(count) {
+            let ratio = self.u8()?;
+            *stop = (ratio, self.take(4)?.try_into().ok()?);
+        }
+        let spread = match self.u8()? {
+            0 => GradientSpread::Pad,
+            1 => GradientSpread::Reflect,
+            2 => GradientSpread::Repeat,
+            _ => return None,
+        };
+        let local_to_gradient = self.matrix()?;
+        Some(GradientPaint { stops, stop_count: count as u8, spread, local_to_gradient })
+    }
+
+    fn matrix(&mut self) -> Option<Matrix2D> {
+        Some(Matrix2D { a: self.f32()?, b: self.f32()?, c: self.f32()?, d: self.f32()?, tx: self.f32()?, ty: self.f32()? })
+    }
+}
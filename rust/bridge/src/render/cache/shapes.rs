@@ -1,24 +1,84 @@
 use std::collections::HashMap;
 use std::mem::size_of;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::Arc;
 
-use crate::render::frame::RectI;
+use crate::render::cache::persist::{self, WarmShape};
+use crate::render::frame::{GradientKind, GradientSpread, Matrix2D, RectI};
 use crate::runlog;
 
 pub type ShapeKey = usize;
 
+/// Default mesh byte budget (`shape_cache_kb` and launchers override it via `set_budget`).
+const SHAPE_CACHE_BUDGET_BYTES: usize = 8 * 1024 * 1024;
+/// Lower clamp for `set_budget`; below this almost every shape would thrash.
+const SHAPE_CACHE_MIN_BUDGET_BYTES: usize = 256 * 1024;
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Vertex2 {
     pub x: i32,
     pub y: i32,
 }
 
+/// SWF gradients carry at most 15 records; one spare keeps the array a round size.
+pub const MAX_GRADIENT_STOPS: usize = 16;
+
+/// Strokes at most this wide (in pixels) keep their polyline so they can be drawn as device lines.
+pub const HAIRLINE_MAX_PX: f32 = 2.0;
+
+/// Gradient fill converted at registration time (renderer-owned, no Ruffle types).
+#[derive(Clone, Copy, Debug)]
+pub struct GradientPaint {
+    /// `(ratio, rgba)` stops sorted by ratio; only the first `stop_count` are valid.
+    pub stops: [(u8, [u8; 4]); MAX_GRADIENT_STOPS],
+    pub stop_count: u8,
+    pub spread: GradientSpread,
+    /// Shape-local pixels → gradient space (`[-1, 1]²` square).
+    pub local_to_gradient: Matrix2D,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum FillPaint {
     SolidRGBA(u8, u8, u8, u8),
+    LinearGradient(GradientPaint),
+    /// `focal_point` is along gradient-space x (SWF `FocalGradient`); `None` is a centered radial.
+    RadialGradient { paint: GradientPaint, focal_point: Option<f32> },
+    /// Bitmap fill (SWF `FillStyle::Bitmap`).
+    ///
+    /// `bitmap_key` is the bitmap cache key, resolved by the backend after tessellation;
+    /// `None` means the bitmap could not be resolved and the fill draws as unsupported.
+    Bitmap {
+        bitmap_id: u16,
+        bitmap_key: Option<usize>,
+        /// Shape-local pixels → bitmap texels.
+        matrix: Matrix2D,
+        smoothing: bool,
+        /// Tile the bitmap past its edges instead of stretching the edge texels.
+        repeating: bool,
+    },
     Unsupported,
 }
 
+impl FillPaint {
+    /// Gradient kind, stops and focal point, if this paint is rasterized per pixel.
+    pub fn gradient(&self) -> Option<(GradientKind, &GradientPaint, f32)> {
+        match self {
+            FillPaint::LinearGradient(paint) => Some((GradientKind::Linear, paint, 0.0)),
+            FillPaint::RadialGradient { paint, focal_point } => Some((GradientKind::Radial, paint, focal_point.unwrap_or(0.0))),
+            _ => None,
+        }
+    }
+
+    /// True if the executor has a real draw path for this paint.
+    pub fn is_drawable(&self) -> bool {
+        match self {
+            FillPaint::SolidRGBA(..) => true,
+            FillPaint::Bitmap { bitmap_key, .. } => bitmap_key.is_some(),
+            _ => self.gradient().is_some(),
+        }
+    }
+}
+
 /// One fill mesh for a shape.
 ///
 /// Design rule: renderer-owned data only (no Ruffle types).
@@ -36,13 +96,21 @@ pub struct StrokeMesh {
     pub r: u8,
     pub g: u8,
     pub b: u8,
+    /// Source polyline in shape-local pixels; only kept for strokes up to `HAIRLINE_MAX_PX` wide.
+    ///
+    /// Thin strokes can round to degenerate triangles, so the executor draws them as lines instead.
+    pub line: Vec<(f32, f32)>,
+    /// Stroke width in shape-local pixels (0 for SWF hairlines).
+    pub width: f32,
+    pub closed: bool,
 }
 
 #[derive(Debug)]
 struct ShapeEntry {
     bounds: RectI,
-    fills: Vec<FillMesh>,
-    strokes: Vec<StrokeMesh>,
+    /// Shared with content-identical aliases (see `ShapeCache::alias_content`).
+    fills: Arc<Vec<FillMesh>>,
+    strokes: Arc<Vec<StrokeMesh>>,
     is_text: bool,
     /// True if the entire tessellation failed and we have no triangle mesh.
     tess_failed: bool,
@@ -50,9 +118,22 @@ struct ShapeEntry {
     tess_partial: bool,
     stroke_failed: bool,
     stroke_partial: bool,
+    /// Charged to the entry that tessellated the meshes; aliases are 0.
     bytes_estimate: usize,
     debug_id: u32,
     last_used: AtomicU32,
+    content_hash: Option<u64>,
+    pin: PinState,
+    /// Assigned on insert, never reused: command traces re-embed a key whose revision changed.
+    revision: u64,
+}
+
+/// Pinning and the consecutive-frame draw streak the backend's auto-pin heuristic reads.
+#[derive(Debug, Default)]
+struct PinState {
+    pinned: AtomicBool,
+    last_frame: AtomicU32,
+    streak: AtomicU32,
 }
 
 /// Cache of registered shapes.
@@ -61,6 +142,11 @@ struct ShapeEntry {
 /// Step 2A stores cached triangle meshes for **fills only**.
 pub struct ShapeCache {
     by_key: HashMap<ShapeKey, ShapeEntry>,
+    /// Content hash → the key whose entry owns those meshes.
+    by_content: HashMap<u64, ShapeKey>,
+    /// Placeholders waiting on the tessellation worker (key → debug id).
+    pending: HashMap<ShapeKey, u32>,
+    dedup_hits: AtomicU32,
     missing_fill_meshes: AtomicU32,
     invalid_fill_meshes: AtomicU32,
     bounds_fallbacks: AtomicU32,
@@ -72,13 +158,21 @@ pub struct ShapeCache {
     lru_clock: AtomicU32,
     evicted_entries: AtomicU32,
     evicted_bytes: AtomicU32,
+    /// Bytes of pinned entries; still part of `bytes_used`.
+    pinned_bytes: AtomicUsize,
+    /// Meshes loaded from a previous run, keyed by content hash, not yet claimed by a shape.
+    warm: HashMap<u64, WarmShape>,
+    warm_hits: u32,
+    next_revision: u64,
 }
 
 impl ShapeCache {
     pub fn new() -> Self {
-        const SHAPE_CACHE_BUDGET_BYTES: usize = 8 * 1024 * 1024;
         Self {
             by_key: HashMap::new(),
+            by_content: HashMap::new(),
+            pending: HashMap::new(),
+            dedup_hits: AtomicU32::new(0),
             missing_fill_meshes: AtomicU32::new(0),
             invalid_fill_meshes: AtomicU32::new(0),
             bounds_fallbacks: AtomicU32::new(0),
@@ -90,18 +184,41 @@ impl ShapeCache {
             lru_clock: AtomicU32::new(0),
             evicted_entries: AtomicU32::new(0),
             evicted_bytes: AtomicU32::new(0),
+            pinned_bytes: AtomicUsize::new(0),
+            warm: HashMap::new(),
+            warm_hits: 0,
+            next_revision: 0,
         }
     }
 
     pub fn clear(&mut self) {
         self.by_key.clear();
+        self.by_content.clear();
+        self.pending.clear();
         self.bytes_used = 0;
+        self.pinned_bytes.store(0, Ordering::Relaxed);
+        self.warm.clear();
+        self.warm_hits = 0;
     }
 
     pub fn len(&self) -> usize {
         self.by_key.len()
     }
 
+    /// Change the mesh byte budget and evict immediately if we're now over it.
+    ///
+    /// Returns the budget actually applied (clamped to a sane minimum).
+    pub fn set_budget(&mut self, bytes: usize) -> usize {
+        let budget = bytes.max(SHAPE_CACHE_MIN_BUDGET_BYTES);
+        self.budget_bytes = budget;
+        runlog::log_important(&format!(
+            "shape_cache_budget requested={} applied={} used={}",
+            bytes, budget, self.bytes_used
+        ));
+        self.evict_if_needed();
+        budget
+    }
+
     pub fn touch(&self, key: ShapeKey) {
         let clock = self.lru_clock.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
         if let Some(entry) = self.by_key.get(&key) {
@@ -109,12 +226,67 @@ impl ShapeCache {
         }
     }
 
+    /// Exclude `key` from eviction. Its bytes still count toward the budget.
+    ///
+    /// Returns false if the key isn't cached. Replacing the entry (re-registration or an async
+    /// tessellation result) drops the pin.
+    pub fn pin(&self, key: ShapeKey) -> bool {
+        let Some(entry) = self.by_key.get(&key) else {
+            return false;
+        };
+        if !entry.pin.pinned.swap(true, Ordering::Relaxed) {
+            self.pinned_bytes.fetch_add(entry.bytes_estimate, Ordering::Relaxed);
+        }
+        true
+    }
+
+    pub fn unpin(&self, key: ShapeKey) {
+        if let Some(entry) = self.by_key.get(&key) {
+            if entry.pin.pinned.swap(false, Ordering::Relaxed) {
+                self.pinned_bytes.fetch_sub(entry.bytes_estimate, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn is_pinned(&self, key: ShapeKey) -> bool {
+        self.by_key.get(&key).is_some_and(|e| e.pin.pinned.load(Ordering::Relaxed))
+    }
+
+    /// Record that `key` was drawn in frame `frame`; returns how many consecutive frames
+    /// (including this one) it has been drawn in. Repeat draws within a frame don't count.
+    pub fn note_drawn(&self, key: ShapeKey, frame: u32) -> u32 {
+        let Some(entry) = self.by_key.get(&key) else {
+            return 0;
+        };
+        let prev = entry.pin.last_frame.swap(frame, Ordering::Relaxed);
+        let streak = entry.pin.streak.load(Ordering::Relaxed);
+        let streak = if prev == frame && streak > 0 {
+            streak
+        } else if prev.wrapping_add(1) == frame {
+            streak.saturating_add(1)
+        } else {
+            1
+        };
+        entry.pin.streak.store(streak, Ordering::Relaxed);
+        streak
+    }
+
+    pub fn bytes_estimate(&self, key: ShapeKey) -> usize {
+        self.by_key.get(&key).map(|e| e.bytes_estimate).unwrap_or(0)
+    }
+
+    /// `(pinned entries, pinned bytes)`.
+    pub fn pinned_stats(&self) -> (usize, usize) {
+        let count = self.by_key.values().filter(|e| e.pin.pinned.load(Ordering::Relaxed)).count();
+        (count, self.pinned_bytes.load(Ordering::Relaxed))
+    }
+
     pub fn insert_bounds(&mut self, key: ShapeKey, bounds: RectI) {
         let clock = self.lru_clock.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
         let entry = ShapeEntry {
             bounds,
-            fills: Vec::new(),
-            strokes: Vec::new(),
+            fills: Arc::new(Vec::new()),
+            strokes: Arc::new(Vec::new()),
             is_text: false,
             tess_failed: false,
             tess_partial: false,
@@ -123,6 +295,9 @@ impl ShapeCache {
             bytes_estimate: 0,
             debug_id: 0,
             last_used: AtomicU32::new(clock),
+            content_hash: None,
+            pin: PinState::default(),
+            revision: 0,
         };
         self.insert_entry(key, entry);
     }
@@ -135,8 +310,8 @@ impl ShapeCache {
         let clock = self.lru_clock.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
         let entry = ShapeEntry {
             bounds,
-            fills: Vec::new(),
-            strokes: Vec::new(),
+            fills: Arc::new(Vec::new()),
+            strokes: Arc::new(Vec::new()),
             is_text: false,
             tess_failed: true,
             tess_partial: false,
@@ -145,6 +320,9 @@ impl ShapeCache {
             bytes_estimate: 0,
             debug_id: 0,
             last_used: AtomicU32::new(clock),
+            content_hash: None,
+            pin: PinState::default(),
+            revision: 0,
         };
         self.insert_entry(key, entry);
     }
@@ -171,8 +349,8 @@ impl ShapeCache {
         let clock = self.lru_clock.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
         let entry = ShapeEntry {
             bounds,
-            fills,
-            strokes: Vec::new(),
+            fills: Arc::new(fills),
+            strokes: Arc::new(Vec::new()),
             is_text: false,
             tess_failed: false,
             tess_partial: false,
@@ -181,6 +359,9 @@ impl ShapeCache {
             bytes_estimate,
             debug_id: 0,
             last_used: AtomicU32::new(clock),
+            content_hash: None,
+            pin: PinState::default(),
+            revision: 0,
         };
         self.insert_entry(key, entry);
     }
@@ -210,8 +391,8 @@ impl ShapeCache {
             let clock = self.lru_clock.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
             let entry = ShapeEntry {
                 bounds,
-                fills: Vec::new(),
-                strokes: Vec::new(),
+                fills: Arc::new(Vec::new()),
+                strokes: Arc::new(Vec::new()),
                 is_text,
                 tess_failed: true,
                 tess_partial: false,
@@ -220,6 +401,9 @@ impl ShapeCache {
                 bytes_estimate: 0,
                 debug_id,
                 last_used: AtomicU32::new(clock),
+                content_hash: None,
+                pin: PinState::default(),
+                revision: 0,
             };
             self.insert_entry(key, entry);
             return;
@@ -228,8 +412,8 @@ impl ShapeCache {
         let clock = self.lru_clock.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
         let entry = ShapeEntry {
             bounds,
-            fills,
-            strokes,
+            fills: Arc::new(fills),
+            strokes: Arc::new(strokes),
             is_text,
             tess_failed,
             tess_partial,
@@ -238,10 +422,132 @@ impl ShapeCache {
             bytes_estimate,
             debug_id,
             last_used: AtomicU32::new(clock),
+            content_hash: None,
+            pin: PinState::default(),
+            revision: 0,
         };
         self.insert_entry(key, entry);
     }
 
+    /// Remember that `key` holds the meshes for shape content `hash`.
+    pub fn index_content(&mut self, key: ShapeKey, hash: u64) {
+        if let Some(entry) = self.by_key.get_mut(&key) {
+            entry.content_hash = Some(hash);
+            self.by_content.insert(hash, key);
+        }
+    }
+
+    /// Register `key` as an alias of an already-tessellated shape with the same content hash.
+    ///
+    /// The meshes are shared (refcounted), so the alias costs no budget. Returns false on a miss.
+    pub fn alias_content(&mut self, key: ShapeKey, debug_id: u32, hash: u64) -> bool {
+        let Some(src_key) = self.by_content.get(&hash).copied() else {
+            return false;
+        };
+        let Some(src) = self.by_key.get(&src_key) else {
+            self.by_content.remove(&hash);
+            return false;
+        };
+        let clock = self.lru_clock.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
+        let entry = ShapeEntry {
+            bounds: src.bounds,
+            fills: Arc::clone(&src.fills),
+            strokes: Arc::clone(&src.strokes),
+            is_text: src.is_text,
+            tess_failed: src.tess_failed,
+            tess_partial: src.tess_partial,
+            stroke_failed: src.stroke_failed,
+            stroke_partial: src.stroke_partial,
+            bytes_estimate: 0,
+            debug_id,
+            last_used: AtomicU32::new(clock),
+            content_hash: None,
+            pin: PinState::default(),
+            revision: 0,
+        };
+        self.insert_entry(key, entry);
+        self.dedup_hits.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    /// Stage meshes from a persisted cache file; `adopt_warm` claims them on registration.
+    pub fn load_warm(&mut self, shapes: Vec<(u64, WarmShape)>) {
+        self.warm.extend(shapes);
+    }
+
+    /// Insert the persisted meshes for content `hash` under `key`, skipping tessellation.
+    ///
+    /// Returns false if nothing was persisted for that hash.
+    pub fn adopt_warm(&mut self, key: ShapeKey, debug_id: u32, hash: u64) -> bool {
+        let Some(shape) = self.warm.remove(&hash) else {
+            return false;
+        };
+        self.insert_meshes(key, debug_id, shape.bounds, shape.fills, false, false, shape.strokes, false, false, shape.is_text);
+        // An oversize drop inside `insert_meshes` leaves a failed placeholder; don't share it.
+        if !self.is_tess_failed(key) {
+            self.index_content(key, hash);
+        }
+        self.warm_hits = self.warm_hits.saturating_add(1);
+        true
+    }
+
+    /// `(adopted shapes, persisted shapes not registered yet)`.
+    pub fn warm_stats(&self) -> (u32, usize) {
+        (self.warm_hits, self.warm.len())
+    }
+
+    /// Serialize every content-indexed entry (plus unclaimed warm shapes) for the next launch.
+    ///
+    /// Stops adding shapes once the file passes `max_bytes`. Returns the file and shape count.
+    pub fn export_persisted(&self, salt: u64, max_bytes: usize) -> (Vec<u8>, usize) {
+        let mut writer = persist::Writer::new(salt);
+        let mut count = 0;
+        let live = self.by_content.iter().filter_map(|(hash, key)| {
+            let entry = self.by_key.get(key)?;
+            Some((*hash, entry.bounds, entry.fills.as_slice(), entry.strokes.as_slice(), entry.is_text))
+        });
+        let warm = self
+            .warm
+            .iter()
+            .map(|(hash, shape)| (*hash, shape.bounds, shape.fills.as_slice(), shape.strokes.as_slice(), shape.is_text));
+        for (hash, bounds, fills, strokes, is_text) in live.chain(warm) {
+            if writer.len() > max_bytes {
+                break;
+            }
+            if writer.write_shape(hash, bounds, fills, strokes, is_text) {
+                count += 1;
+            }
+        }
+        (writer.finish(), count)
+    }
+
+    pub fn dedup_hits(&self) -> u32 {
+        self.dedup_hits.load(Ordering::Relaxed)
+    }
+
+    /// Insert a bounds-only placeholder for a shape queued on the tessellation worker.
+    ///
+    /// The executor draws it as a bounds rect until `take_pending` succeeds and the worker
+    /// inserts the real meshes.
+    pub fn insert_pending(&mut self, key: ShapeKey, debug_id: u32, bounds: RectI) {
+        self.insert_bounds(key, bounds);
+        self.pending.insert(key, debug_id);
+    }
+
+    /// Claim a finished job's slot. False if the cache was cleared (or the key reused by a
+    /// newer shape) since the job was queued, in which case the meshes must be dropped.
+    pub fn take_pending(&mut self, key: ShapeKey, debug_id: u32) -> bool {
+        if self.pending.get(&key) != Some(&debug_id) {
+            return false;
+        }
+        self.pending.remove(&key);
+        true
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
     pub fn get_bounds(&self, key: ShapeKey) -> Option<RectI> {
         self.by_key.get(&key).map(|e| e.bounds)
     }
@@ -298,6 +604,17 @@ impl ShapeCache {
         self.by_key.get(&key).map(|e| e.is_text).unwrap_or(false)
     }
 
+    /// Changes whenever `key` is given new contents; `None` if it isn't cached.
+    pub fn revision(&self, key: ShapeKey) -> Option<u64> {
+        self.by_key.get(&key).map(|e| e.revision)
+    }
+
+    /// `key`'s bounds, meshes and text flag as one `persist::encode_shape` record.
+    pub fn encode_entry(&self, key: ShapeKey) -> Option<Vec<u8>> {
+        let e = self.by_key.get(&key)?;
+        Some(persist::encode_shape(e.bounds, &e.fills, &e.strokes, e.is_text))
+    }
+
     pub fn record_missing_fill_mesh(&self) {
         self.missing_fill_meshes.fetch_add(1, Ordering::Relaxed);
     }
@@ -358,28 +675,54 @@ fn estimate_mesh_bytes(fills: &[FillMesh], strokes: &[StrokeMesh]) -> usize {
     let stroke_bytes: usize = strokes
         .iter()
         .map(|mesh| {
-            mesh.verts.len() * size_of::<Vertex2>() + mesh.indices.len() * size_of::<u16>()
+            mesh.verts.len() * size_of::<Vertex2>()
+                + mesh.indices.len() * size_of::<u16>()
+                + mesh.line.len() * size_of::<(f32, f32)>()
         })
         .sum();
     fill_bytes + stroke_bytes
 }
 
 impl ShapeCache {
-    fn insert_entry(&mut self, key: ShapeKey, entry: ShapeEntry) {
+    fn insert_entry(&mut self, key: ShapeKey, mut entry: ShapeEntry) {
         let bytes_estimate = entry.bytes_estimate;
+        entry.revision = self.next_revision;
+        self.next_revision += 1;
+        // Whatever replaces a placeholder wins over the job still queued for it.
+        self.pending.remove(&key);
         if let Some(prev) = self.by_key.insert(key, entry) {
-            self.bytes_used = self.bytes_used.saturating_sub(prev.bytes_estimate);
+            self.release(key, prev);
         }
         self.bytes_used = self.bytes_used.saturating_add(bytes_estimate);
         self.evict_if_needed();
     }
 
+    /// Drop the entry for `key`, if any; draws fall back as for an unregistered shape.
+    pub fn remove(&mut self, key: ShapeKey) {
+        self.pending.remove(&key);
+        if let Some(prev) = self.by_key.remove(&key) {
+            self.release(key, prev);
+        }
+    }
+
+    /// Undo the accounting of an entry that left `by_key`.
+    fn release(&mut self, key: ShapeKey, prev: ShapeEntry) {
+        self.bytes_used = self.bytes_used.saturating_sub(prev.bytes_estimate);
+        if prev.pin.pinned.load(Ordering::Relaxed) {
+            self.pinned_bytes.fetch_sub(prev.bytes_estimate, Ordering::Relaxed);
+        }
+        self.forget_content(key, prev.content_hash);
+    }
+
     fn evict_if_needed(&mut self) {
         let mut logged = false;
         while self.bytes_used > self.budget_bytes {
             let mut oldest_key: Option<ShapeKey> = None;
             let mut oldest_used = u32::MAX;
             for (key, entry) in &self.by_key {
+                if entry.pin.pinned.load(Ordering::Relaxed) {
+                    continue;
+                }
                 let used = entry.last_used.load(Ordering::Relaxed);
                 if used < oldest_used {
                     oldest_used = used;
@@ -393,6 +736,7 @@ impl ShapeCache {
 
             if let Some(entry) = self.by_key.remove(&key) {
                 self.bytes_used = self.bytes_used.saturating_sub(entry.bytes_estimate);
+                self.forget_content(key, entry.content_hash);
                 self.evicted_entries.fetch_add(1, Ordering::Relaxed);
                 self.evicted_bytes.fetch_add(entry.bytes_estimate as u32, Ordering::Relaxed);
                 if !logged {
@@ -411,4 +755,16 @@ impl ShapeCache {
             }
         }
     }
+
+    /// Drop the content index entry if it still points at `key`.
+    ///
+    /// Live aliases keep their `Arc`s, so their meshes stay valid; new registrations just
+    /// stop matching until the content is tessellated again.
+    fn forget_content(&mut self, key: ShapeKey, hash: Option<u64>) {
+        if let Some(hash) = hash {
+            if self.by_content.get(&hash) == Some(&key) {
+                self.by_content.remove(&hash);
+            }
+        }
+    }
 }
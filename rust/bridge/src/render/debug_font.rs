@@ -0,0 +1,137 @@
+//! Tiny 3x5 bitmap font for on-device debug overlays.
+//!
+//! Design rule: device-agnostic; text is drawn as `fill_rect` runs so any `RenderDevice` can show it.
+
+use crate::render::device::RenderDevice;
+use crate::render::frame::RectI;
+
+pub const GLYPH_W: i32 = 3;
+pub const GLYPH_H: i32 = 5;
+
+/// Rows top→bottom, 3 bits per row (bit 2 = leftmost column).
+fn glyph_rows(ch: char) -> [u8; 5] {
+    match ch.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b011, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '=' => [0b000, 0b111, 0b000, 0b111, 0b000],
+        '%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+        '!' => [0b010, 0b010, 0b010, 0b000, 0b010],
+        '?' => [0b111, 0b001, 0b010, 0b000, 0b010],
+        '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+        '(' => [0b001, 0b010, 0b010, 0b010, 0b001],
+        ')' => [0b100, 0b010, 0b010, 0b010, 0b100],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        '\'' => [0b010, 0b010, 0b000, 0b000, 0b000],
+        _ => [0; 5],
+    }
+}
+
+/// Width in pixels of `text` drawn at `scale` (one blank column between glyphs).
+pub fn text_width(text: &str, scale: i32) -> i32 {
+    let n = text.chars().count() as i32;
+    if n == 0 {
+        return 0;
+    }
+    (n * (GLYPH_W + 1) - 1) * scale
+}
+
+/// Split `text` into lines no wider than `max_width` at `scale`, breaking at spaces and
+/// hard-splitting words that don't fit on a line of their own.
+pub fn wrap_lines(text: &str, max_width: i32, scale: i32) -> Vec<String> {
+    let max_chars = ((max_width / scale.max(1) + 1) / (GLYPH_W + 1)).max(1) as usize;
+    let mut out = Vec::new();
+    for para in text.lines() {
+        let mut line = String::new();
+        for word in para.split(' ') {
+            let mut word: Vec<char> = word.chars().collect();
+            while word.len() > max_chars {
+                if !line.is_empty() {
+                    out.push(core::mem::take(&mut line));
+                }
+                out.push(word.drain(..max_chars).collect());
+            }
+            let needed = line.chars().count() + usize::from(!line.is_empty()) + word.len();
+            if needed > max_chars && !line.is_empty() {
+                out.push(core::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.extend(word);
+        }
+        out.push(line);
+    }
+    out
+}
+
+/// Draw a single line of text at `(x, y)`; each lit row segment becomes one `fill_rect`.
+pub fn draw_text<D: RenderDevice>(device: &mut D, x: i32, y: i32, text: &str, scale: i32, r: u8, g: u8, b: u8) {
+    let scale = scale.max(1);
+    let mut pen_x = x;
+    for ch in text.chars() {
+        let rows = glyph_rows(ch);
+        for (row, bits) in rows.iter().enumerate() {
+            let mut col = 0;
+            while col < GLYPH_W {
+                if bits & (0b100 >> col) == 0 {
+                    col += 1;
+                    continue;
+                }
+                let start = col;
+                while col < GLYPH_W && bits & (0b100 >> col) != 0 {
+                    col += 1;
+                }
+                device.fill_rect(
+                    RectI {
+                        x: pen_x + start * scale,
+                        y: y + row as i32 * scale,
+                        w: (col - start) * scale,
+                        h: scale,
+                    },
+                    r,
+                    g,
+                    b,
+                );
+            }
+        }
+        pen_x += (GLYPH_W + 1) * scale;
+    }
+}
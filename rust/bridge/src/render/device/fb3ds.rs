@@ -1,10 +1,9 @@
 use crate::render::device::RenderDevice;
-use crate::render::frame::{ClearColor, ColorTransform, Matrix2D, RectI, TexVertex};
-use crate::render::cache::bitmaps::BitmapSurface;
+use crate::render::frame::{ClearColor, ColorTransform, DrawBlend, FixedVertex, GradientFill, Matrix2D, RectI, TexVertex, FIXED_VERTEX_SHIFT};
+use crate::render::cache::bitmaps::{AlphaMode, BitmapSurface};
 use crate::render::cache::shapes::Vertex2;
 
-#[cfg(debug_assertions)]
-use core::sync::atomic::{AtomicU32, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
 const AFFINE_FP_SHIFT: i64 = 16;
 const AFFINE_FP_HALF: i64 = 1 << (AFFINE_FP_SHIFT - 1);
@@ -49,10 +48,132 @@ pub fn take_affine_path_counts() -> (u32, u32, u32) {
 
 extern "C" {
     fn gfxGetFramebuffer(screen: i32, side: i32, width: *mut u16, height: *mut u16) -> *mut u8;
+    fn gfxIsWide() -> bool;
+    fn gfxGetScreenFormat(screen: i32) -> u32;
 }
 
 const GFX_TOP: i32 = 0;
 const GFX_LEFT: i32 = 0;
+const GFX_RIGHT: i32 = 1;
+/// `GSPGPU_FramebufferFormat` values we can draw into.
+const GSP_BGR8_OES: u32 = 1;
+const GSP_RGB565_OES: u32 = 2;
+
+static UNSUPPORTED_FORMAT_WARNED: AtomicBool = AtomicBool::new(false);
+static COVERAGE_ALLOC_WARNED: AtomicBool = AtomicBool::new(false);
+static NO_FRAMEBUFFER_WARNINGS: AtomicU32 = AtomicU32::new(0);
+/// Thick-line endpoints beyond this many pixels from the origin are dropped.
+const MAX_LINE_COORD: f32 = 8192.0;
+/// Subpixel bits for textured-triangle edge functions.
+const TEX_SUB_SHIFT: i64 = 4;
+const TEX_SUB_ONE: i64 = 1 << TEX_SUB_SHIFT;
+/// Snapped vertex bound (about 4M px); keeps edge products well inside i64.
+const TEX_FX_LIMIT: i64 = 1 << 26;
+
+/// Top-screen pixel layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PixelFormat {
+    /// 3 bytes per pixel, stored B, G, R (the default mode).
+    Bgr8,
+    /// 2 bytes per pixel, little-endian `rrrrrggg gggbbbbb`.
+    Rgb565,
+}
+
+impl PixelFormat {
+    fn from_gsp(format: u32) -> Option<Self> {
+        match format {
+            GSP_BGR8_OES => Some(PixelFormat::Bgr8),
+            GSP_RGB565_OES => Some(PixelFormat::Rgb565),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Bgr8 => 3,
+            PixelFormat::Rgb565 => 2,
+        }
+    }
+}
+
+#[inline(always)]
+fn pack_565(r: u8, g: u8, b: u8) -> u16 {
+    ((r as u16 >> 3) << 11) | ((g as u16 >> 2) << 5) | (b as u16 >> 3)
+}
+
+/// Expand to 8 bits per channel, replicating the top bits so white stays 255.
+#[inline(always)]
+fn unpack_565(v: u16) -> (u8, u8, u8) {
+    let r = ((v >> 11) & 0x1F) as u8;
+    let g = ((v >> 5) & 0x3F) as u8;
+    let b = (v & 0x1F) as u8;
+    ((r << 3) | (r >> 2), (g << 2) | (g >> 4), (b << 3) | (b >> 2))
+}
+
+/// Vertical extent of a triangle along the line `x = sx`, or `None` if the line misses it.
+fn tri_column_extent(v: &[(f32, f32); 3], sx: f32) -> Option<(f32, f32)> {
+    let mut lo = f32::MAX;
+    let mut hi = f32::MIN;
+    let mut hits = 0;
+    for e in 0..3 {
+        let (x0, y0) = v[e];
+        let (x1, y1) = v[(e + 1) % 3];
+        if x0 == x1 || sx < x0.min(x1) || sx > x0.max(x1) {
+            continue;
+        }
+        let y = y0 + (sx - x0) * (y1 - y0) / (x1 - x0);
+        lo = lo.min(y);
+        hi = hi.max(y);
+        hits += 1;
+    }
+    if hits >= 2 && hi > lo { Some((lo, hi)) } else { None }
+}
+
+/// Premultiplied texel back to straight color (fully transparent stays black).
+#[inline(always)]
+fn unpremultiply(px: [u8; 4]) -> [u8; 4] {
+    let a = px[3] as u16;
+    if a == 0 || a == 255 {
+        return px;
+    }
+    let un = |c: u8| ((c as u16 * 255 + a / 2) / a).min(255) as u8;
+    [un(px[0]), un(px[1]), un(px[2]), px[3]]
+}
+
+/// Straight-alpha blend of one channel: `src*a + dst*(1-a)`.
+#[inline(always)]
+fn blend_channel(src: u8, dst: u8, a: u16) -> u8 {
+    ((src as u16 * a + dst as u16 * (255 - a) + 127) / 255) as u8
+}
+
+/// Nearest texel along one axis of `size` texels. Clamped coordinates put the edge texel
+/// centers at 0 and 1; repeating ones wrap every texel-width cell of `t.rem_euclid(1.0)`.
+#[inline(always)]
+fn texel_nearest(t: f32, size: usize, repeat: bool) -> usize {
+    if repeat {
+        ((t.rem_euclid(1.0) * size as f32) as usize).min(size - 1)
+    } else {
+        (t.clamp(0.0, 1.0) * (size as f32 - 1.0)).round() as usize
+    }
+}
+
+/// The two texels around `t` along one axis, and the second one's 7-bit weight (7 bits keep
+/// the bilinear alpha-weighted sums inside u32). Same texel layout as `texel_nearest`.
+#[inline(always)]
+fn texel_pair(t: f32, size: usize, repeat: bool) -> (usize, usize, u32) {
+    if repeat {
+        let f = t.rem_euclid(1.0) * size as f32 - 0.5;
+        let i0 = f.floor();
+        let weight = ((f - i0) * 128.0) as u32;
+        let i0 = (i0 as isize).rem_euclid(size as isize) as usize;
+        (i0, (i0 + 1) % size, weight)
+    } else {
+        let f = t.clamp(0.0, 1.0) * (size as f32 - 1.0);
+        let i0 = f as usize;
+        (i0, (i0 + 1).min(size - 1), ((f - i0 as f32) * 128.0) as u32)
+    }
+}
 
 #[derive(Clone, Copy)]
 struct FbView {
@@ -60,6 +181,25 @@ struct FbView {
     w_mem: usize,
     h_mem: usize,
     scissor: Option<RectI>,
+    /// Diagonal-hatch solid spans (placeholder for unsupported fill paints).
+    hatch: bool,
+    /// Straight alpha for solid spans; 255 writes opaque.
+    alpha: u8,
+    /// How solid spans and textured texels combine with the framebuffer.
+    blend: DrawBlend,
+    /// Wrap texture coordinates instead of clamping them (repeating bitmap fills).
+    repeat: bool,
+    /// Anti-alias solid triangle edges.
+    edge_aa: bool,
+    /// Top screen is in 800px wide mode (pixels are half as wide as they are tall).
+    wide: bool,
+    format: PixelFormat,
+    /// `format.bytes_per_pixel()`, cached for the inner loops.
+    bpp: usize,
+    /// Shape-mask levels, one byte per pixel in framebuffer memory order; null when no mask is active.
+    coverage: *const u8,
+    /// Pixels are written only where `coverage` holds this level.
+    coverage_level: u8,
 }
 
 impl FbView {
@@ -68,27 +208,121 @@ impl FbView {
     #[inline(always)]
     fn disp_h(&self) -> usize { self.w_mem }
 
+    /// Whether the active shape mask lets the pixel at `p` through.
     #[inline(always)]
-    unsafe fn put_pixel(&self, x: i32, y: i32, r: u8, g: u8, b: u8) {
-        if x < 0 || y < 0 { return; }
+    unsafe fn covered(&self, p: *const u8) -> bool {
+        self.coverage.is_null() || *self.coverage.add((p as usize - self.ptr as usize) / self.bpp) == self.coverage_level
+    }
+
+    /// Write one opaque pixel at `p` in the surface format (skipped outside the shape mask).
+    #[inline(always)]
+    unsafe fn write_px(&self, p: *mut u8, r: u8, g: u8, b: u8) {
+        if !self.covered(p) {
+            return;
+        }
+        match self.format {
+            PixelFormat::Bgr8 => {
+                *p.add(0) = b;
+                *p.add(1) = g;
+                *p.add(2) = r;
+            }
+            PixelFormat::Rgb565 => {
+                // Byte-wise so the layout doesn't depend on host endianness or alignment.
+                let v = pack_565(r, g, b);
+                *p.add(0) = v as u8;
+                *p.add(1) = (v >> 8) as u8;
+            }
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn read_px(&self, p: *const u8) -> (u8, u8, u8) {
+        match self.format {
+            PixelFormat::Bgr8 => (*p.add(2), *p.add(1), *p.add(0)),
+            PixelFormat::Rgb565 => unpack_565(*p.add(0) as u16 | ((*p.add(1) as u16) << 8)),
+        }
+    }
+
+    /// Composite premultiplied `(r, g, b, a)` over the pixel at `p`: `src + dst*(1-a)`.
+    #[inline(always)]
+    unsafe fn blend_premul_px(&self, p: *mut u8, r: u8, g: u8, b: u8, a: u16) {
+        let (dr, dg, db) = self.read_px(p);
+        let inv = 255 - a;
+        let over = |s: u8, d: u8| (s as u16 + (d as u16 * inv + 127) / 255).min(255) as u8;
+        self.write_px(p, over(r, dr), over(g, dg), over(b, db));
+    }
+
+    /// Add `(r, g, b)` scaled by `a` to the pixel at `p`, saturating at 255.
+    #[inline(always)]
+    unsafe fn add_px(&self, p: *mut u8, r: u8, g: u8, b: u8, a: u16) {
+        let (dr, dg, db) = self.read_px(p);
+        let add = |s: u8, d: u8| (d as u16 + (s as u16 * a + 127) / 255).min(255) as u8;
+        self.write_px(p, add(r, dr), add(g, dg), add(b, db));
+    }
+
+    /// Multiply the pixel at `p` by `(r, g, b)`, with `a` fading the source toward white.
+    #[inline(always)]
+    unsafe fn mul_px(&self, p: *mut u8, r: u8, g: u8, b: u8, a: u16) {
+        let (dr, dg, db) = self.read_px(p);
+        let mul = |s: u8, d: u8| {
+            let s = 255 - ((255 - s as u16) * a + 127) / 255;
+            ((d as u16 * s + 127) / 255) as u8
+        };
+        self.write_px(p, mul(r, dr), mul(g, dg), mul(b, db));
+    }
+
+    /// Blend `(r, g, b)` over the pixel at `p` with straight alpha `a` (0..=255).
+    #[inline(always)]
+    unsafe fn blend_px(&self, p: *mut u8, r: u8, g: u8, b: u8, a: u16) {
+        let (dr, dg, db) = self.read_px(p);
+        self.write_px(p, blend_channel(r, dr, a), blend_channel(g, dg, a), blend_channel(b, db, a));
+    }
+
+    /// Draw `(r, g, b)` at `p` with coverage alpha `a`, honoring the current blend mode.
+    #[inline(always)]
+    unsafe fn shade_px(&self, p: *mut u8, r: u8, g: u8, b: u8, a: u16) {
+        match self.blend {
+            DrawBlend::Normal if a >= 255 => self.write_px(p, r, g, b),
+            DrawBlend::Normal => self.blend_px(p, r, g, b, a),
+            DrawBlend::Add => self.add_px(p, r, g, b, a),
+            DrawBlend::Multiply => self.mul_px(p, r, g, b, a),
+        }
+    }
+
+    /// Framebuffer pointer for display pixel `(x, y)`, or `None` outside the surface or scissor.
+    #[inline(always)]
+    unsafe fn pixel_ptr(&self, x: i32, y: i32) -> Option<*mut u8> {
+        if x < 0 || y < 0 { return None; }
         if let Some(scissor) = self.scissor {
             if x < scissor.x || y < scissor.y || x >= scissor.x + scissor.w || y >= scissor.y + scissor.h {
-                return;
+                return None;
             }
         }
         let x = x as usize;
         let y = y as usize;
-        if x >= self.disp_w() || y >= self.disp_h() { return; }
-        let idx = 3 * (x * self.w_mem + (self.w_mem - 1 - y));
-        let p = self.ptr.add(idx);
-        *p.add(0) = b;
-        *p.add(1) = g;
-        *p.add(2) = r;
+        if x >= self.disp_w() || y >= self.disp_h() { return None; }
+        Some(self.ptr.add(self.bpp * (x * self.w_mem + (self.w_mem - 1 - y))))
+    }
+
+    #[inline(always)]
+    unsafe fn put_pixel(&self, x: i32, y: i32, r: u8, g: u8, b: u8) {
+        if let Some(p) = self.pixel_ptr(x, y) {
+            self.write_px(p, r, g, b);
+        }
     }
 
     unsafe fn clear(&self, r: u8, g: u8, b: u8) {
         let count = self.w_mem * self.h_mem;
         let mut p = self.ptr;
+        if self.format == PixelFormat::Rgb565 {
+            let [lo, hi] = pack_565(r, g, b).to_le_bytes();
+            for _ in 0..count {
+                *p.add(0) = lo;
+                *p.add(1) = hi;
+                p = p.add(2);
+            }
+            return;
+        }
         for _ in 0..count {
             *p.add(0) = b;
             *p.add(1) = g;
@@ -97,6 +331,25 @@ impl FbView {
         }
     }
 
+    /// Scale every pixel by `alpha / 255` (blend toward black).
+    unsafe fn fade(&self, alpha: u8) {
+        let a = alpha as u16;
+        if self.format == PixelFormat::Rgb565 {
+            let mut p = self.ptr;
+            for _ in 0..self.w_mem * self.h_mem {
+                self.blend_px(p, 0, 0, 0, 255 - a);
+                p = p.add(2);
+            }
+            return;
+        }
+        let count = self.w_mem * self.h_mem * 3;
+        let mut p = self.ptr;
+        for _ in 0..count {
+            *p = ((*p as u16 * a + 127) / 255) as u8;
+            p = p.add(1);
+        }
+    }
+
     unsafe fn fill_rect(&self, x0: i32, y0: i32, w: i32, h: i32, r: u8, g: u8, b: u8) {
         if w <= 0 || h <= 0 { return; }
         let x1 = x0 + w;
@@ -119,7 +372,7 @@ impl FbView {
 
         // IMPORTANT (3DS framebuffer layout):
         // The top framebuffer is stored rotated. Our put_pixel mapping is:
-        //   idx = 3 * (x * w_mem + (w_mem - 1 - y))
+        //   idx = bpp * (x * w_mem + (w_mem - 1 - y))
         // So for a fixed x, varying y is contiguous in memory (reverse order).
         // Looping x outer + y inner is significantly faster than y outer + x inner.
 
@@ -130,8 +383,15 @@ impl FbView {
         for x in cx0..cx1 {
             // Start at y = cy1-1 so we can increment forward in memory.
             let start_col = (w_mem_i32 - cy1) as usize; // col = w_mem - 1 - (cy1-1)
-            let base = 3 * ((x as usize) * row_stride + start_col);
+            let base = self.bpp * ((x as usize) * row_stride + start_col);
             let mut p = self.ptr.add(base);
+            if self.format != PixelFormat::Bgr8 || !self.coverage.is_null() {
+                for _y in (cy0..cy1).rev() {
+                    self.write_px(p, r, g, b);
+                    p = p.add(self.bpp);
+                }
+                continue;
+            }
             for _y in (cy0..cy1).rev() {
                 *p.add(0) = b;
                 *p.add(1) = g;
@@ -174,7 +434,7 @@ impl FbView {
             if sx < 0 || sx >= src_w { continue; }
 
             let start_col = (w_mem_i32 - cy1) as usize;
-            let base = 3 * ((x as usize) * row_stride + start_col);
+            let base = self.bpp * ((x as usize) * row_stride + start_col);
             let mut p = self.ptr.add(base);
 
             for y in (cy0..cy1).rev() {
@@ -185,31 +445,16 @@ impl FbView {
                     let sg = src.rgba[si + 1];
                     let sb = src.rgba[si + 2];
                     let sa = src.rgba[si + 3];
-                    if src.is_opaque {
-                        *p.add(0) = sb;
-                        *p.add(1) = sg;
-                        *p.add(2) = sr;
-                    } else if sa == 255 {
-                        *p.add(0) = sb;
-                        *p.add(1) = sg;
-                        *p.add(2) = sr;
+                    if src.is_opaque || sa == 255 {
+                        self.write_px(p, sr, sg, sb);
+                    } else if src.alpha_mode == AlphaMode::Premultiplied {
+                        self.blend_premul_px(p, sr, sg, sb, sa as u16);
                     } else if sa != 0 {
                         // Straight-alpha blend: out = src*a + dst*(1-a)
-                        let inv = 255u16 - sa as u16;
-                        let db = *p.add(0) as u16;
-                        let dg = *p.add(1) as u16;
-                        let dr = *p.add(2) as u16;
-
-                        let ob = ((sb as u16 * sa as u16 + db * inv + 127) / 255) as u8;
-                        let og = ((sg as u16 * sa as u16 + dg * inv + 127) / 255) as u8;
-                        let or = ((sr as u16 * sa as u16 + dr * inv + 127) / 255) as u8;
-
-                        *p.add(0) = ob;
-                        *p.add(1) = og;
-                        *p.add(2) = or;
+                        self.blend_px(p, sr, sg, sb, sa as u16);
                     }
                 }
-                p = p.add(3);
+                p = p.add(self.bpp);
             }
         }
     }
@@ -228,6 +473,63 @@ impl FbView {
         }
     }
 
+    /// Texel nearest to `(u, v)`, clamped to the bitmap edges or wrapped when `repeat` is set.
+    #[inline(always)]
+    fn sample_nearest(src: &BitmapSurface, u: f32, v: f32, repeat: bool) -> [u8; 4] {
+        let sx = texel_nearest(u, src.width as usize, repeat);
+        let sy = texel_nearest(v, src.height as usize, repeat);
+        let si = 4 * (sy * (src.width as usize) + sx);
+        [src.rgba[si], src.rgba[si + 1], src.rgba[si + 2], src.rgba[si + 3]]
+    }
+
+    /// Bilinear blend of the four texels around `(u, v)`, clamped to the bitmap edges or
+    /// wrapped when `repeat` is set (so tile seams blend across the opposite edge).
+    ///
+    /// Straight-alpha color is weighted by alpha so fully transparent texels don't bleed their
+    /// RGB into edges. Premultiplied texels already carry that weighting and average directly.
+    /// The result stays in the source's alpha mode.
+    #[inline(always)]
+    fn sample_bilinear(src: &BitmapSurface, u: f32, v: f32, repeat: bool) -> [u8; 4] {
+        let w = src.width as usize;
+        let (x0, x1, wx) = texel_pair(u, w, repeat);
+        let (y0, y1, wy) = texel_pair(v, src.height as usize, repeat);
+        let taps = [
+            (y0 * w + x0, (128 - wx) * (128 - wy)),
+            (y0 * w + x1, wx * (128 - wy)),
+            (y1 * w + x0, (128 - wx) * wy),
+            (y1 * w + x1, wx * wy),
+        ];
+        let mut acc_a = 0u32;
+        let mut acc = [0u32; 3];
+        if src.alpha_mode == AlphaMode::Premultiplied {
+            let mut acc4 = [0u32; 4];
+            for (idx, weight) in taps {
+                let si = 4 * idx;
+                for c in 0..4 {
+                    acc4[c] += src.rgba[si + c] as u32 * weight;
+                }
+            }
+            return acc4.map(|v| ((v + (1 << 13)) >> 14) as u8);
+        }
+        for (idx, weight) in taps {
+            let si = 4 * idx;
+            let aw = src.rgba[si + 3] as u32 * weight;
+            acc_a += aw;
+            for c in 0..3 {
+                acc[c] += src.rgba[si + c] as u32 * aw;
+            }
+        }
+        if acc_a == 0 {
+            return [0, 0, 0, 0];
+        }
+        [
+            ((acc[0] + acc_a / 2) / acc_a) as u8,
+            ((acc[1] + acc_a / 2) / acc_a) as u8,
+            ((acc[2] + acc_a / 2) / acc_a) as u8,
+            ((acc_a + (1 << 13)) >> 14) as u8,
+        ]
+    }
+
     unsafe fn draw_triangle_textured(
         &self,
         v0: TexVertex,
@@ -235,7 +537,11 @@ impl FbView {
         v2: TexVertex,
         src: &BitmapSurface,
         color_transform: Option<ColorTransform>,
+        bilinear: bool,
     ) {
+        if src.width == 0 || src.height == 0 {
+            return;
+        }
         let (minx, maxx) = (v0.x.min(v1.x.min(v2.x)), v0.x.max(v1.x.max(v2.x)));
         let (miny, maxy) = (v0.y.min(v1.y.min(v2.y)), v0.y.max(v1.y.max(v2.y)));
         let mut ix0 = minx.floor() as i32;
@@ -259,82 +565,159 @@ impl FbView {
             iy1 = iy1.min(scissor.y + scissor.h - 1);
         }
 
+        // Edge functions in fixed point (1/16 px vertices), stepped by constants per pixel.
+        let snap = |c: f32| ((c * TEX_SUB_ONE as f32).round() as i64).clamp(-TEX_FX_LIMIT, TEX_FX_LIMIT);
+        let (x0f, y0f) = (snap(v0.x), snap(v0.y));
+        let (x1f, y1f) = (snap(v1.x), snap(v1.y));
+        let (x2f, y2f) = (snap(v2.x), snap(v2.y));
+        if (x1f - x0f) * (y2f - y0f) - (y1f - y0f) * (x2f - x0f) == 0 {
+            return;
+        }
+        // E(p) = (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x) for edge a -> b.
+        let edge = |ax: i64, ay: i64, bx: i64, by: i64, px: i64, py: i64| (bx - ax) * (py - ay) - (by - ay) * (px - ax);
+        // Per-pixel deltas: one column right (+x), one row up (-y, the inner loop direction).
+        let e0_dx = -(y1f - y0f) * TEX_SUB_ONE;
+        let e1_dx = -(y2f - y1f) * TEX_SUB_ONE;
+        let e2_dx = -(y0f - y2f) * TEX_SUB_ONE;
+        let e0_dy = -(x1f - x0f) * TEX_SUB_ONE;
+        let e1_dy = -(x2f - x1f) * TEX_SUB_ONE;
+        let e2_dy = -(x0f - x2f) * TEX_SUB_ONE;
+
+        // u/v are affine in screen space; solve the plane once and step it along each span.
         let area = (v1.x - v0.x) * (v2.y - v0.y) - (v1.y - v0.y) * (v2.x - v0.x);
         if area.abs() <= f32::EPSILON {
             return;
         }
         let inv_area = 1.0 / area;
+        let (e1x, e1y) = (v1.x - v0.x, v1.y - v0.y);
+        let (e2x, e2y) = (v2.x - v0.x, v2.y - v0.y);
+        let (du1, du2) = (v1.u - v0.u, v2.u - v0.u);
+        let (dv1, dv2) = (v1.v - v0.v, v2.v - v0.v);
+        let u_dx = (du1 * e2y - du2 * e1y) * inv_area;
+        let u_dy = (du2 * e1x - du1 * e2x) * inv_area;
+        let v_dx = (dv1 * e2y - dv2 * e1y) * inv_area;
+        let v_dy = (dv2 * e1x - dv1 * e2x) * inv_area;
+        let u_base = v0.u - u_dx * v0.x - u_dy * v0.y;
+        let v_base = v0.v - v_dx * v0.x - v_dy * v0.y;
+
+        // Color transforms act on straight color, so premultiplied texels are
+        // converted first; untransformed ones keep the cheaper premultiplied blend.
+        let premultiplied = src.alpha_mode == AlphaMode::Premultiplied && color_transform.is_none();
+        let unpremultiply_first = src.alpha_mode == AlphaMode::Premultiplied && color_transform.is_some();
+        let opaque = src.is_opaque && color_transform.is_none();
 
         let w_mem_i32 = self.w_mem as i32;
         let row_stride = self.w_mem;
 
+        // Pixel center of the first visited pixel: column ix0, row iy1.
+        let py_top = iy1 as i64 * TEX_SUB_ONE + TEX_SUB_ONE / 2;
+        let px_left = ix0 as i64 * TEX_SUB_ONE + TEX_SUB_ONE / 2;
+        let mut c0 = edge(x0f, y0f, x1f, y1f, px_left, py_top);
+        let mut c1 = edge(x1f, y1f, x2f, y2f, px_left, py_top);
+        let mut c2 = edge(x2f, y2f, x0f, y0f, px_left, py_top);
+
         for x in ix0..=ix1 {
             let start_col = (w_mem_i32 - (iy1 + 1)) as usize;
-            let base = 3 * ((x as usize) * row_stride + start_col);
+            let base = self.bpp * ((x as usize) * row_stride + start_col);
             let mut p = self.ptr.add(base);
-            for y in (iy0..=iy1).rev() {
-                let px = x as f32 + 0.5;
-                let py = y as f32 + 0.5;
-
-                let w0 = (v1.x - v0.x) * (py - v0.y) - (v1.y - v0.y) * (px - v0.x);
-                let w1 = (v2.x - v1.x) * (py - v1.y) - (v2.y - v1.y) * (px - v1.x);
-                let w2 = (v0.x - v2.x) * (py - v2.y) - (v0.y - v2.y) * (px - v2.x);
-
-                if (w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0) || (w0 <= 0.0 && w1 <= 0.0 && w2 <= 0.0) {
-                    let l0 = ((v1.x - px) * (v2.y - py) - (v1.y - py) * (v2.x - px)) * inv_area;
-                    let l1 = ((v2.x - px) * (v0.y - py) - (v2.y - py) * (v0.x - px)) * inv_area;
-                    let l2 = 1.0 - l0 - l1;
-
-                    let u = v0.u * l0 + v1.u * l1 + v2.u * l2;
-                    let v = v0.v * l0 + v1.v * l1 + v2.v * l2;
-                    let sx = (u.clamp(0.0, 1.0) * (src.width as f32 - 1.0)).round() as i32;
-                    let sy = (v.clamp(0.0, 1.0) * (src.height as f32 - 1.0)).round() as i32;
-
-                    if sx >= 0 && sy >= 0 && sx < src.width as i32 && sy < src.height as i32 {
-                        let si = 4 * ((sy as usize) * (src.width as usize) + (sx as usize));
-                        let tex = [
-                            src.rgba[si + 0],
-                            src.rgba[si + 1],
-                            src.rgba[si + 2],
-                            src.rgba[si + 3],
-                        ];
-                        let tex = FbView::apply_color_transform(tex, color_transform);
-                        let sr = tex[0];
-                        let sg = tex[1];
-                        let sb = tex[2];
-                        let sa = tex[3];
-
-                        if src.is_opaque && color_transform.is_none() {
-                            *p.add(0) = sb;
-                            *p.add(1) = sg;
-                            *p.add(2) = sr;
-                        } else if sa == 255 {
-                            *p.add(0) = sb;
-                            *p.add(1) = sg;
-                            *p.add(2) = sr;
-                        } else if sa != 0 {
-                            let inv = 255u16 - sa as u16;
-                            let db = *p.add(0) as u16;
-                            let dg = *p.add(1) as u16;
-                            let dr = *p.add(2) as u16;
-
-                            let ob = ((sb as u16 * sa as u16 + db * inv + 127) / 255) as u8;
-                            let og = ((sg as u16 * sa as u16 + dg * inv + 127) / 255) as u8;
-                            let or = ((sr as u16 * sa as u16 + dr * inv + 127) / 255) as u8;
-
-                            *p.add(0) = ob;
-                            *p.add(1) = og;
-                            *p.add(2) = or;
+            let (mut w0, mut w1, mut w2) = (c0, c1, c2);
+            // Restart u/v from the plane each column so float drift stays within one span.
+            let px = x as f32 + 0.5;
+            let py = iy1 as f32 + 0.5;
+            let mut u = u_base + u_dx * px + u_dy * py;
+            let mut v = v_base + v_dx * px + v_dy * py;
+            for _ in (iy0..=iy1).rev() {
+                if (w0 >= 0 && w1 >= 0 && w2 >= 0) || (w0 <= 0 && w1 <= 0 && w2 <= 0) {
+                    let tex = if bilinear {
+                        FbView::sample_bilinear(src, u, v, self.repeat)
+                    } else {
+                        FbView::sample_nearest(src, u, v, self.repeat)
+                    };
+                    let tex = if unpremultiply_first {
+                        FbView::apply_color_transform(unpremultiply(tex), color_transform)
+                    } else {
+                        FbView::apply_color_transform(tex, color_transform)
+                    };
+                    let sr = tex[0];
+                    let sg = tex[1];
+                    let sb = tex[2];
+                    let sa = tex[3];
+
+                    if self.blend != DrawBlend::Normal {
+                        // Premultiplied texels already carry their alpha; straighten them here.
+                        let (sr, sg, sb) = if premultiplied && self.blend == DrawBlend::Multiply {
+                            let t = unpremultiply([sr, sg, sb, sa]);
+                            (t[0], t[1], t[2])
+                        } else {
+                            (sr, sg, sb)
+                        };
+                        match self.blend {
+                            DrawBlend::Add => self.add_px(p, sr, sg, sb, if premultiplied { 255 } else { sa as u16 }),
+                            _ => self.mul_px(p, sr, sg, sb, sa as u16),
                         }
+                    } else if opaque || sa == 255 {
+                        self.write_px(p, sr, sg, sb);
+                    } else if premultiplied {
+                        self.blend_premul_px(p, sr, sg, sb, sa as u16);
+                    } else if sa != 0 {
+                        self.blend_px(p, sr, sg, sb, sa as u16);
                     }
                 }
-                p = p.add(3);
+                w0 += e0_dy;
+                w1 += e1_dy;
+                w2 += e2_dy;
+                u -= u_dy;
+                v -= v_dy;
+                p = p.add(self.bpp);
             }
+            c0 += e0_dx;
+            c1 += e1_dx;
+            c2 += e2_dx;
         }
     }
 }
 
 
+/// Per-channel RGBA as an affine function of screen position, solved from one triangle's
+/// barycentric setup (same edge terms as `draw_triangle_textured`).
+struct GouraudPlane {
+    base: [f32; 4],
+    dx: [f32; 4],
+    dy: [f32; 4],
+}
+
+impl GouraudPlane {
+    fn new(v: [Vertex2; 3], c: [[u8; 4]; 3]) -> Option<Self> {
+        let (ax, ay) = (v[0].x as f32, v[0].y as f32);
+        let (e1x, e1y) = (v[1].x as f32 - ax, v[1].y as f32 - ay);
+        let (e2x, e2y) = (v[2].x as f32 - ax, v[2].y as f32 - ay);
+        let area = e1x * e2y - e1y * e2x;
+        if area.abs() <= f32::EPSILON {
+            return None;
+        }
+        let inv_area = 1.0 / area;
+        let mut plane = GouraudPlane { base: [0.0; 4], dx: [0.0; 4], dy: [0.0; 4] };
+        for k in 0..4 {
+            let c0 = c[0][k] as f32;
+            let d1 = c[1][k] as f32 - c0;
+            let d2 = c[2][k] as f32 - c0;
+            plane.dx[k] = (d1 * e2y - d2 * e1y) * inv_area;
+            plane.dy[k] = (d2 * e1x - d1 * e2x) * inv_area;
+            plane.base[k] = c0 - plane.dx[k] * ax - plane.dy[k] * ay;
+        }
+        Some(plane)
+    }
+
+    #[inline(always)]
+    fn at(&self, x: f32, y: f32) -> [f32; 4] {
+        let mut out = [0.0; 4];
+        for k in 0..4 {
+            out[k] = self.base[k] + self.dx[k] * x + self.dy[k] * y;
+        }
+        out
+    }
+}
+
 // -----------------------------
 // Triangle rasterization (opaque solid) + optional wireframe
 // -----------------------------
@@ -344,27 +727,85 @@ impl FbView {
 // For performance, the solid fill uses an x-major scan (vertical spans).
 
 impl FbView {
+    /// Clip a vertical span against the surface and scissor.
+    ///
+    /// Returns the framebuffer pointer for row `cy1 - 1` plus the clipped `[cy0, cy1)`;
+    /// walking forward in memory visits rows from `cy1 - 1` down to `cy0`.
     #[inline(always)]
-    unsafe fn fill_col_span(&self, x: i32, y0: i32, y1_excl: i32, r: u8, g: u8, b: u8) {
-        if x < 0 || x >= self.disp_w() as i32 { return; }
+    unsafe fn col_span_ptr(&self, x: i32, y0: i32, y1_excl: i32) -> Option<(*mut u8, i32, i32)> {
+        if x < 0 || x >= self.disp_w() as i32 { return None; }
         let mut cy0 = y0.max(0);
         let mut cy1 = y1_excl.min(self.disp_h() as i32);
         if let Some(scissor) = self.scissor {
             if x < scissor.x || x >= scissor.x + scissor.w {
-                return;
+                return None;
             }
             cy0 = cy0.max(scissor.y);
             cy1 = cy1.min(scissor.y + scissor.h);
         }
-        if cy1 <= cy0 { return; }
+        if cy1 <= cy0 { return None; }
 
         let w_mem_i32 = self.w_mem as i32;
         let row_stride = self.w_mem; // pixels
 
         // Start at y=cy1-1 so we can increment forward in memory.
         let start_col = (w_mem_i32 - cy1) as usize;
-        let base = 3 * ((x as usize) * row_stride + start_col);
-        let mut p = self.ptr.add(base);
+        let base = self.bpp * ((x as usize) * row_stride + start_col);
+        Some((self.ptr.add(base), cy0, cy1))
+    }
+
+    #[inline(always)]
+    unsafe fn fill_col_span(&self, x: i32, y0: i32, y1_excl: i32, r: u8, g: u8, b: u8) {
+        let Some((mut p, cy0, cy1)) = self.col_span_ptr(x, y0, y1_excl) else { return; };
+        if self.hatch {
+            // Alternate the base color with a 3/4-darkened shade in 4px diagonal bands.
+            let (dr, dg, db) = (r - (r >> 2), g - (g >> 2), b - (b >> 2));
+            for y in (cy0..cy1).rev() {
+                if ((x + y) & 4) != 0 {
+                    self.write_px(p, dr, dg, db);
+                } else {
+                    self.write_px(p, r, g, b);
+                }
+                p = p.add(self.bpp);
+            }
+            return;
+        }
+        if self.blend != DrawBlend::Normal {
+            let a = self.alpha as u16;
+            for _ in (cy0..cy1).rev() {
+                if self.blend == DrawBlend::Add {
+                    self.add_px(p, r, g, b, a);
+                } else {
+                    self.mul_px(p, r, g, b, a);
+                }
+                p = p.add(self.bpp);
+            }
+            return;
+        }
+        if self.format != PixelFormat::Bgr8 || !self.coverage.is_null() {
+            for _ in (cy0..cy1).rev() {
+                if self.alpha != 255 {
+                    self.blend_px(p, r, g, b, self.alpha as u16);
+                } else {
+                    self.write_px(p, r, g, b);
+                }
+                p = p.add(self.bpp);
+            }
+            return;
+        }
+        if self.alpha != 255 {
+            // Same blend as `blit_rgba`, with the source terms hoisted out of the loop.
+            let a = self.alpha as u16;
+            let inv = 255u16 - a;
+            let (sr, sg, sb) = (r as u16 * a, g as u16 * a, b as u16 * a);
+            for _ in (cy0..cy1).rev() {
+                *p.add(0) = ((sb + *p.add(0) as u16 * inv + 127) / 255) as u8;
+                *p.add(1) = ((sg + *p.add(1) as u16 * inv + 127) / 255) as u8;
+                *p.add(2) = ((sr + *p.add(2) as u16 * inv + 127) / 255) as u8;
+                p = p.add(3);
+            }
+            return;
+        }
         for _ in (cy0..cy1).rev() {
             *p.add(0) = b;
             *p.add(1) = g;
@@ -373,6 +814,44 @@ impl FbView {
         }
     }
 
+    /// Vertical span sampled from a gradient ramp at each pixel center, blended by the ramp's
+    /// alpha and the view's blend mode.
+    #[inline(always)]
+    unsafe fn fill_col_span_gradient(&self, x: i32, y0: i32, y1_excl: i32, grad: &GradientFill) {
+        let Some((mut p, cy0, cy1)) = self.col_span_ptr(x, y0, y1_excl) else { return; };
+        let m = grad.screen_to_gradient;
+        let (mut u, mut v) = m.apply(x as f32 + 0.5, (cy1 - 1) as f32 + 0.5);
+        for _ in (cy0..cy1).rev() {
+            let [r, g, b, a] = grad.lut[grad.ramp_index(u, v)];
+            if a != 0 {
+                self.shade_px(p, r, g, b, a as u16);
+            }
+            p = p.add(self.bpp);
+            u -= m.c;
+            v -= m.d;
+        }
+    }
+
+    /// Vertical span with per-pixel color from a `GouraudPlane`, blended by the interpolated alpha.
+    #[inline(always)]
+    unsafe fn fill_col_span_gouraud(&self, x: i32, y0: i32, y1_excl: i32, plane: &GouraudPlane) {
+        let Some((mut p, cy0, cy1)) = self.col_span_ptr(x, y0, y1_excl) else { return; };
+        let mut c = plane.at(x as f32 + 0.5, (cy1 - 1) as f32 + 0.5);
+        for _ in (cy0..cy1).rev() {
+            let sa = c[3].clamp(0.0, 255.0) as u16;
+            let (sr, sg, sb) = (c[0].clamp(0.0, 255.0) as u16, c[1].clamp(0.0, 255.0) as u16, c[2].clamp(0.0, 255.0) as u16);
+            if sa == 255 {
+                self.write_px(p, sr as u8, sg as u8, sb as u8);
+            } else if sa != 0 {
+                self.blend_px(p, sr as u8, sg as u8, sb as u8, sa);
+            }
+            p = p.add(self.bpp);
+            for k in 0..4 {
+                c[k] -= plane.dy[k];
+            }
+        }
+    }
+
     #[inline(always)]
     unsafe fn fill_triangle_solid(&self, a: Vertex2, b: Vertex2, c: Vertex2, tx: i32, ty: i32, r: u8, g: u8, bcol: u8) {
         // Apply translation.
@@ -382,8 +861,74 @@ impl FbView {
         self.fill_triangle_solid_xy(ax, ay, bx, by, cx, cy, r, g, bcol);
     }
 
+    /// Winding-agnostic: only zero-area triangles are rejected. Fill meshes from the
+    /// tessellator are CCW anyway (see `enforce_ccw_triangles`), but callers like the debug
+    /// overlay and stroke meshes may pass either winding.
     #[inline(always)]
     unsafe fn fill_triangle_solid_xy(&self, ax: i32, ay: i32, bx: i32, by: i32, cx: i32, cy: i32, r: u8, g: u8, bcol: u8) {
+        if self.edge_aa && !self.hatch {
+            self.fill_triangle_solid_aa(ax, ay, bx, by, cx, cy, r, g, bcol);
+            return;
+        }
+        self.for_each_tri_span(ax, ay, bx, by, cx, cy, |x, y0, y1_excl| {
+            self.fill_col_span(x, y0, y1_excl, r, g, bcol);
+        });
+    }
+
+    /// Anti-aliased variant of `fill_triangle_solid_xy`.
+    ///
+    /// Pixel `(x, y)` is the unit square centered on the integer grid point, so the outline
+    /// matches the hard-edged path. Coverage averages two vertical samples at `x ± 0.25`,
+    /// which softens both shallow and steep edges; fully covered rows still use span fills.
+    unsafe fn fill_triangle_solid_aa(&self, ax: i32, ay: i32, bx: i32, by: i32, cx: i32, cy: i32, r: u8, g: u8, bcol: u8) {
+        let area2 = (bx - ax) as i64 * (cy - ay) as i64 - (by - ay) as i64 * (cx - ax) as i64;
+        if area2 == 0 { return; }
+
+        let verts = [(ax as f32, ay as f32), (bx as f32, by as f32), (cx as f32, cy as f32)];
+        let disp_w = self.disp_w() as i32;
+        let disp_h = self.disp_h() as i32;
+        let x0 = ax.min(bx.min(cx)).max(0);
+        let x1 = ax.max(bx.max(cx)).min(disp_w - 1);
+        let overlap = |y: i32, lo: f32, hi: f32| ((y as f32 + 0.5).min(hi) - (y as f32 - 0.5).max(lo)).max(0.0);
+
+        for x in x0..=x1 {
+            let samples = [tri_column_extent(&verts, x as f32 - 0.25), tri_column_extent(&verts, x as f32 + 0.25)];
+            let (lo, hi) = match samples {
+                [Some(a), Some(b)] => (a.0.min(b.0), a.1.max(b.1)),
+                [Some(a), None] | [None, Some(a)] => a,
+                [None, None] => continue,
+            };
+            let row0 = ((lo + 0.5).floor() as i32).max(0);
+            let row1 = ((hi - 0.5).ceil() as i32).min(disp_h - 1);
+
+            // Rows inside both samples are fully covered.
+            let (full0, full1) = match samples {
+                [Some(a), Some(b)] => (((a.0.max(b.0)) + 0.5).ceil() as i32, ((a.1.min(b.1)) - 0.5).floor() as i32),
+                _ => (1, 0),
+            };
+            if full0 <= full1 {
+                self.fill_col_span(x, full0, full1 + 1, r, g, bcol);
+            }
+
+            for y in row0..=row1 {
+                if y >= full0 && y <= full1 {
+                    continue;
+                }
+                let coverage: f32 = samples.iter().flatten().map(|&(l, h)| overlap(y, l, h) * 0.5).sum();
+                let a = (coverage * self.alpha as f32 + 0.5) as u16;
+                if a == 0 {
+                    continue;
+                }
+                if let Some(p) = self.pixel_ptr(x, y) {
+                    self.shade_px(p, r, g, bcol, a.min(255));
+                }
+            }
+        }
+    }
+
+    /// Walk the vertical spans `(x, y0, y1_excl)` covered by an integer triangle (x-major).
+    #[inline(always)]
+    unsafe fn for_each_tri_span<F: FnMut(i32, i32, i32)>(&self, ax: i32, ay: i32, bx: i32, by: i32, cx: i32, cy: i32, mut span: F) {
 
         // Degenerate reject (area == 0).
         // This avoids wasting time on tiny/flat triangles produced by tessellation.
@@ -461,7 +1006,145 @@ impl FbView {
             }
             let y0 = ((y_min_fp + 0xFFFF) >> 16) as i32;
             let y1_excl = ((y_max_fp >> 16) as i32) + 1;
-            self.fill_col_span(x, y0, y1_excl, r, g, bcol);
+            span(x, y0, y1_excl);
+        }
+    }
+
+    /// Sub-pixel variant of `fill_triangle_solid_xy`: vertices are 16.16 fixed point and
+    /// coverage is decided at pixel centers, so a shape moving by 0.25px shifts its edges
+    /// once the edge crosses a center instead of snapping with the rounded origin.
+    #[inline(always)]
+    unsafe fn fill_triangle_solid_fixed(&self, a: FixedVertex, b: FixedVertex, c: FixedVertex, r: u8, g: u8, bcol: u8) {
+        const ONE: i64 = 1 << FIXED_VERTEX_SHIFT;
+        const HALF: i64 = ONE >> 1;
+
+        let (ax, ay) = (a.x as i64, a.y as i64);
+        let (bx, by) = (b.x as i64, b.y as i64);
+        let (cx, cy) = (c.x as i64, c.y as i64);
+
+        let area2 = (bx - ax) * (cy - ay) - (by - ay) * (cx - ax);
+        if area2 == 0 { return; }
+
+        let minx_fp = ax.min(bx.min(cx));
+        let maxx_fp = ax.max(bx.max(cx));
+        let miny_fp = ay.min(by.min(cy));
+        let maxy_fp = ay.max(by.max(cy));
+
+        // Columns whose center (x + 0.5) lies in [minx, maxx).
+        let disp_w = self.disp_w() as i64;
+        let disp_h = self.disp_h() as i64;
+        let mut minx = (minx_fp - HALF + ONE - 1) >> FIXED_VERTEX_SHIFT;
+        let mut maxx = ((maxx_fp - HALF + ONE - 1) >> FIXED_VERTEX_SHIFT) - 1;
+        if maxx < 0 || minx >= disp_w { return; }
+        if maxy_fp < 0 || (miny_fp >> FIXED_VERTEX_SHIFT) >= disp_h { return; }
+        minx = minx.max(0);
+        maxx = maxx.min(disp_w - 1);
+        if maxx < minx { return; }
+
+        #[derive(Clone, Copy)]
+        struct Edge {
+            x_start: i64,
+            x_end: i64,
+            y_fp: i64,
+            step: i64,
+        }
+
+        let mut edges: [Option<Edge>; 3] = [None, None, None];
+        let verts = [(ax, ay), (bx, by), (cx, cy)];
+
+        for e in 0..3 {
+            let (x0, y0) = verts[e];
+            let (x1, y1) = verts[(e + 1) % 3];
+            if x0 == x1 {
+                continue;
+            }
+            let (sx, sy, ex, ey) = if x0 < x1 { (x0, y0, x1, y1) } else { (x1, y1, x0, y0) };
+            // Same center rule as the bounding box: the edge spans columns with sx <= x+0.5 < ex.
+            let x_start = ((sx - HALF + ONE - 1) >> FIXED_VERTEX_SHIFT).max(minx);
+            let x_end = ((ex - HALF + ONE - 1) >> FIXED_VERTEX_SHIFT).min(maxx + 1);
+            if x_end <= x_start {
+                continue;
+            }
+            let dx = ex - sx;
+            let dy = ey - sy;
+            let step = (dy << FIXED_VERTEX_SHIFT) / dx;
+            let center = (x_start << FIXED_VERTEX_SHIFT) + HALF;
+            let y_fp = sy + (dy * (center - sx)) / dx;
+            let slot = edges.iter_mut().find(|item| item.is_none());
+            if let Some(target) = slot {
+                *target = Some(Edge { x_start, x_end, y_fp, step });
+            }
+        }
+
+        for x in minx..=maxx {
+            let mut y_min_fp: i64 = i64::MAX;
+            let mut y_max_fp: i64 = i64::MIN;
+            let mut hits: i32 = 0;
+
+            for edge in edges.iter_mut().flatten() {
+                if x < edge.x_start || x >= edge.x_end {
+                    continue;
+                }
+                y_min_fp = y_min_fp.min(edge.y_fp);
+                y_max_fp = y_max_fp.max(edge.y_fp);
+                edge.y_fp = edge.y_fp.saturating_add(edge.step);
+                hits += 1;
+            }
+
+            if hits < 2 {
+                continue;
+            }
+            // Rows whose center (y + 0.5) lies in [y_min, y_max).
+            let y0 = ((y_min_fp - HALF + ONE - 1) >> FIXED_VERTEX_SHIFT) as i32;
+            let y1_excl = ((y_max_fp - HALF + ONE - 1) >> FIXED_VERTEX_SHIFT) as i32;
+            self.fill_col_span(x as i32, y0, y1_excl, r, g, bcol);
+        }
+    }
+
+    unsafe fn fill_tris_solid_fixed(&self, verts: &[FixedVertex], indices: &[u16], r: u8, g: u8, b: u8) {
+        let mut i = 0usize;
+        while i + 2 < indices.len() {
+            let ia = indices[i] as usize;
+            let ib = indices[i + 1] as usize;
+            let ic = indices[i + 2] as usize;
+            i += 3;
+
+            if ia >= verts.len() || ib >= verts.len() || ic >= verts.len() { continue; }
+            self.fill_triangle_solid_fixed(verts[ia], verts[ib], verts[ic], r, g, b);
+        }
+    }
+
+    unsafe fn fill_tris_gradient(&self, verts: &[Vertex2], indices: &[u16], grad: &GradientFill) {
+        let mut i = 0usize;
+        while i + 2 < indices.len() {
+            let ia = indices[i] as usize;
+            let ib = indices[i + 1] as usize;
+            let ic = indices[i + 2] as usize;
+            i += 3;
+
+            if ia >= verts.len() || ib >= verts.len() || ic >= verts.len() { continue; }
+            let (a, b, c) = (verts[ia], verts[ib], verts[ic]);
+            self.for_each_tri_span(a.x, a.y, b.x, b.y, c.x, c.y, |x, y0, y1_excl| {
+                self.fill_col_span_gradient(x, y0, y1_excl, grad);
+            });
+        }
+    }
+
+    unsafe fn fill_tris_gouraud(&self, verts: &[Vertex2], colors: &[[u8; 4]], indices: &[u16]) {
+        let n = verts.len().min(colors.len());
+        let mut i = 0usize;
+        while i + 2 < indices.len() {
+            let ia = indices[i] as usize;
+            let ib = indices[i + 1] as usize;
+            let ic = indices[i + 2] as usize;
+            i += 3;
+
+            if ia >= n || ib >= n || ic >= n { continue; }
+            let (a, b, c) = (verts[ia], verts[ib], verts[ic]);
+            let Some(plane) = GouraudPlane::new([a, b, c], [colors[ia], colors[ib], colors[ic]]) else { continue; };
+            self.for_each_tri_span(a.x, a.y, b.x, b.y, c.x, c.y, |x, y0, y1_excl| {
+                self.fill_col_span_gouraud(x, y0, y1_excl, &plane);
+            });
         }
     }
 
@@ -534,7 +1217,27 @@ impl FbView {
     }
 
     #[inline(always)]
-    unsafe fn draw_line(&self, mut x0: i32, mut y0: i32, x1: i32, y1: i32, r: u8, g: u8, b: u8) {
+    unsafe fn draw_line(&self, x0: i32, y0: i32, x1: i32, y1: i32, r: u8, g: u8, b: u8) {
+        self.for_each_line_px(x0, y0, x1, y1, |x, y| self.put_pixel(x, y, r, g, b));
+    }
+
+    /// Bresenham line of `width` pixels: each step stamps a run across the minor axis.
+    unsafe fn draw_thick_line(&self, x0: i32, y0: i32, x1: i32, y1: i32, width: i32, r: u8, g: u8, b: u8) {
+        let x_major = (x1 - x0).abs() >= (y1 - y0).abs();
+        let lo = -(width - 1) / 2;
+        let hi = lo + width - 1;
+        self.for_each_line_px(x0, y0, x1, y1, |x, y| {
+            for k in lo..=hi {
+                if x_major {
+                    self.put_pixel(x, y + k, r, g, b);
+                } else {
+                    self.put_pixel(x + k, y, r, g, b);
+                }
+            }
+        });
+    }
+
+    unsafe fn for_each_line_px<F: FnMut(i32, i32)>(&self, mut x0: i32, mut y0: i32, x1: i32, y1: i32, mut plot: F) {
         // Bresenham
         let dx = (x1 - x0).abs();
         let sx = if x0 < x1 { 1 } else { -1 };
@@ -543,7 +1246,7 @@ impl FbView {
         let mut err = dx + dy;
 
         loop {
-            self.put_pixel(x0, y0, r, g, b);
+            plot(x0, y0);
             if x0 == x1 && y0 == y1 { break; }
             let e2 = 2 * err;
             if e2 >= dy {
@@ -635,12 +1338,37 @@ impl FbView {
     }
 }
 
-fn top_left_fb() -> Option<FbView> {
+fn top_fb(side: i32) -> Option<FbView> {
     let mut w: u16 = 0;
     let mut h: u16 = 0;
-    let ptr = unsafe { gfxGetFramebuffer(GFX_TOP, GFX_LEFT, &mut w, &mut h) };
+    let ptr = unsafe { gfxGetFramebuffer(GFX_TOP, side, &mut w, &mut h) };
     if ptr.is_null() || w == 0 || h == 0 { return None; }
-    Some(FbView { ptr, w_mem: w as usize, h_mem: h as usize, scissor: None })
+    // The framebuffer is stored rotated, so wide mode shows up as a taller `h_mem` (800);
+    // all stride math below already derives from these dimensions.
+    let wide = unsafe { gfxIsWide() } && h as usize >= 2 * 400;
+    let gsp_format = unsafe { gfxGetScreenFormat(GFX_TOP) };
+    let Some(format) = PixelFormat::from_gsp(gsp_format) else {
+        if !UNSUPPORTED_FORMAT_WARNED.swap(true, Ordering::Relaxed) {
+            crate::runlog::warn_line(&format!("fb3ds unsupported framebuffer format={}; not drawing", gsp_format));
+        }
+        return None;
+    };
+    Some(FbView {
+        ptr,
+        w_mem: w as usize,
+        h_mem: h as usize,
+        scissor: None,
+        hatch: false,
+        alpha: 255,
+        blend: DrawBlend::Normal,
+        repeat: false,
+        edge_aa: false,
+        wide,
+        format,
+        bpp: format.bytes_per_pixel(),
+        coverage: core::ptr::null(),
+        coverage_level: 0,
+    })
 }
 
 /// 3DS framebuffer-backed device.
@@ -648,12 +1376,76 @@ fn top_left_fb() -> Option<FbView> {
 /// Design rule: this is the ONLY module allowed to touch `gfxGetFramebuffer` or raw framebuffer pointers.
 pub struct Fb3dsDevice {
     fb: Option<FbView>,
+    /// Active clip: the top of `scissor_stack` unless overridden by `set_scissor`.
     scissor: Option<RectI>,
+    /// Nested scissors, each already intersected with the one below.
+    scissor_stack: Vec<RectI>,
+    /// Union of everything drawn since the last `take_dirty_rect` (clipped to surface + scissor).
+    dirty: Option<RectI>,
+    /// Shape-mask coverage, one byte per framebuffer pixel; allocated on the first shape mask.
+    ///
+    /// Each byte holds how many nested masks cover the pixel, so one buffer serves the whole stack.
+    coverage: Vec<u8>,
+    /// Bounds rasterized by each pushed mask level (innermost last).
+    coverage_levels: Vec<RectI>,
+    edge_aa: bool,
 }
 
 impl Fb3dsDevice {
     pub fn new() -> Self {
-        Self { fb: None, scissor: None }
+        Self { fb: None, scissor: None, scissor_stack: Vec::new(), dirty: None, coverage: Vec::new(), coverage_levels: Vec::new(), edge_aa: false }
+    }
+
+    /// Point the current view at the innermost coverage level (or disable coverage).
+    fn sync_coverage(&mut self) {
+        let level = self.coverage_levels.len();
+        let ptr = if level == 0 { core::ptr::null() } else { self.coverage.as_ptr() };
+        if let Some(fb) = self.fb {
+            self.fb = Some(FbView { coverage: ptr, coverage_level: level as u8, ..fb });
+        }
+    }
+
+    /// Replace pixels at level `from` with `to` inside `bounds`.
+    fn relabel_coverage(&mut self, bounds: RectI, w_mem: usize, from: u8, to: u8) {
+        for x in bounds.x..bounds.x + bounds.w {
+            for y in bounds.y..bounds.y + bounds.h {
+                let idx = x as usize * w_mem + (w_mem - 1 - y as usize);
+                if let Some(c) = self.coverage.get_mut(idx) {
+                    if *c == from {
+                        *c = to;
+                    }
+                }
+            }
+        }
+    }
+
+    fn mark_dirty(&mut self, rect: RectI) {
+        let surface = RectI { x: 0, y: 0, w: self.surface_width(), h: self.surface_height() };
+        let mut clipped = rect.intersect(surface);
+        if let (Some(r), Some(scissor)) = (clipped, self.scissor) {
+            clipped = r.intersect(scissor);
+        }
+        if let Some(r) = clipped {
+            self.dirty = Some(self.dirty.map_or(r, |d| d.union(r)));
+        }
+    }
+
+    /// Mark the conservative pixel bounds of a set of screen-space points (max edge is inclusive).
+    fn mark_dirty_points<I: Iterator<Item = (f32, f32)>>(&mut self, points: I) {
+        let mut min = (f32::MAX, f32::MAX);
+        let mut max = (f32::MIN, f32::MIN);
+        for (x, y) in points {
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        }
+        if min.0 > max.0 || min.1 > max.1 {
+            return;
+        }
+        let x0 = min.0.floor() as i32;
+        let y0 = min.1.floor() as i32;
+        let x1 = max.0.ceil() as i32 + 1;
+        let y1 = max.1.ceil() as i32 + 1;
+        self.mark_dirty(RectI { x: x0, y: y0, w: x1 - x0, h: y1 - y0 });
     }
 }
 
@@ -666,11 +1458,38 @@ impl RenderDevice for Fb3dsDevice {
         self.fb.map(|fb| fb.disp_h() as i32).unwrap_or(240)
     }
 
-    fn begin_frame(&mut self) {
-        self.fb = top_left_fb().map(|mut fb| {
+    fn surface_x_scale(&self) -> i32 {
+        if self.fb.is_some_and(|fb| fb.wide) { 2 } else { 1 }
+    }
+
+    fn begin_frame(&mut self) -> bool {
+        self.begin_eye_frame(false)
+    }
+
+    fn begin_eye_frame(&mut self, right_eye: bool) -> bool {
+        let side = if right_eye { GFX_RIGHT } else { GFX_LEFT };
+        self.fb = top_fb(side).map(|mut fb| {
             fb.scissor = self.scissor;
+            fb.edge_aa = self.edge_aa;
             fb
         });
+        if self.fb.is_none() {
+            // Seen transiently around mode switches; keep the log readable if it persists.
+            let n = NO_FRAMEBUFFER_WARNINGS.fetch_add(1, Ordering::Relaxed);
+            if n < 4 || n % 600 == 0 {
+                crate::runlog::warn_line(&format!("fb3ds no top framebuffer; skipping frame (count={})", n + 1));
+            }
+        }
+        // Masks never span frames; drop any left unbalanced by the previous one.
+        if !self.scissor_stack.is_empty() {
+            self.scissor_stack.clear();
+            self.set_scissor(None);
+        }
+        if !self.coverage_levels.is_empty() {
+            self.coverage_levels.clear();
+            self.coverage.fill(0);
+        }
+        self.fb.is_some()
     }
 
     fn end_frame(&mut self) {
@@ -678,14 +1497,54 @@ impl RenderDevice for Fb3dsDevice {
         self.fb = None;
     }
 
+    fn read_pixels(&self, out: &mut Vec<u8>) -> Option<(u32, u32)> {
+        out.clear();
+        let fb = top_fb(GFX_LEFT)?;
+        let (w, h) = (fb.disp_w(), fb.disp_h());
+        out.reserve(w * h * 4);
+        // Undo the rotation from `put_pixel`: display (x, y) lives at `x * w_mem + (w_mem - 1 - y)`.
+        for y in 0..h {
+            for x in 0..w {
+                let idx = fb.bpp * (x * fb.w_mem + (fb.w_mem - 1 - y));
+                let (r, g, b) = unsafe { fb.read_px(fb.ptr.add(idx)) };
+                out.extend_from_slice(&[r, g, b, 255]);
+            }
+        }
+        Some((w as u32, h as u32))
+    }
+
     fn clear(&mut self, clear: ClearColor) {
         if let Some(fb) = self.fb {
             unsafe { fb.clear(clear.r, clear.g, clear.b); }
         }
     }
 
+    fn clear_rect(&mut self, rect: RectI, clear: ClearColor) {
+        if let Some(fb) = self.fb {
+            let fb = FbView { scissor: None, coverage: core::ptr::null(), ..fb };
+            unsafe { fb.fill_rect(rect.x, rect.y, rect.w, rect.h, clear.r, clear.g, clear.b); }
+        }
+    }
+
+    fn take_dirty_rect(&mut self) -> Option<RectI> {
+        self.dirty.take()
+    }
+
+    fn fade_surface(&mut self, alpha: u8) {
+        if alpha == 255 {
+            return;
+        }
+        if let Some(fb) = self.fb {
+            let w = fb.disp_w() as i32;
+            let h = fb.disp_h() as i32;
+            self.dirty = Some(RectI { x: 0, y: 0, w, h });
+            unsafe { fb.fade(alpha); }
+        }
+    }
+
     fn fill_rect(&mut self, rect: RectI, r: u8, g: u8, b: u8) {
         if let Some(fb) = self.fb {
+            self.mark_dirty(rect);
             unsafe { fb.fill_rect(rect.x, rect.y, rect.w, rect.h, r, g, b); }
         }
     }
@@ -702,6 +1561,7 @@ impl RenderDevice for Fb3dsDevice {
             let y0 = rect.y;
             let x1 = rect.x + w - 1;
             let y1 = rect.y + h - 1;
+            self.mark_dirty(rect);
             unsafe {
                 fb.draw_line(x0, y0, x1, y0, r, g, b);
                 fb.draw_line(x0, y1, x1, y1, r, g, b);
@@ -713,10 +1573,35 @@ impl RenderDevice for Fb3dsDevice {
 
     fn blit_rgba(&mut self, x: i32, y: i32, src: &BitmapSurface) {
         if let Some(fb) = self.fb {
+            self.mark_dirty(RectI { x, y, w: src.width as i32, h: src.height as i32 });
             unsafe { fb.blit_rgba(x, y, src); }
         }
     }
 
+    fn push_scissor(&mut self, rect: RectI) -> RectI {
+        let mut next = rect;
+        if let Some(prev) = self.scissor_stack.last() {
+            let x0 = next.x.max(prev.x);
+            let y0 = next.y.max(prev.y);
+            let x1 = (next.x + next.w).min(prev.x + prev.w);
+            let y1 = (next.y + next.h).min(prev.y + prev.h);
+            next = RectI { x: x0, y: y0, w: (x1 - x0).max(0), h: (y1 - y0).max(0) };
+        }
+        self.scissor_stack.push(next);
+        self.set_scissor(Some(next));
+        next
+    }
+
+    fn pop_scissor(&mut self) -> bool {
+        let popped = self.scissor_stack.pop().is_some();
+        self.set_scissor(self.scissor_stack.last().copied());
+        popped
+    }
+
+    fn current_scissor(&self) -> Option<RectI> {
+        self.scissor_stack.last().copied()
+    }
+
     fn set_scissor(&mut self, rect: Option<RectI>) {
         self.scissor = rect;
         if let Some(mut fb) = self.fb {
@@ -725,17 +1610,92 @@ impl RenderDevice for Fb3dsDevice {
         }
     }
 
+    fn set_edge_antialias(&mut self, enabled: bool) {
+        self.edge_aa = enabled;
+        if let Some(mut fb) = self.fb {
+            fb.edge_aa = enabled;
+            self.fb = Some(fb);
+        }
+    }
+
+    fn push_mask_coverage(&mut self, verts: &[Vertex2], indices: &[u16]) -> bool {
+        let Some(fb) = self.fb else { return false; };
+        let level = self.coverage_levels.len() + 1;
+        if level > u8::MAX as usize {
+            return false;
+        }
+        let len = fb.w_mem * fb.h_mem;
+        if self.coverage.len() != len {
+            // The surface changed size; only safe to reallocate with no levels in use.
+            if !self.coverage_levels.is_empty() {
+                return false;
+            }
+            self.coverage = Vec::new();
+            if self.coverage.try_reserve_exact(len).is_err() {
+                if !COVERAGE_ALLOC_WARNED.swap(true, Ordering::Relaxed) {
+                    crate::runlog::warn_line(&format!("fb3ds mask coverage alloc failed bytes={}; using scissor", len));
+                }
+                return false;
+            }
+            self.coverage.resize(len, 0);
+        }
+
+        let (prev, next) = ((level - 1) as u8, level as u8);
+        let w_mem = fb.w_mem;
+        let cov = self.coverage.as_mut_ptr();
+        let mut bounds: Option<RectI> = None;
+        for tri in indices.chunks_exact(3) {
+            let (Some(a), Some(b), Some(c)) = (verts.get(tri[0] as usize), verts.get(tri[1] as usize), verts.get(tri[2] as usize)) else {
+                continue;
+            };
+            unsafe {
+                fb.for_each_tri_span(a.x, a.y, b.x, b.y, c.x, c.y, |x, y0, y1_excl| {
+                    let y0 = y0.max(0);
+                    let y1 = y1_excl.min(fb.disp_h() as i32);
+                    if y1 <= y0 {
+                        return;
+                    }
+                    for y in y0..y1 {
+                        let px = cov.add(x as usize * w_mem + (w_mem - 1 - y as usize));
+                        if *px == prev {
+                            *px = next;
+                        }
+                    }
+                    let span = RectI { x, y: y0, w: 1, h: y1 - y0 };
+                    bounds = Some(bounds.map_or(span, |r| r.union(span)));
+                });
+            }
+        }
+        self.coverage_levels.push(bounds.unwrap_or(RectI { x: 0, y: 0, w: 0, h: 0 }));
+        self.sync_coverage();
+        true
+    }
+
+    fn pop_mask_coverage(&mut self) {
+        let Some(bounds) = self.coverage_levels.pop() else { return; };
+        if let Some(fb) = self.fb {
+            let level = (self.coverage_levels.len() + 1) as u8;
+            self.relabel_coverage(bounds, fb.w_mem, level, level - 1);
+        }
+        self.sync_coverage();
+    }
+
     fn draw_tris_textured(
         &mut self,
         verts: &[TexVertex],
         indices: &[u16],
         src: &BitmapSurface,
         color_transform: Option<ColorTransform>,
+        bilinear: bool,
+        repeat: bool,
+        blend: DrawBlend,
     ) {
         if let Some(fb) = self.fb {
+            let fb = FbView { blend, repeat, ..fb };
             if verts.is_empty() || indices.len() < 3 {
                 return;
             }
+            self.mark_dirty_points(verts.iter().map(|v| (v.x, v.y)));
             for tri in indices.chunks(3) {
                 if tri.len() < 3 {
                     continue;
@@ -747,7 +1707,7 @@ impl RenderDevice for Fb3dsDevice {
                     continue;
                 }
                 unsafe {
-                    fb.draw_triangle_textured(verts[ia], verts[ib], verts[ic], src, color_transform);
+                    fb.draw_triangle_textured(verts[ia], verts[ib], verts[ic], src, color_transform, bilinear);
                 }
             }
         }
@@ -755,12 +1715,89 @@ impl RenderDevice for Fb3dsDevice {
 
     fn fill_tris_solid(&mut self, verts: &[Vertex2], indices: &[u16], tx: i32, ty: i32, r: u8, g: u8, b: u8) {
         if let Some(fb) = self.fb {
+            self.mark_dirty_points(verts.iter().map(|v| ((v.x + tx) as f32, (v.y + ty) as f32)));
             unsafe { fb.fill_tris_solid(verts, indices, tx, ty, r, g, b); }
         }
     }
 
+    fn fill_tris_solid_alpha(&mut self, verts: &[Vertex2], indices: &[u16], tx: i32, ty: i32, r: u8, g: u8, b: u8, a: u8) {
+        if a == 0 {
+            return;
+        }
+        if let Some(fb) = self.fb {
+            let fb = FbView { alpha: a, ..fb };
+            self.mark_dirty_points(verts.iter().map(|v| ((v.x + tx) as f32, (v.y + ty) as f32)));
+            unsafe { fb.fill_tris_solid(verts, indices, tx, ty, r, g, b); }
+        }
+    }
+
+    fn fill_tris_solid_blend(
+        &mut self,
+        verts: &[Vertex2],
+        indices: &[u16],
+        r: u8,
+        g: u8,
+        b: u8,
+        a: u8,
+        blend: DrawBlend,
+    ) {
+        if a == 0 {
+            return;
+        }
+        if let Some(fb) = self.fb {
+            let fb = FbView { alpha: a, blend, ..fb };
+            self.mark_dirty_points(verts.iter().map(|v| (v.x as f32, v.y as f32)));
+            unsafe { fb.fill_tris_solid(verts, indices, 0, 0, r, g, b); }
+        }
+    }
+
+    fn fill_tris_solid_hatched(&mut self, verts: &[Vertex2], indices: &[u16], tx: i32, ty: i32, r: u8, g: u8, b: u8) {
+        if let Some(fb) = self.fb {
+            let fb = FbView { hatch: true, ..fb };
+            self.mark_dirty_points(verts.iter().map(|v| ((v.x + tx) as f32, (v.y + ty) as f32)));
+            unsafe { fb.fill_tris_solid(verts, indices, tx, ty, r, g, b); }
+        }
+    }
+
+    fn fill_tris_gradient(&mut self, verts: &[Vertex2], indices: &[u16], gradient: &GradientFill, blend: DrawBlend) {
+        if let Some(fb) = self.fb {
+            let fb = FbView { blend, ..fb };
+            self.mark_dirty_points(verts.iter().map(|v| (v.x as f32, v.y as f32)));
+            unsafe { fb.fill_tris_gradient(verts, indices, gradient); }
+        }
+    }
+
+    fn fill_tris_gouraud(&mut self, verts: &[Vertex2], colors: &[[u8; 4]], indices: &[u16]) {
+        if let Some(fb) = self.fb {
+            self.mark_dirty_points(verts.iter().map(|v| (v.x as f32, v.y as f32)));
+            unsafe { fb.fill_tris_gouraud(verts, colors, indices); }
+        }
+    }
+
+    fn fill_tris_solid_fixed(&mut self, verts: &[FixedVertex], indices: &[u16], r: u8, g: u8, b: u8) {
+        if let Some(fb) = self.fb {
+            let scale = (1i32 << FIXED_VERTEX_SHIFT) as f32;
+            self.mark_dirty_points(verts.iter().map(|v| (v.x as f32 / scale, v.y as f32 / scale)));
+            unsafe { fb.fill_tris_solid_fixed(verts, indices, r, g, b); }
+        }
+    }
+
+    fn draw_thick_line(&mut self, x0: f32, y0: f32, x1: f32, y1: f32, width: f32, r: u8, g: u8, b: u8) {
+        // Bresenham walks every step, so refuse absurd off-screen lengths outright.
+        if [x0, y0, x1, y1].iter().any(|v| !v.is_finite() || v.abs() > MAX_LINE_COORD) {
+            return;
+        }
+        if let Some(fb) = self.fb {
+            let w = (width.round() as i32).max(1);
+            let pad = (w / 2 + 1) as f32;
+            self.mark_dirty_points([(x0 - pad, y0 - pad), (x1 + pad, y1 + pad), (x0 + pad, y0 + pad), (x1 - pad, y1 - pad)].into_iter());
+            unsafe { fb.draw_thick_line(x0.round() as i32, y0.round() as i32, x1.round() as i32, y1.round() as i32, w, r, g, b); }
+        }
+    }
+
     fn draw_tris_wireframe(&mut self, verts: &[Vertex2], indices: &[u16], tx: i32, ty: i32, r: u8, g: u8, b: u8) {
         if let Some(fb) = self.fb {
+            self.mark_dirty_points(verts.iter().map(|v| ((v.x + tx) as f32, (v.y + ty) as f32)));
             unsafe { fb.draw_tris_wireframe(verts, indices, tx, ty, r, g, b); }
         }
     }
@@ -775,6 +1812,7 @@ impl RenderDevice for Fb3dsDevice {
         b: u8,
     ) {
         if let Some(fb) = self.fb {
+            self.mark_dirty_points(verts.iter().map(|v| transform.apply(v.x as f32, v.y as f32)));
             unsafe { fb.fill_tris_solid_affine(verts, indices, transform, r, g, b); }
         }
     }
@@ -789,8 +1827,227 @@ impl RenderDevice for Fb3dsDevice {
         b: u8,
     ) {
         if let Some(fb) = self.fb {
+            self.mark_dirty_points(verts.iter().map(|v| transform.apply(v.x as f32, v.y as f32)));
             unsafe { fb.draw_tris_wireframe_affine(verts, indices, transform, r, g, b); }
         }
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::frame::{GradientKind, GradientSpread, GRADIENT_LUT_SIZE};
+
+    /// A BGR8 `FbView` over `buf`, with the same rotated layout as the top screen.
+    fn view(buf: &mut Vec<u8>, disp_w: usize, disp_h: usize) -> FbView {
+        buf.clear();
+        buf.resize(disp_w * disp_h * 3, 0);
+        FbView {
+            ptr: buf.as_mut_ptr(),
+            w_mem: disp_h,
+            h_mem: disp_w,
+            scissor: None,
+            hatch: false,
+            alpha: 255,
+            blend: DrawBlend::Normal,
+            repeat: false,
+            edge_aa: false,
+            wide: false,
+            format: PixelFormat::Bgr8,
+            bpp: 3,
+            coverage: core::ptr::null(),
+            coverage_level: 0,
+        }
+    }
+
+    fn px(fb: &FbView, x: i32, y: i32) -> (u8, u8, u8) {
+        unsafe { fb.read_px(fb.pixel_ptr(x, y).expect("pixel on surface")) }
+    }
+
+    /// Columns of row `y` that are not black, as `[first, last]`.
+    fn lit_columns(fb: &FbView, y: i32) -> Option<(i32, i32)> {
+        let lit: Vec<i32> = (0..fb.disp_w() as i32).filter(|&x| px(fb, x, y) != (0, 0, 0)).collect();
+        Some((*lit.first()?, *lit.last()?))
+    }
+
+    #[test]
+    fn fixed_fill_moves_edges_in_quarter_pixel_steps() {
+        let mut buf = Vec::new();
+        for step in 0..=8 {
+            let fb = view(&mut buf, 48, 16);
+            let left = 10.0 + 0.25 * step as f32;
+            let right = left + 8.0;
+            let verts = [
+                FixedVertex::from_f32(left, 4.0),
+                FixedVertex::from_f32(right, 4.0),
+                FixedVertex::from_f32(right, 12.0),
+                FixedVertex::from_f32(left, 12.0),
+            ];
+            unsafe { fb.fill_tris_solid_fixed(&verts, &[0, 1, 2, 0, 2, 3], 255, 255, 255) };
+
+            // A column is lit when its center x + 0.5 lies in [left, right).
+            let first = (left - 0.5).ceil() as i32;
+            let last = (right - 0.5).ceil() as i32 - 1;
+            for y in 4..12 {
+                assert_eq!(lit_columns(&fb, y), Some((first, last)), "left={} row {}", left, y);
+            }
+            assert_eq!(lit_columns(&fb, 3), None);
+            assert_eq!(lit_columns(&fb, 12), None);
+        }
+    }
+
+    #[test]
+    fn gouraud_interpolates_vertex_colors() {
+        let mut buf = Vec::new();
+        let fb = view(&mut buf, 40, 40);
+        let verts = [Vertex2 { x: 2, y: 2 }, Vertex2 { x: 34, y: 2 }, Vertex2 { x: 2, y: 34 }];
+        let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+        unsafe { fb.fill_tris_gouraud(&verts, &colors, &[0, 1, 2]) };
+
+        // Barycentric weights of the pixel center, from the triangle's legs along x and y.
+        let mut checked = 0;
+        for y in 0..40 {
+            for x in 0..40 {
+                let (cx, cy) = (x as f32 + 0.5, y as f32 + 0.5);
+                let w1 = (cx - 2.0) / 32.0;
+                let w2 = (cy - 2.0) / 32.0;
+                let w0 = 1.0 - w1 - w2;
+                if w0 < 0.05 || w1 < 0.05 || w2 < 0.05 {
+                    continue; // Edge pixels depend on the span rule, not interpolation.
+                }
+                let want = [w0 * 255.0, w1 * 255.0, w2 * 255.0];
+                let (r, g, b) = px(&fb, x, y);
+                for (got, want) in [r, g, b].into_iter().zip(want) {
+                    assert!((got as f32 - want).abs() <= 2.0, "({}, {}) got {:?} want {:?}", x, y, (r, g, b), want);
+                }
+                checked += 1;
+            }
+        }
+        assert!(checked > 300, "only {} interior pixels", checked);
+        assert_eq!(px(&fb, 36, 36), (0, 0, 0));
+    }
+
+    #[test]
+    fn gouraud_blends_interpolated_alpha() {
+        let mut buf = Vec::new();
+        let fb = view(&mut buf, 40, 40);
+        unsafe { fb.clear(0, 0, 200) };
+        // White fading from opaque at the left edge to transparent at the right.
+        let verts = [Vertex2 { x: 0, y: 0 }, Vertex2 { x: 32, y: 0 }, Vertex2 { x: 0, y: 32 }];
+        let colors = [[255, 255, 255, 255], [255, 255, 255, 0], [255, 255, 255, 255]];
+        unsafe { fb.fill_tris_gouraud(&verts, &colors, &[0, 1, 2]) };
+        let (r, _, b) = px(&fb, 8, 4);
+        // Alpha at the center (8.5, 4.5) is 255 * (1 - 8.5 / 32).
+        let a = 255.0 * (1.0 - 8.5 / 32.0);
+        assert!((r as f32 - a).abs() <= 2.0, "red {} want {}", r, a);
+        assert!((b as f32 - (a + 200.0 * (1.0 - a / 255.0))).abs() <= 2.0, "blue {}", b);
+    }
+
+    /// The float barycentric textured fill that `draw_triangle_textured` replaced (opaque,
+    /// nearest sampling), kept as the reference for the fixed-point edge stepping.
+    fn textured_reference(fb: &FbView, v: [TexVertex; 3], src: &BitmapSurface) {
+        let [v0, v1, v2] = v;
+        let area = (v1.x - v0.x) * (v2.y - v0.y) - (v1.y - v0.y) * (v2.x - v0.x);
+        for y in 0..fb.disp_h() as i32 {
+            for x in 0..fb.disp_w() as i32 {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let w0 = (v1.x - v0.x) * (py - v0.y) - (v1.y - v0.y) * (px - v0.x);
+                let w1 = (v2.x - v1.x) * (py - v1.y) - (v2.y - v1.y) * (px - v1.x);
+                let w2 = (v0.x - v2.x) * (py - v2.y) - (v0.y - v2.y) * (px - v2.x);
+                if !((w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0) || (w0 <= 0.0 && w1 <= 0.0 && w2 <= 0.0)) {
+                    continue;
+                }
+                let l0 = ((v1.x - px) * (v2.y - py) - (v1.y - py) * (v2.x - px)) / area;
+                let l1 = ((v2.x - px) * (v0.y - py) - (v2.y - py) * (v0.x - px)) / area;
+                let l2 = 1.0 - l0 - l1;
+                let [r, g, b, _] = FbView::sample_nearest(
+                    src,
+                    v0.u * l0 + v1.u * l1 + v2.u * l2,
+                    v0.v * l0 + v1.v * l1 + v2.v * l2,
+                    false,
+                );
+                unsafe { fb.put_pixel(x, y, r, g, b) };
+            }
+        }
+    }
+
+    #[test]
+    fn gradient_spans_blend_by_ramp_alpha_and_mode() {
+        // A flat half-transparent ramp, so every pixel sees the same texel.
+        let grad = GradientFill {
+            kind: GradientKind::Linear,
+            spread: GradientSpread::Pad,
+            screen_to_gradient: Matrix2D { a: 0.0, b: 0.0, c: 0.0, d: 0.0, tx: 0.0, ty: 0.0 },
+            focal_point: 0.0,
+            lut: [[200, 0, 0, 128]; GRADIENT_LUT_SIZE],
+        };
+        let tri = [Vertex2 { x: 0, y: 0 }, Vertex2 { x: 8, y: 0 }, Vertex2 { x: 0, y: 8 }];
+        for (blend, want) in [(DrawBlend::Normal, (100, 0, 50)), (DrawBlend::Add, (100, 0, 100))] {
+            let mut buf = Vec::new();
+            let fb = view(&mut buf, 8, 8);
+            unsafe {
+                fb.fill_rect(0, 0, 8, 8, 0, 0, 100);
+                FbView { blend, ..fb }.fill_tris_gradient(&tri, &[0, 1, 2], &grad);
+            }
+            let (r, g, b) = px(&fb, 1, 1);
+            assert!(r.abs_diff(want.0) <= 1 && g == want.1 && b.abs_diff(want.2) <= 1, "{:?}: {:?}", blend, (r, g, b));
+            // Untouched outside the triangle.
+            assert_eq!(px(&fb, 7, 7), (0, 0, 100));
+        }
+    }
+
+    #[test]
+    fn repeating_samples_wrap_across_the_bitmap_edge() {
+        // Two texels: red then blue.
+        let src = BitmapSurface::new(2, 1, vec![255, 0, 0, 255, 0, 0, 255, 255]);
+        let red = [255, 0, 0, 255];
+        let blue = [0, 0, 255, 255];
+        assert_eq!(FbView::sample_nearest(&src, 1.25, 0.5, true), red);
+        assert_eq!(FbView::sample_nearest(&src, -0.25, 0.5, true), blue);
+        assert_eq!(FbView::sample_nearest(&src, 1.25, 0.5, false), blue);
+        // Exactly on the seam between tiles: half blue (the previous tile's last texel), half red.
+        let [r, g, b, a] = FbView::sample_bilinear(&src, 1.0, 0.5, true);
+        assert!((126..=129).contains(&r) && g == 0 && (126..=129).contains(&b) && a == 255, "{:?}", [r, g, b, a]);
+        // Clamped, the same coordinate is the last texel.
+        assert_eq!(FbView::sample_bilinear(&src, 1.0, 0.5, false), blue);
+    }
+
+    #[test]
+    fn fixed_point_textured_quad_matches_float_reference() {
+        // 4x4 texels, each a distinct opaque color.
+        let rgba: Vec<u8> = (0..16u8).flat_map(|i| [i * 16 + 8, 255 - i * 16, (i % 4) * 60 + 20, 255]).collect();
+        let mut src = BitmapSurface::new(4, 4, rgba);
+        src.is_opaque = true;
+        // A slightly rotated quad at fractional coordinates.
+        let quad = [
+            TexVertex { x: 3.3, y: 2.6, u: 0.0, v: 0.0 },
+            TexVertex { x: 37.8, y: 5.1, u: 1.0, v: 0.0 },
+            TexVertex { x: 35.2, y: 33.7, u: 1.0, v: 1.0 },
+            TexVertex { x: 0.9, y: 30.4, u: 0.0, v: 1.0 },
+        ];
+        let tris = [[quad[0], quad[1], quad[2]], [quad[0], quad[2], quad[3]]];
+
+        let (mut fixed_buf, mut float_buf) = (Vec::new(), Vec::new());
+        let fixed = view(&mut fixed_buf, 44, 40);
+        let float = view(&mut float_buf, 44, 40);
+        for [a, b, c] in tris {
+            unsafe { fixed.draw_triangle_textured(a, b, c, &src, None, false) };
+            textured_reference(&float, [a, b, c], &src);
+        }
+
+        // Vertices snap to 1/16px, so only pixels whose center is that close to an edge may
+        // flip coverage; texel lookups can differ right at a texel boundary.
+        let mut differing = 0;
+        let mut covered = 0;
+        for y in 0..40 {
+            for x in 0..44 {
+                let (got, want) = (px(&fixed, x, y), px(&float, x, y));
+                covered += usize::from(want != (0, 0, 0));
+                differing += usize::from(got != want);
+            }
+        }
+        assert!(covered > 900, "reference covered only {} pixels", covered);
+        assert!(differing * 100 <= covered, "{} of {} pixels differ", differing, covered);
+    }
+}
@@ -0,0 +1,363 @@
+use crate::render::cache::bitmaps::{AlphaMode, BitmapSurface};
+use crate::render::cache::shapes::Vertex2;
+use crate::render::device::RenderDevice;
+use crate::render::frame::{ClearColor, ColorTransform, DrawBlend, GradientFill, RectI, TexVertex};
+
+/// In-memory device backed by a row-major RGBA8 `Vec`, for running `CommandExecutor::execute`
+/// off-device (golden-image tests of fills, strokes, masks and color transforms).
+///
+/// This is a reference rasterizer, not a copy of `Fb3dsDevice`: pixels are covered when their
+/// center is inside a triangle, textures are sampled nearest, and dirty tracking reports the
+/// whole surface. Alpha in the buffer is always 255.
+pub struct MemDevice {
+    width: i32,
+    height: i32,
+    pixels: Vec<u8>,
+    scissor: Option<RectI>,
+    scissor_stack: Vec<RectI>,
+    /// Effective shape-mask coverage per pushed level, each already intersected with the one below.
+    masks: Vec<Vec<bool>>,
+}
+
+impl MemDevice {
+    pub fn new(width: u32, height: u32) -> Self {
+        let (w, h) = (width.max(1) as i32, height.max(1) as i32);
+        Self {
+            width: w,
+            height: h,
+            pixels: vec![0; (w * h * 4) as usize],
+            scissor: None,
+            scissor_stack: Vec::new(),
+            masks: Vec::new(),
+        }
+    }
+
+    /// The surface as row-major RGBA8.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// `(r, g, b)` at `(x, y)`, or `None` outside the surface.
+    pub fn pixel(&self, x: i32, y: i32) -> Option<(u8, u8, u8)> {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return None;
+        }
+        let i = ((y * self.width + x) * 4) as usize;
+        Some((self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]))
+    }
+
+    /// Whether a draw may touch `(x, y)`: on the surface, inside the scissor and the shape mask.
+    fn writable(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return false;
+        }
+        if let Some(s) = self.scissor {
+            if x < s.x || y < s.y || x >= s.x + s.w || y >= s.y + s.h {
+                return false;
+            }
+        }
+        self.masks.last().is_none_or(|mask| mask[(y * self.width + x) as usize])
+    }
+
+    /// Combine straight-alpha `(r, g, b, a)` with the pixel at `(x, y)` by `blend`.
+    fn shade(&mut self, x: i32, y: i32, [r, g, b, a]: [u8; 4], blend: DrawBlend) {
+        if a == 0 || !self.writable(x, y) {
+            return;
+        }
+        let i = ((y * self.width + x) * 4) as usize;
+        let a = a as u16;
+        for (c, s) in [r, g, b].into_iter().enumerate() {
+            let d = self.pixels[i + c] as u16;
+            let s16 = s as u16;
+            self.pixels[i + c] = match blend {
+                DrawBlend::Normal => ((s16 * a + d * (255 - a) + 127) / 255) as u8,
+                DrawBlend::Add => (d + (s16 * a + 127) / 255).min(255) as u8,
+                DrawBlend::Multiply => {
+                    let s = 255 - ((255 - s16) * a + 127) / 255;
+                    ((d * s + 127) / 255) as u8
+                }
+            };
+        }
+        self.pixels[i + 3] = 255;
+    }
+
+    /// Visit the pixels whose centers fall inside triangle `v` with barycentric weights.
+    fn raster_tri(&mut self, v: [(f32, f32); 3], mut shade: impl FnMut(&mut Self, i32, i32, [f32; 3])) {
+        let area = edge(v[0], v[1], v[2]);
+        if area == 0.0 || !area.is_finite() {
+            return;
+        }
+        let x0 = v.iter().map(|p| p.0).fold(f32::MAX, f32::min).floor().max(0.0) as i32;
+        let y0 = v.iter().map(|p| p.1).fold(f32::MAX, f32::min).floor().max(0.0) as i32;
+        let x1 = (v.iter().map(|p| p.0).fold(f32::MIN, f32::max).ceil() as i32).min(self.width);
+        let y1 = (v.iter().map(|p| p.1).fold(f32::MIN, f32::max).ceil() as i32).min(self.height);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let p = (x as f32 + 0.5, y as f32 + 0.5);
+                let w = [edge(v[1], v[2], p) / area, edge(v[2], v[0], p) / area, edge(v[0], v[1], p) / area];
+                if w.iter().all(|&w| w >= 0.0) {
+                    shade(self, x, y, w);
+                }
+            }
+        }
+    }
+
+    fn fill_solid(&mut self, verts: &[Vertex2], indices: &[u16], tx: i32, ty: i32, rgba: [u8; 4], blend: DrawBlend) {
+        for tri in indices.chunks_exact(3) {
+            let Some(v) = tri_points(verts, tri, tx, ty) else { continue; };
+            self.raster_tri(v, |dev, x, y, _| dev.shade(x, y, rgba, blend));
+        }
+    }
+
+    fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, rgb: [u8; 3]) {
+        let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+        let (sx, sy) = (if x0 < x1 { 1 } else { -1 }, if y0 < y1 { 1 } else { -1 });
+        let (mut x, mut y, mut err) = (x0, y0, dx + dy);
+        loop {
+            self.shade(x, y, [rgb[0], rgb[1], rgb[2], 255], DrawBlend::Normal);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+}
+
+/// Twice the signed area of `(a, b, p)`.
+fn edge(a: (f32, f32), b: (f32, f32), p: (f32, f32)) -> f32 {
+    (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+}
+
+fn tri_points(verts: &[Vertex2], tri: &[u16], tx: i32, ty: i32) -> Option<[(f32, f32); 3]> {
+    let p = |i: u16| verts.get(i as usize).map(|v| ((v.x + tx) as f32, (v.y + ty) as f32));
+    Some([p(tri[0])?, p(tri[1])?, p(tri[2])?])
+}
+
+/// Nearest texel at `(u, v)` as straight alpha, with `color_transform` applied.
+fn sample(src: &BitmapSurface, u: f32, v: f32, repeat: bool, color_transform: Option<ColorTransform>) -> [u8; 4] {
+    let texel = |t: f32, size: u32| {
+        if repeat {
+            ((t.rem_euclid(1.0) * size as f32) as usize).min(size as usize - 1)
+        } else {
+            (t.clamp(0.0, 1.0) * (size as f32 - 1.0)).round() as usize
+        }
+    };
+    let (sx, sy) = (texel(u, src.width), texel(v, src.height));
+    let si = 4 * (sy * src.width as usize + sx);
+    let Some(texel) = src.rgba.get(si..si + 4) else {
+        return [0; 4];
+    };
+    let mut px = [texel[0], texel[1], texel[2], texel[3]];
+    let a = px[3];
+    if src.alpha_mode == AlphaMode::Premultiplied && a != 0 && a != 255 {
+        for c in &mut px[..3] {
+            *c = ((*c as u16 * 255 + a as u16 / 2) / a as u16).min(255) as u8;
+        }
+    }
+    if let Some(ct) = color_transform {
+        for (i, c) in px.iter_mut().enumerate() {
+            *c = (*c as f32 * ct.mul[i] + ct.add[i]).clamp(0.0, 255.0) as u8;
+        }
+    }
+    px
+}
+
+impl RenderDevice for MemDevice {
+    fn surface_width(&self) -> i32 {
+        self.width
+    }
+
+    fn surface_height(&self) -> i32 {
+        self.height
+    }
+
+    fn clear(&mut self, clear: ClearColor) {
+        for px in self.pixels.chunks_exact_mut(4) {
+            px.copy_from_slice(&[clear.r, clear.g, clear.b, 255]);
+        }
+    }
+
+    fn clear_rect(&mut self, rect: RectI, clear: ClearColor) {
+        let (scissor, masks) = (self.scissor.take(), core::mem::take(&mut self.masks));
+        self.fill_rect(rect, clear.r, clear.g, clear.b);
+        self.scissor = scissor;
+        self.masks = masks;
+    }
+
+    fn fade_surface(&mut self, alpha: u8) {
+        for px in self.pixels.chunks_exact_mut(4) {
+            for c in &mut px[..3] {
+                *c = ((*c as u16 * alpha as u16 + 127) / 255) as u8;
+            }
+        }
+    }
+
+    fn fill_rect(&mut self, rect: RectI, r: u8, g: u8, b: u8) {
+        for y in rect.y..rect.y + rect.h {
+            for x in rect.x..rect.x + rect.w {
+                self.shade(x, y, [r, g, b, 255], DrawBlend::Normal);
+            }
+        }
+    }
+
+    fn stroke_rect(&mut self, rect: RectI, r: u8, g: u8, b: u8) {
+        if rect.is_empty() {
+            return;
+        }
+        let (x0, y0, x1, y1) = (rect.x, rect.y, rect.x + rect.w - 1, rect.y + rect.h - 1);
+        for (ax, ay, bx, by) in [(x0, y0, x1, y0), (x0, y1, x1, y1), (x0, y0, x0, y1), (x1, y0, x1, y1)] {
+            self.draw_line(ax, ay, bx, by, [r, g, b]);
+        }
+    }
+
+    fn blit_rgba(&mut self, x: i32, y: i32, src: &BitmapSurface) {
+        for sy in 0..src.height as i32 {
+            for sx in 0..src.width as i32 {
+                let u = if src.width > 1 { sx as f32 / (src.width - 1) as f32 } else { 0.0 };
+                let v = if src.height > 1 { sy as f32 / (src.height - 1) as f32 } else { 0.0 };
+                let px = sample(src, u, v, false, None);
+                self.shade(x + sx, y + sy, px, DrawBlend::Normal);
+            }
+        }
+    }
+
+    fn push_scissor(&mut self, rect: RectI) -> RectI {
+        let next = match self.scissor_stack.last() {
+            Some(prev) => rect.intersect(*prev).unwrap_or(RectI { x: rect.x, y: rect.y, w: 0, h: 0 }),
+            None => rect,
+        };
+        self.scissor_stack.push(next);
+        self.scissor = Some(next);
+        next
+    }
+
+    fn pop_scissor(&mut self) -> bool {
+        let ok = self.scissor_stack.pop().is_some();
+        self.scissor = self.scissor_stack.last().copied();
+        ok
+    }
+
+    fn current_scissor(&self) -> Option<RectI> {
+        self.scissor_stack.last().copied()
+    }
+
+    fn set_scissor(&mut self, rect: Option<RectI>) {
+        self.scissor = rect;
+    }
+
+    fn push_mask_coverage(&mut self, verts: &[Vertex2], indices: &[u16]) -> bool {
+        let mut mask = vec![false; (self.width * self.height) as usize];
+        let outer = self.masks.last().cloned();
+        let width = self.width;
+        for tri in indices.chunks_exact(3) {
+            let Some(v) = tri_points(verts, tri, 0, 0) else { continue; };
+            self.raster_tri(v, |_, x, y, _| mask[(y * width + x) as usize] = true);
+        }
+        if let Some(outer) = outer {
+            mask.iter_mut().zip(outer).for_each(|(m, o)| *m &= o);
+        }
+        self.masks.push(mask);
+        true
+    }
+
+    fn pop_mask_coverage(&mut self) {
+        self.masks.pop();
+    }
+
+    fn draw_tris_textured(
+        &mut self,
+        verts: &[TexVertex],
+        indices: &[u16],
+        src: &BitmapSurface,
+        color_transform: Option<ColorTransform>,
+        _bilinear: bool,
+        repeat: bool,
+        blend: DrawBlend,
+    ) {
+        if src.width == 0 || src.height == 0 {
+            return;
+        }
+        for tri in indices.chunks_exact(3) {
+            let (Some(&t0), Some(&t1), Some(&t2)) = (verts.get(tri[0] as usize), verts.get(tri[1] as usize), verts.get(tri[2] as usize)) else {
+                continue;
+            };
+            let tv = [t0, t1, t2];
+            let v = [(tv[0].x, tv[0].y), (tv[1].x, tv[1].y), (tv[2].x, tv[2].y)];
+            self.raster_tri(v, |dev, x, y, w| {
+                let u = w[0] * tv[0].u + w[1] * tv[1].u + w[2] * tv[2].u;
+                let t = w[0] * tv[0].v + w[1] * tv[1].v + w[2] * tv[2].v;
+                dev.shade(x, y, sample(src, u, t, repeat, color_transform), blend);
+            });
+        }
+    }
+
+    fn fill_tris_solid(&mut self, verts: &[Vertex2], indices: &[u16], tx: i32, ty: i32, r: u8, g: u8, b: u8) {
+        self.fill_solid(verts, indices, tx, ty, [r, g, b, 255], DrawBlend::Normal);
+    }
+
+    fn fill_tris_solid_alpha(&mut self, verts: &[Vertex2], indices: &[u16], tx: i32, ty: i32, r: u8, g: u8, b: u8, a: u8) {
+        self.fill_solid(verts, indices, tx, ty, [r, g, b, a], DrawBlend::Normal);
+    }
+
+    fn fill_tris_solid_blend(&mut self, verts: &[Vertex2], indices: &[u16], r: u8, g: u8, b: u8, a: u8, blend: DrawBlend) {
+        self.fill_solid(verts, indices, 0, 0, [r, g, b, a], blend);
+    }
+
+    fn fill_tris_gradient(&mut self, verts: &[Vertex2], indices: &[u16], gradient: &GradientFill, blend: DrawBlend) {
+        for tri in indices.chunks_exact(3) {
+            let Some(v) = tri_points(verts, tri, 0, 0) else { continue; };
+            self.raster_tri(v, |dev, x, y, _| {
+                let (u, w) = gradient.screen_to_gradient.apply(x as f32 + 0.5, y as f32 + 0.5);
+                dev.shade(x, y, gradient.lut[gradient.ramp_index(u, w)], blend);
+            });
+        }
+    }
+
+    fn fill_tris_gouraud(&mut self, verts: &[Vertex2], colors: &[[u8; 4]], indices: &[u16]) {
+        for tri in indices.chunks_exact(3) {
+            let Some(v) = tri_points(verts, tri, 0, 0) else { continue; };
+            let (Some(&c0), Some(&c1), Some(&c2)) = (colors.get(tri[0] as usize), colors.get(tri[1] as usize), colors.get(tri[2] as usize)) else {
+                continue;
+            };
+            let c = [c0, c1, c2];
+            self.raster_tri(v, |dev, x, y, w| {
+                let lerp = |ch: usize| (w[0] * c[0][ch] as f32 + w[1] * c[1][ch] as f32 + w[2] * c[2][ch] as f32).round().clamp(0.0, 255.0) as u8;
+                dev.shade(x, y, [lerp(0), lerp(1), lerp(2), lerp(3)], DrawBlend::Normal);
+            });
+        }
+    }
+
+    fn draw_tris_wireframe(&mut self, verts: &[Vertex2], indices: &[u16], tx: i32, ty: i32, r: u8, g: u8, b: u8) {
+        for tri in indices.chunks_exact(3) {
+            let Some(v) = tri_points(verts, tri, tx, ty) else { continue; };
+            for e in 0..3 {
+                let (a, b2) = (v[e], v[(e + 1) % 3]);
+                self.draw_line(a.0 as i32, a.1 as i32, b2.0 as i32, b2.1 as i32, [r, g, b]);
+            }
+        }
+    }
+
+    fn begin_frame(&mut self) -> bool {
+        // Masks never span frames, same as the hardware device.
+        self.scissor_stack.clear();
+        self.scissor = None;
+        self.masks.clear();
+        true
+    }
+
+    fn end_frame(&mut self) {}
+
+    fn read_pixels(&self, out: &mut Vec<u8>) -> Option<(u32, u32)> {
+        out.clear();
+        out.extend_from_slice(&self.pixels);
+        Some((self.width as u32, self.height as u32))
+    }
+}
@@ -1,7 +1,11 @@
 #[cfg(feature = "legacy_sw_render")]
 pub mod fb3ds;
+// Unit tests (e.g. the executor's) and `test_device` builds construct it; the staticlib never does.
+#[cfg(any(test, feature = "test_device"))]
+#[cfg_attr(not(test), allow(dead_code))]
+pub mod mem;
 
-use crate::render::frame::{ClearColor, ColorTransform, Matrix2D, RectI, TexVertex};
+use crate::render::frame::{ClearColor, ColorTransform, DrawBlend, FixedVertex, GradientFill, Matrix2D, RectI, TexVertex, FIXED_VERTEX_SHIFT};
 use crate::render::cache::bitmaps::BitmapSurface;
 use crate::render::cache::shapes::Vertex2;
 
@@ -15,7 +19,45 @@ pub trait RenderDevice {
     /// Display surface height in pixels.
     fn surface_height(&self) -> i32;
 
+    /// Surface pixels per square pixel horizontally (2 on the 800px wide top screen).
+    ///
+    /// The renderer stretches frame packets by this factor so content keeps its aspect ratio
+    /// while gaining horizontal resolution.
+    fn surface_x_scale(&self) -> i32 {
+        1
+    }
+
+    /// Start a frame in one eye's buffer of a stereo surface.
+    ///
+    /// Devices without stereo output draw both eyes to the same surface (right overwrites left).
+    fn begin_eye_frame(&mut self, right_eye: bool) -> bool {
+        let _ = right_eye;
+        self.begin_frame()
+    }
+
     fn clear(&mut self, clear: ClearColor);
+
+    /// Clear only `rect` (ignores the scissor). Like `clear`, this does not count as drawing
+    /// for `take_dirty_rect`.
+    fn clear_rect(&mut self, rect: RectI, clear: ClearColor) {
+        let _ = rect;
+        self.clear(clear);
+    }
+
+    /// Bounding box of every pixel drawn since the previous call (`None` if nothing was drawn).
+    ///
+    /// Devices that don't track this report the whole surface, which keeps callers on full clears.
+    fn take_dirty_rect(&mut self) -> Option<RectI> {
+        Some(RectI { x: 0, y: 0, w: self.surface_width(), h: self.surface_height() })
+    }
+
+    /// Fade the finished frame toward black by `alpha / 255` (255 = unchanged).
+    ///
+    /// A faded frame touches every pixel, so devices must report it as fully dirty.
+    fn fade_surface(&mut self, alpha: u8) {
+        let _ = alpha;
+    }
+
     fn fill_rect(&mut self, rect: RectI, r: u8, g: u8, b: u8);
 
     /// Draw a 1px outline of `rect` (used for wireframe/debug overlays).
@@ -26,16 +68,58 @@ pub trait RenderDevice {
     /// Step 3 bootstrap: no scaling, nearest sampling, basic alpha blending.
     fn blit_rgba(&mut self, x: i32, y: i32, src: &BitmapSurface);
 
-    /// Set or clear a scissor rectangle for masking.
+    /// Push a scissor rect intersected with the current one; later draws clip to the result.
+    ///
+    /// Returns the effective rect (empty when the two don't overlap).
+    fn push_scissor(&mut self, rect: RectI) -> RectI;
+
+    /// Restore the scissor from before the most recent `push_scissor`.
+    ///
+    /// Returns false on underflow, in which case clipping is turned off.
+    fn pop_scissor(&mut self) -> bool;
+
+    /// Top of the scissor stack (`None` when nothing is pushed).
+    fn current_scissor(&self) -> Option<RectI>;
+
+    /// Override the active clip without touching the stack (e.g. unclipped overlays).
+    ///
+    /// The next push or pop re-applies the stack; `set_scissor(current_scissor())` restores it.
     fn set_scissor(&mut self, rect: Option<RectI>);
 
-    /// Draw textured triangles with nearest-neighbor sampling.
+    /// Anti-alias the edges of later solid triangle fills (`aa_edges` config).
+    ///
+    /// Devices without edge anti-aliasing ignore this.
+    fn set_edge_antialias(&mut self, enabled: bool) {
+        let _ = enabled;
+    }
+
+    /// Restrict later draws to the screen-space triangles `verts`/`indices`, inside any enclosing
+    /// shape mask. The scissor still applies on top.
+    ///
+    /// Returns false when the device can't (no support, nesting too deep, or out of memory); the
+    /// caller then clips by scissor alone and must not call `pop_mask_coverage` for this mask.
+    fn push_mask_coverage(&mut self, verts: &[Vertex2], indices: &[u16]) -> bool {
+        let _ = (verts, indices);
+        false
+    }
+
+    /// Undo the most recent successful `push_mask_coverage`.
+    fn pop_mask_coverage(&mut self) {}
+
+    /// Draw textured triangles.
+    ///
+    /// Samples the nearest texel, or blends the four neighbors when `bilinear` is set.
+    /// UVs outside `[0, 1]` clamp to the edge texels, or wrap when `repeat` is set.
+    /// `blend` selects how texels combine with the framebuffer.
     fn draw_tris_textured(
         &mut self,
         verts: &[TexVertex],
         indices: &[u16],
         src: &BitmapSurface,
         color_transform: Option<ColorTransform>,
+        bilinear: bool,
+        repeat: bool,
+        blend: DrawBlend,
     );
 
     /// Fill a set of triangles with an opaque solid color.
@@ -43,6 +127,100 @@ pub trait RenderDevice {
     /// `verts` are in shape-local pixel units; `(tx, ty)` is a per-draw translation applied by the device.
     fn fill_tris_solid(&mut self, verts: &[Vertex2], indices: &[u16], tx: i32, ty: i32, r: u8, g: u8, b: u8);
 
+    /// Fill a set of triangles with `(r, g, b)` blended over the framebuffer at straight alpha `a`.
+    ///
+    /// The default implementation skips fully transparent draws and fills the rest opaque.
+    fn fill_tris_solid_alpha(&mut self, verts: &[Vertex2], indices: &[u16], tx: i32, ty: i32, r: u8, g: u8, b: u8, a: u8) {
+        if a != 0 {
+            self.fill_tris_solid(verts, indices, tx, ty, r, g, b);
+        }
+    }
+
+    /// Fill a set of triangles with `(r, g, b)` at alpha `a`, combined with the framebuffer by `blend`.
+    ///
+    /// The default implementation ignores `blend` and composites normally.
+    fn fill_tris_solid_blend(
+        &mut self,
+        verts: &[Vertex2],
+        indices: &[u16],
+        r: u8,
+        g: u8,
+        b: u8,
+        a: u8,
+        blend: DrawBlend,
+    ) {
+        let _ = blend;
+        self.fill_tris_solid_alpha(verts, indices, 0, 0, r, g, b, a);
+    }
+
+    /// Fill a set of triangles with a diagonal hatch of `(r, g, b)` and a darker shade.
+    ///
+    /// Used as a placeholder for fill paints the renderer cannot draw yet. The default
+    /// implementation fills solid.
+    fn fill_tris_solid_hatched(&mut self, verts: &[Vertex2], indices: &[u16], tx: i32, ty: i32, r: u8, g: u8, b: u8) {
+        self.fill_tris_solid(verts, indices, tx, ty, r, g, b);
+    }
+
+    /// Fill screen-space triangles with a gradient sampled per pixel, blended over the
+    /// framebuffer by the ramp's alpha and `blend`.
+    ///
+    /// The default implementation samples the ramp at each vertex and interpolates with
+    /// `fill_tris_gouraud`, which is exact for linear ramps between two stops (and ignores `blend`).
+    fn fill_tris_gradient(&mut self, verts: &[Vertex2], indices: &[u16], gradient: &GradientFill, blend: DrawBlend) {
+        let _ = blend;
+        let colors: Vec<[u8; 4]> = verts
+            .iter()
+            .map(|v| {
+                let (u, w) = gradient.screen_to_gradient.apply(v.x as f32 + 0.5, v.y as f32 + 0.5);
+                gradient.lut[gradient.ramp_index(u, w)]
+            })
+            .collect();
+        self.fill_tris_gouraud(verts, &colors, indices);
+    }
+
+    /// Fill screen-space triangles with per-vertex RGBA colors interpolated across each triangle.
+    ///
+    /// `colors` is parallel to `verts`. The default implementation fills each triangle
+    /// with its first vertex's color (alpha ignored).
+    fn fill_tris_gouraud(&mut self, verts: &[Vertex2], colors: &[[u8; 4]], indices: &[u16]) {
+        for tri in indices.chunks_exact(3) {
+            let Some(&[r, g, b, _]) = colors.get(tri[0] as usize) else { continue; };
+            self.fill_tris_solid(verts, tri, 0, 0, r, g, b);
+        }
+    }
+
+    /// Fill a set of triangles whose screen-space vertices are in 16.16 fixed point.
+    ///
+    /// The default implementation rounds to whole pixels and forwards to `fill_tris_solid`.
+    fn fill_tris_solid_fixed(&mut self, verts: &[FixedVertex], indices: &[u16], r: u8, g: u8, b: u8) {
+        let half = 1i32 << (FIXED_VERTEX_SHIFT - 1);
+        let rounded: Vec<Vertex2> = verts
+            .iter()
+            .map(|v| Vertex2 {
+                x: (v.x + half) >> FIXED_VERTEX_SHIFT,
+                y: (v.y + half) >> FIXED_VERTEX_SHIFT,
+            })
+            .collect();
+        self.fill_tris_solid(&rounded, indices, 0, 0, r, g, b);
+    }
+
+    /// Draw a line `width` pixels wide (at least 1px) between two screen-space points.
+    ///
+    /// Used for hairline strokes, which can collapse to nothing as triangles. The default
+    /// implementation fills a quad at least 1px wide.
+    fn draw_thick_line(&mut self, x0: f32, y0: f32, x1: f32, y1: f32, width: f32, r: u8, g: u8, b: u8) {
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let len = (dx * dx + dy * dy).sqrt();
+        if len <= f32::EPSILON {
+            return;
+        }
+        let half = width.max(1.0) * 0.5;
+        let (nx, ny) = (-dy / len * half, dx / len * half);
+        let corner = |x: f32, y: f32| Vertex2 { x: x.round() as i32, y: y.round() as i32 };
+        let verts = [corner(x0 + nx, y0 + ny), corner(x1 + nx, y1 + ny), corner(x1 - nx, y1 - ny), corner(x0 - nx, y0 - ny)];
+        self.fill_tris_solid(&verts, &[0, 1, 2, 0, 2, 3], 0, 0, r, g, b);
+    }
+
     /// Optional debug: draw triangle edges (wireframe).
     fn draw_tris_wireframe(&mut self, verts: &[Vertex2], indices: &[u16], tx: i32, ty: i32, r: u8, g: u8, b: u8);
 
@@ -92,9 +270,19 @@ pub trait RenderDevice {
         }
     }
 
-    /// Called at the beginning of each frame.
-    fn begin_frame(&mut self);
+    /// Called at the beginning of each frame. Returns false when there is no surface to draw
+    /// into this frame (the renderer skips it).
+    fn begin_frame(&mut self) -> bool;
 
     /// Called at the end of each frame.
     fn end_frame(&mut self);
+
+    /// Copy the displayed surface into `out` as row-major RGBA8 (opaque alpha) and return its
+    /// `(width, height)`.
+    ///
+    /// Returns `None` (leaving `out` empty) when there is no surface to read.
+    fn read_pixels(&self, out: &mut Vec<u8>) -> Option<(u32, u32)> {
+        out.clear();
+        None
+    }
 }
@@ -1,10 +1,14 @@
 use crate::render::device::RenderDevice;
 #[cfg(debug_assertions)]
 use crate::render::device::fb3ds;
-use crate::render::frame::{ColorTransform, FramePacket, Matrix2D, RectI, RenderCmd, TexVertex};
+use crate::render::frame::{
+    ColorTransform, DrawBlend, FixedVertex, FramePacket, GradientFill, GradientKind, Matrix2D, RectI, RenderCmd, TexVertex,
+    GRADIENT_LUT_SIZE, MAX_FOCAL_POINT,
+};
 use crate::render::SharedCaches;
 use crate::render::cache::bitmaps::BitmapCache;
-use crate::render::cache::shapes::Vertex2;
+use crate::render::cache::shapes::{FillPaint, GradientPaint, ShapeCache, ShapeKey, Vertex2, HAIRLINE_MAX_PX};
+use crate::render::debug_font;
 use crate::runlog;
 use crate::util::config;
 
@@ -38,6 +42,41 @@ fn counter_fetch_add(counter: &CounterAtomic, value: u64) -> u64 {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum BlendMode {
     Opaque,
+    /// Straight alpha over the framebuffer with a constant per-draw alpha.
+    Alpha(u8),
+    /// Saturating add of the source scaled by alpha (255 for textured meshes, which use texel alpha).
+    Add(u8),
+    /// Per-channel multiply; alpha as for `Add`.
+    Multiply(u8),
+}
+
+impl BlendMode {
+    /// Mesh blend for a solid draw at `alpha` under the movie's current blend mode.
+    fn solid(draw: DrawBlend, alpha: u8) -> Self {
+        match draw {
+            DrawBlend::Normal if alpha == 255 => BlendMode::Opaque,
+            DrawBlend::Normal => BlendMode::Alpha(alpha),
+            DrawBlend::Add => BlendMode::Add(alpha),
+            DrawBlend::Multiply => BlendMode::Multiply(alpha),
+        }
+    }
+
+    /// Mesh blend for a textured draw; texel alpha supplies the coverage.
+    fn textured(draw: DrawBlend) -> Self {
+        match draw {
+            DrawBlend::Normal => BlendMode::Opaque,
+            DrawBlend::Add => BlendMode::Add(255),
+            DrawBlend::Multiply => BlendMode::Multiply(255),
+        }
+    }
+
+    fn draw_blend(self) -> DrawBlend {
+        match self {
+            BlendMode::Opaque | BlendMode::Alpha(_) => DrawBlend::Normal,
+            BlendMode::Add(_) => DrawBlend::Add,
+            BlendMode::Multiply(_) => DrawBlend::Multiply,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -61,11 +100,18 @@ struct MeshState {
     blend: BlendMode,
     color: Option<[u8; 3]>,
     color_transform: Option<ColorTransformKey>,
+    /// Bilinear texture sampling (textured meshes only).
+    smoothing: bool,
+    /// Wrap UVs instead of clamping (repeating bitmap fills).
+    repeat: bool,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum MeshKind {
     Solid,
+    SolidFixed,
+    /// Solid mesh drawn with a diagonal hatch (placeholder for unsupported paints).
+    Hatched,
     Wireframe,
     Textured,
 }
@@ -73,6 +119,7 @@ enum MeshKind {
 #[derive(Clone, Debug)]
 enum MeshData {
     Solid { verts: Vec<Vertex2>, indices: Vec<u16> },
+    SolidFixed { verts: Vec<FixedVertex>, indices: Vec<u16> },
     Textured { verts: Vec<TexVertex>, indices: Vec<u16>, color_transform: Option<ColorTransform> },
 }
 
@@ -94,10 +141,68 @@ impl FrameQueue {
     }
 }
 
+/// Buffers handed back by `flush_frame`, reused for the next queued meshes so steady-state
+/// frames stop allocating a vertex and index `Vec` per draw.
+#[derive(Default)]
+struct BufferPool {
+    verts: Vec<Vec<Vertex2>>,
+    indices: Vec<Vec<u16>>,
+}
+
+/// Buffers kept per kind; enough for a frame's worth of batches without hoarding memory.
+const MAX_POOLED_BUFFERS: usize = 64;
+
+impl BufferPool {
+    fn take_verts(&mut self) -> Vec<Vertex2> {
+        self.verts.pop().unwrap_or_default()
+    }
+
+    fn indices_from(&mut self, src: &[u16]) -> Vec<u16> {
+        let mut out = self.indices.pop().unwrap_or_default();
+        out.extend_from_slice(src);
+        out
+    }
+
+    fn recycle_solid(&mut self, mut verts: Vec<Vertex2>, mut indices: Vec<u16>) {
+        if self.verts.len() < MAX_POOLED_BUFFERS {
+            verts.clear();
+            self.verts.push(verts);
+        }
+        if self.indices.len() < MAX_POOLED_BUFFERS {
+            indices.clear();
+            self.indices.push(indices);
+        }
+    }
+
+    fn recycle(&mut self, data: MeshData) {
+        match data {
+            MeshData::Solid { verts, indices } => self.recycle_solid(verts, indices),
+            MeshData::SolidFixed { mut indices, .. } | MeshData::Textured { mut indices, .. } => {
+                if self.indices.len() < MAX_POOLED_BUFFERS {
+                    indices.clear();
+                    self.indices.push(indices);
+                }
+            }
+        }
+    }
+}
+
 pub struct CommandExecutor {
     frame_queue: FrameQueue,
+    pool: BufferPool,
+    /// Transformed vertices for draws that go straight to the device (gradients).
+    scratch_verts: Vec<Vertex2>,
 }
 
+const WIREFRAME_STATE: MeshState = MeshState {
+    texture: None,
+    blend: BlendMode::Opaque,
+    color: Some([255, 255, 255]),
+    color_transform: None,
+    smoothing: false,
+    repeat: false,
+};
+
 const DEBUG_AFFINE_VERTS: [Vertex2; 4] = [
     Vertex2 { x: 0, y: 0 },
     Vertex2 { x: 40, y: 0 },
@@ -106,11 +211,16 @@ const DEBUG_AFFINE_VERTS: [Vertex2; 4] = [
 ];
 const DEBUG_AFFINE_INDICES: [u16; 6] = [0, 1, 2, 0, 2, 3];
 
+/// Triangle count at which the heatmap overlay turns fully red.
+const TRI_HEAT_MAX: u32 = 512;
+const TRI_HEAT_ALPHA: u8 = 112;
+
 static MESH_WARN_COUNT: AtomicU32 = AtomicU32::new(0);
 static TEXTURE_WARN_COUNT: AtomicU32 = AtomicU32::new(0);
 static STROKE_WARN_COUNT: AtomicU32 = AtomicU32::new(0);
 static TEXT_MESH_WARN_COUNT: AtomicU32 = AtomicU32::new(0);
 static MASK_WARN_COUNT: AtomicU32 = AtomicU32::new(0);
+static MASK_OVERSIZED_WARN_COUNT: AtomicU32 = AtomicU32::new(0);
 static FRAME_COUNTER: CounterAtomic = counter_init(0);
 static FILL_DRAW_COUNT: AtomicU32 = AtomicU32::new(0);
 static FILL_FALLBACK_COUNT: AtomicU32 = AtomicU32::new(0);
@@ -121,8 +231,17 @@ static STROKE_FALLBACK_COUNT: AtomicU32 = AtomicU32::new(0);
 static LAST_MESH_TRIS: AtomicU32 = AtomicU32::new(0);
 static LAST_RECT_FASTPATH: AtomicU32 = AtomicU32::new(0);
 static LAST_BOUNDS_FALLBACKS: AtomicU32 = AtomicU32::new(0);
-static FILL_ALPHA_WARN_COUNT: AtomicU32 = AtomicU32::new(0);
+static LAST_FILL_DRAWS: AtomicU32 = AtomicU32::new(0);
+static LAST_FILL_FALLBACKS: AtomicU32 = AtomicU32::new(0);
+static LAST_TEXT_DRAWS: AtomicU32 = AtomicU32::new(0);
+static LAST_TEXT_FALLBACKS: AtomicU32 = AtomicU32::new(0);
+static LAST_STROKE_DRAWS: AtomicU32 = AtomicU32::new(0);
+static LAST_STROKE_FALLBACKS: AtomicU32 = AtomicU32::new(0);
+static LAST_BITMAP_DRAWS: AtomicU32 = AtomicU32::new(0);
+static LAST_RADIAL_FILLS: AtomicU32 = AtomicU32::new(0);
 const DRAW_SUMMARY_FRAMES: u32 = 1800;
+/// Base color for the hatch placeholder drawn in place of unsupported fill paints.
+const UNSUPPORTED_FILL_HATCH_RGB: (u8, u8, u8) = (160, 160, 160);
 
 fn apply_color_transform_rgba(mut rgba: [u8; 4], ct: Option<ColorTransform>) -> [u8; 4] {
     if let Some(ct) = ct {
@@ -134,6 +253,76 @@ fn apply_color_transform_rgba(mut rgba: [u8; 4], ct: Option<ColorTransform>) ->
     rgba
 }
 
+/// Sample the stop list into a LUT, applying the draw's color transform per entry.
+fn gradient_lut(paint: &GradientPaint, ct: Option<ColorTransform>) -> [[u8; 4]; GRADIENT_LUT_SIZE] {
+    let stops = &paint.stops[..paint.stop_count as usize];
+    let mut lut = [[0u8; 4]; GRADIENT_LUT_SIZE];
+    let Some(last) = stops.last() else {
+        return lut;
+    };
+    for (i, out) in lut.iter_mut().enumerate() {
+        let ratio = i as f32 * 255.0 / (GRADIENT_LUT_SIZE - 1) as f32;
+        let rgba = match stops.iter().position(|stop| stop.0 as f32 >= ratio) {
+            Some(0) => stops[0].1,
+            Some(k) => {
+                let (r0, c0) = stops[k - 1];
+                let (r1, c1) = stops[k];
+                let span = (r1 as f32 - r0 as f32).max(1.0);
+                let f = (ratio - r0 as f32) / span;
+                let mut c = [0u8; 4];
+                for ch in 0..4 {
+                    c[ch] = (c0[ch] as f32 + (c1[ch] as f32 - c0[ch] as f32) * f).round() as u8;
+                }
+                c
+            }
+            None => last.1,
+        };
+        *out = apply_color_transform_rgba(rgba, ct);
+    }
+    lut
+}
+
+/// Build the screen-space gradient for one draw; `None` if the draw transform is singular.
+fn gradient_fill(
+    paint: &GradientPaint,
+    kind: GradientKind,
+    focal_point: f32,
+    transform: Matrix2D,
+    ct: Option<ColorTransform>,
+) -> Option<GradientFill> {
+    let screen_to_local = transform.invert()?;
+    Some(GradientFill {
+        kind,
+        spread: paint.spread,
+        screen_to_gradient: paint.local_to_gradient.multiply(&screen_to_local),
+        focal_point: focal_point.clamp(-MAX_FOCAL_POINT, MAX_FOCAL_POINT),
+        lut: gradient_lut(paint, ct),
+    })
+}
+
+/// Screen-space textured vertices for a resolved bitmap fill.
+///
+/// `None` if the paint is not a bitmap fill or its bitmap is no longer cached. Returns the
+/// bitmap key, vertices, and the paint's smoothing and repeating flags; UVs leave `[0, 1]`
+/// wherever the fill extends past the bitmap, and the device clamps or wraps them.
+fn bitmap_fill_verts(paint: &FillPaint, verts: &[Vertex2], transform: Matrix2D, bitmaps: &BitmapCache) -> Option<(usize, Vec<TexVertex>, bool, bool)> {
+    let FillPaint::Bitmap { bitmap_key: Some(key), matrix, smoothing, repeating, .. } = *paint else {
+        return None;
+    };
+    let src = bitmaps.get(key)?;
+    let inv_w = 1.0 / src.source_width.max(1) as f32;
+    let inv_h = 1.0 / src.source_height.max(1) as f32;
+    let tex_verts = verts
+        .iter()
+        .map(|v| {
+            let (x, y) = transform.apply(v.x as f32, v.y as f32);
+            let (u, t) = matrix.apply(v.x as f32, v.y as f32);
+            TexVertex { x, y, u: u * inv_w, v: t * inv_h }
+        })
+        .collect();
+    Some((key, tex_verts, smoothing, repeating))
+}
+
 fn rect_intersects_surface(rect: RectI, sw: i32, sh: i32) -> bool {
     if rect.w <= 0 || rect.h <= 0 {
         return false;
@@ -180,22 +369,89 @@ fn is_integer_translation(transform: Matrix2D) -> Option<(i32, i32)> {
     None
 }
 
-fn transform_mesh_vertices(verts: &[Vertex2], transform: Matrix2D) -> Vec<Vertex2> {
+/// Append `verts` transformed to whole screen pixels onto `out`.
+fn extend_transformed(out: &mut Vec<Vertex2>, verts: &[Vertex2], transform: Matrix2D) {
     if let Some((tx, ty)) = is_integer_translation(transform) {
-        return verts
-            .iter()
-            .map(|v| Vertex2 { x: v.x + tx, y: v.y + ty })
-            .collect();
+        out.extend(verts.iter().map(|v| Vertex2 { x: v.x + tx, y: v.y + ty }));
+        return;
     }
+    out.extend(verts.iter().map(|v| {
+        let (x, y) = transform.apply(v.x as f32, v.y as f32);
+        Vertex2 { x: x.round() as i32, y: y.round() as i32 }
+    }));
+}
+
+/// Like `extend_transformed`, but keeps the result in 16.16 fixed point for the sub-pixel path.
+fn transform_mesh_vertices_fixed(verts: &[Vertex2], transform: Matrix2D) -> Vec<FixedVertex> {
     verts
         .iter()
         .map(|v| {
             let (x, y) = transform.apply(v.x as f32, v.y as f32);
-            Vertex2 { x: x.round() as i32, y: y.round() as i32 }
+            FixedVertex::from_f32(x, y)
         })
         .collect()
 }
 
+/// Average linear scale of `transform` (square root of the area scale).
+fn transform_scale(transform: Matrix2D) -> f32 {
+    (transform.a * transform.d - transform.b * transform.c).abs().sqrt()
+}
+
+/// Draw a hairline stroke polyline as device lines, which never drop below 1px coverage.
+fn draw_hairline<D: RenderDevice>(device: &mut D, line: &[(f32, f32)], closed: bool, transform: Matrix2D, width: f32, color: [u8; 3]) {
+    let [r, g, b] = color;
+    let points: Vec<(f32, f32)> = line.iter().map(|&(x, y)| transform.apply(x, y)).collect();
+    for seg in points.windows(2) {
+        device.draw_thick_line(seg[0].0, seg[0].1, seg[1].0, seg[1].1, width, r, g, b);
+    }
+    if closed && points.len() > 2 {
+        let (first, last) = (points[0], points[points.len() - 1]);
+        device.draw_thick_line(last.0, last.1, first.0, first.1, width, r, g, b);
+    }
+}
+
+/// All fill meshes of a mask shape merged into one screen-space mesh (`None` if it has none).
+fn mask_coverage_mesh(shapes: &ShapeCache, key: ShapeKey, transform: Matrix2D) -> Option<(Vec<Vertex2>, Vec<u16>)> {
+    let mut verts: Vec<Vertex2> = Vec::new();
+    let mut indices: Vec<u16> = Vec::new();
+    for fi in 0..shapes.fill_count(key) {
+        let Some(mesh) = shapes.get_fill_mesh(key, fi) else { continue; };
+        if verts.len() + mesh.verts.len() > u16::MAX as usize {
+            return None;
+        }
+        let offset = verts.len() as u16;
+        extend_transformed(&mut verts, &mesh.verts, transform);
+        indices.extend(mesh.indices.iter().map(|i| i + offset));
+    }
+    if indices.is_empty() { None } else { Some((verts, indices)) }
+}
+
+/// `a ∪ b` when they share a whole edge (same row span side by side, or same column span
+/// stacked), so the union is exactly their two areas.
+fn merge_adjacent_rects(a: RectI, b: RectI) -> Option<RectI> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    let side_by_side = a.y == b.y && a.h == b.h && (a.x + a.w == b.x || b.x + b.w == a.x);
+    let stacked = a.x == b.x && a.w == b.w && (a.y + a.h == b.y || b.y + b.h == a.y);
+    (side_by_side || stacked).then(|| a.union(b))
+}
+
+/// Screen rect covered by `local` under a scale+translation transform (`None` if empty).
+///
+/// Edges round like `extend_transformed` rounds vertices, so the fast path covers the same
+/// pixels the triangle path would.
+fn scaled_rect(local: RectI, transform: Matrix2D) -> Option<RectI> {
+    let (ax, ay) = transform.apply(local.x as f32, local.y as f32);
+    let (bx, by) = transform.apply((local.x + local.w) as f32, (local.y + local.h) as f32);
+    let x0 = ax.min(bx).round() as i32;
+    let y0 = ay.min(by).round() as i32;
+    let x1 = ax.max(bx).round() as i32;
+    let y1 = ay.max(by).round() as i32;
+    let rect = RectI { x: x0, y: y0, w: x1.saturating_sub(x0), h: y1.saturating_sub(y0) };
+    (!rect.is_empty()).then_some(rect)
+}
+
 fn mesh_is_axis_aligned_rect(mesh_verts: &[crate::render::cache::shapes::Vertex2], indices: &[u16]) -> Option<RectI> {
     // Fast-path: the common 2-triangle rectangle mesh.
     if mesh_verts.len() != 4 || indices.len() != 6 {
@@ -228,13 +484,77 @@ fn mesh_is_axis_aligned_rect(mesh_verts: &[crate::render::cache::shapes::Vertex2
 
 impl CommandExecutor {
     pub fn new() -> Self {
-        Self { frame_queue: FrameQueue::default() }
+        Self { frame_queue: FrameQueue::default(), pool: BufferPool::default(), scratch_verts: Vec::new() }
     }
 
     fn draw_mesh(&mut self, mesh: QueuedMesh) {
         self.frame_queue.entries.push(mesh);
     }
 
+    /// Queue a solid mesh, keeping sub-pixel vertex positions when `subpixel_shapes` is on.
+    ///
+    /// Integer translations stay on the cheaper `Vertex2` path since they land on whole pixels anyway.
+    /// `hatched` meshes are placeholders for unsupported paints and always use whole-pixel vertices,
+    /// as do translucent and non-normal `draw_blend` meshes, which the fixed-point path can't blend.
+    /// With `wireframe`, a white outline pass is queued from the same transformed vertices.
+    fn draw_solid_mesh(
+        &mut self,
+        verts: &[Vertex2],
+        indices: &[u16],
+        transform: Matrix2D,
+        color: [u8; 3],
+        alpha: u8,
+        hatched: bool,
+        draw_blend: DrawBlend,
+        wireframe: bool,
+    ) {
+        let blend = BlendMode::solid(draw_blend, alpha);
+        let state = MeshState {
+            texture: None,
+            blend,
+            color: Some(color),
+            color_transform: None,
+            smoothing: false,
+            repeat: false,
+        };
+        let mut wire_verts = None;
+        if !hatched && blend == BlendMode::Opaque && config::subpixel_shapes_enabled() && is_integer_translation(transform).is_none() {
+            let indices = self.pool.indices_from(indices);
+            self.draw_mesh(QueuedMesh {
+                kind: MeshKind::SolidFixed,
+                state,
+                data: MeshData::SolidFixed { verts: transform_mesh_vertices_fixed(verts, transform), indices },
+            });
+        } else {
+            let mut out = self.pool.take_verts();
+            extend_transformed(&mut out, verts, transform);
+            if wireframe {
+                let mut copy = self.pool.take_verts();
+                copy.extend_from_slice(&out);
+                wire_verts = Some(copy);
+            }
+            let indices = self.pool.indices_from(indices);
+            self.draw_mesh(QueuedMesh {
+                kind: if hatched { MeshKind::Hatched } else { MeshKind::Solid },
+                state,
+                data: MeshData::Solid { verts: out, indices },
+            });
+        }
+        if wireframe {
+            let wire_verts = wire_verts.unwrap_or_else(|| {
+                let mut out = self.pool.take_verts();
+                extend_transformed(&mut out, verts, transform);
+                out
+            });
+            let indices = self.pool.indices_from(indices);
+            self.draw_mesh(QueuedMesh {
+                kind: MeshKind::Wireframe,
+                state: WIREFRAME_STATE,
+                data: MeshData::Solid { verts: wire_verts, indices },
+            });
+        }
+    }
+
     fn flush_if_pending<D: RenderDevice>(&mut self, device: &mut D, bitmaps: &BitmapCache) {
         if self.frame_queue.entries.is_empty() {
             return;
@@ -252,7 +572,19 @@ impl CommandExecutor {
                         (MeshData::Solid { verts, indices }, MeshData::Solid { verts: next_verts, indices: next_indices }) => {
                             if verts.len() + next_verts.len() > u16::MAX as usize {
                                 Self::submit_batch(device, bitmaps, batch);
-                                *batch = QueuedMesh { kind, state, data: MeshData::Solid { verts: next_verts, indices: next_indices } };
+                                let next = QueuedMesh { kind, state, data: MeshData::Solid { verts: next_verts, indices: next_indices } };
+                                self.pool.recycle(std::mem::replace(batch, next).data);
+                                continue;
+                            }
+                            let offset = verts.len() as u16;
+                            verts.extend_from_slice(&next_verts);
+                            indices.extend(next_indices.iter().map(|i| i + offset));
+                            self.pool.recycle_solid(next_verts, next_indices);
+                        }
+                        (MeshData::SolidFixed { verts, indices }, MeshData::SolidFixed { verts: next_verts, indices: next_indices }) => {
+                            if verts.len() + next_verts.len() > u16::MAX as usize {
+                                Self::submit_batch(device, bitmaps, batch);
+                                *batch = QueuedMesh { kind, state, data: MeshData::SolidFixed { verts: next_verts, indices: next_indices } };
                                 continue;
                             }
                             let offset = verts.len() as u16;
@@ -280,18 +612,21 @@ impl CommandExecutor {
                         }
                         (_, other) => {
                             Self::submit_batch(device, bitmaps, batch);
-                            *batch = QueuedMesh { kind, state, data: other };
+                            self.pool.recycle(std::mem::replace(batch, QueuedMesh { kind, state, data: other }).data);
                         }
                     }
                     continue;
                 }
                 Self::submit_batch(device, bitmaps, batch);
             }
-            current = Some(QueuedMesh { kind, state, data });
+            if let Some(done) = current.replace(QueuedMesh { kind, state, data }) {
+                self.pool.recycle(done.data);
+            }
         }
 
-        if let Some(batch) = current.as_ref() {
-            Self::submit_batch(device, bitmaps, batch);
+        if let Some(batch) = current {
+            Self::submit_batch(device, bitmaps, &batch);
+            self.pool.recycle(batch.data);
         }
     }
 
@@ -299,7 +634,24 @@ impl CommandExecutor {
         match (&batch.kind, &batch.data) {
             (MeshKind::Solid, MeshData::Solid { verts, indices }) => {
                 if let Some([r, g, b]) = batch.state.color {
-                    device.fill_tris_solid(verts, indices, 0, 0, r, g, b);
+                    match batch.state.blend {
+                        BlendMode::Opaque => device.fill_tris_solid(verts, indices, 0, 0, r, g, b),
+                        BlendMode::Alpha(a) => device.fill_tris_solid_alpha(verts, indices, 0, 0, r, g, b, a),
+                        BlendMode::Add(a) => device.fill_tris_solid_blend(verts, indices, r, g, b, a, DrawBlend::Add),
+                        BlendMode::Multiply(a) => {
+                            device.fill_tris_solid_blend(verts, indices, r, g, b, a, DrawBlend::Multiply)
+                        }
+                    }
+                }
+            }
+            (MeshKind::SolidFixed, MeshData::SolidFixed { verts, indices }) => {
+                if let Some([r, g, b]) = batch.state.color {
+                    device.fill_tris_solid_fixed(verts, indices, r, g, b);
+                }
+            }
+            (MeshKind::Hatched, MeshData::Solid { verts, indices }) => {
+                if let Some([r, g, b]) = batch.state.color {
+                    device.fill_tris_solid_hatched(verts, indices, 0, 0, r, g, b);
                 }
             }
             (MeshKind::Wireframe, MeshData::Solid { verts, indices }) => {
@@ -309,7 +661,15 @@ impl CommandExecutor {
             }
             (MeshKind::Textured, MeshData::Textured { verts, indices, color_transform }) => {
                 if let Some(texture) = batch.state.texture.and_then(|key| bitmaps.get(key)) {
-                    device.draw_tris_textured(verts, indices, texture, *color_transform);
+                    device.draw_tris_textured(
+                        verts,
+                        indices,
+                        texture,
+                        *color_transform,
+                        batch.state.smoothing,
+                        batch.state.repeat,
+                        batch.state.blend.draw_blend(),
+                    );
                 }
             }
             _ => {}
@@ -323,34 +683,63 @@ impl CommandExecutor {
         // Lock caches once per frame.
         let bitmaps = caches.bitmaps.lock().unwrap();
         let shapes = caches.shapes.lock().unwrap();
-        let mut mask_stack: Vec<RectI> = Vec::new();
+        // Parallel to the device scissor stack: whether that mask also pushed device coverage.
+        let mut coverage_stack: Vec<bool> = Vec::new();
+        let mut blend_stack: Vec<DrawBlend> = Vec::new();
         self.frame_queue.clear();
+        device.set_edge_antialias(config::aa_edges_enabled());
 
         let mut mesh_tris = 0u32;
         let mut rect_fastpath = 0u32;
         let mut bounds_fallbacks = 0u32;
+        let mut fill_draws = 0u32;
+        let mut fill_fallbacks = 0u32;
+        let mut text_draws = 0u32;
+        let mut text_fallbacks = 0u32;
+        let mut stroke_draws = 0u32;
+        let mut stroke_fallbacks = 0u32;
+        let mut bitmap_draws = 0u32;
+        let mut radial_fills = 0u32;
 
-        for cmd in &packet.cmds {
+        let mut cmds = packet.cmds.iter().peekable();
+        while let Some(cmd) = cmds.next() {
+            let draw_blend = blend_stack.last().copied().unwrap_or_default();
             match cmd {
                 RenderCmd::FillRect { rect, color_key, wireframe } => {
                     self.flush_if_pending(device, &bitmaps);
-                    let (cr, cg, cb) = color_from_key(*color_key);
-                    device.fill_rect(*rect, cr, cg, cb);
+                    let (cr, cg, cb) = fallback_color(*color_key);
+                    let mut rect = *rect;
+                    // Tiled fallbacks: fold the following same-color rects that share a full edge
+                    // into one fill. Wireframe rects keep their own outlines.
+                    if !*wireframe {
+                        while let Some(RenderCmd::FillRect { rect: next, color_key: next_key, wireframe: false }) = cmds.peek() {
+                            if fallback_color(*next_key) != (cr, cg, cb) {
+                                break;
+                            }
+                            let Some(merged) = merge_adjacent_rects(rect, *next) else {
+                                break;
+                            };
+                            rect = merged;
+                            cmds.next();
+                        }
+                    }
+                    device.fill_rect(rect, cr, cg, cb);
                     if *wireframe {
-                        device.stroke_rect(*rect, 255, 255, 255);
+                        device.stroke_rect(rect, 255, 255, 255);
                     }
                 }
                 RenderCmd::DrawShapeSolidFill { shape_key, fill_idx, transform, solid_rgba, color_transform, color_key, wireframe } => {
                     FILL_DRAW_COUNT.fetch_add(1, Ordering::Relaxed);
+                    fill_draws = fill_draws.saturating_add(1);
                     let solid_rgba = solid_rgba.map(|rgba| apply_color_transform_rgba(rgba, *color_transform));
-                    let (fallback_r, fallback_g, fallback_b) = if let Some([r, g, b, a]) = solid_rgba {
-                        if a != 255 && FILL_ALPHA_WARN_COUNT.fetch_add(1, Ordering::Relaxed) < 4 {
-                            // Alpha blending for vector fills is future work; current Step 3 is opaque.
-                            runlog::warn_line("fill_alpha ignored; vector fills are opaque in Step 3");
-                        }
+                    let hatched = solid_rgba.is_none() && config::unsupported_fill_hatch_enabled();
+                    let fill_alpha = solid_rgba.map_or(255, |[_, _, _, a]| a);
+                    let (fallback_r, fallback_g, fallback_b) = if let Some([r, g, b, _]) = solid_rgba {
                         (r, g, b)
+                    } else if hatched {
+                        UNSUPPORTED_FILL_HATCH_RGB
                     } else {
-                        color_from_key(*color_key)
+                        fallback_color(*color_key)
                     };
                     // Early reject by transformed bounds (very common win for offscreen sprites).
                     if let Some(b) = shapes.get_bounds(*shape_key) {
@@ -369,8 +758,42 @@ impl CommandExecutor {
                         let indices_ok = !mesh.indices.is_empty() && mesh.indices.len() % 3 == 0;
                         let verts_ok = !mesh.verts.is_empty();
                         if indices_ok && verts_ok {
+                            if let Some((kind, paint, focal_point)) = mesh.paint.gradient() {
+                                mesh_tris = mesh_tris.saturating_add((mesh.indices.len() as u32) / 3);
+                                if kind == GradientKind::Radial {
+                                    radial_fills = radial_fills.saturating_add(1);
+                                }
+                                // Singular transforms collapse the fill to nothing; skip it.
+                                if let Some(gradient) = gradient_fill(paint, kind, focal_point, *transform, *color_transform) {
+                                    self.flush_if_pending(device, &bitmaps);
+                                    self.scratch_verts.clear();
+                                    extend_transformed(&mut self.scratch_verts, &mesh.verts, *transform);
+                                    device.fill_tris_gradient(&self.scratch_verts, &mesh.indices, &gradient, draw_blend);
+                                    if *wireframe {
+                                        device.draw_tris_wireframe(&self.scratch_verts, &mesh.indices, 0, 0, 255, 255, 255);
+                                    }
+                                }
+                            } else if let Some((bitmap_key, verts, smoothing, repeating)) = bitmap_fill_verts(&mesh.paint, &mesh.verts, *transform, &bitmaps)
+                                .filter(|_| config::textured_bitmaps_enabled())
+                            {
+                                mesh_tris = mesh_tris.saturating_add((mesh.indices.len() as u32) / 3);
+                                let state = MeshState {
+                                    texture: Some(bitmap_key),
+                                    blend: BlendMode::textured(draw_blend),
+                                    color: None,
+                                    color_transform: ColorTransformKey::from_transform(*color_transform),
+                                    smoothing: smoothing && config::bilinear_enabled(),
+                                    repeat: repeating,
+                                };
+                                self.draw_mesh(QueuedMesh {
+                                    kind: MeshKind::Textured,
+                                    state,
+                                    data: MeshData::Textured { verts, indices: mesh.indices.clone(), color_transform: *color_transform },
+                                });
                             // Fast-path: common rect mesh is much faster to draw with `fill_rect`.
-                            if let Some(local) = mesh_is_axis_aligned_rect(&mesh.verts, &mesh.indices) {
+                            } else if let Some(local) = mesh_is_axis_aligned_rect(&mesh.verts, &mesh.indices)
+                                .filter(|_| !hatched && fill_alpha == 255 && draw_blend == DrawBlend::Normal)
+                            {
                                 if let Some((tx, ty)) = int_translation {
                                     rect_fastpath = rect_fastpath.saturating_add(1);
                                     let rect = RectI { x: local.x + tx, y: local.y + ty, w: local.w, h: local.h };
@@ -379,26 +802,10 @@ impl CommandExecutor {
                                     if *wireframe {
                                         device.stroke_rect(rect, 255, 255, 255);
                                     }
-                                } else if transform.is_axis_aligned() {
+                                } else if transform.is_scale_translation() && !config::subpixel_shapes_enabled() {
+                                    // Scaled sprite rects: one `fill_rect` instead of two rasterized triangles.
                                     rect_fastpath = rect_fastpath.saturating_add(1);
-                                    let x0 = local.x as f32;
-                                    let y0 = local.y as f32;
-                                    let x1 = (local.x + local.w) as f32;
-                                    let y1 = (local.y + local.h) as f32;
-                                    let tx0 = transform.a * x0 + transform.tx;
-                                    let tx1 = transform.a * x1 + transform.tx;
-                                    let ty0 = transform.d * y0 + transform.ty;
-                                    let ty1 = transform.d * y1 + transform.ty;
-                                    let minx = tx0.min(tx1);
-                                    let maxx = tx0.max(tx1);
-                                    let miny = ty0.min(ty1);
-                                    let maxy = ty0.max(ty1);
-                                    let x = minx.floor() as i32;
-                                    let y = miny.floor() as i32;
-                                    let w = (maxx.ceil() as i32).saturating_sub(x);
-                                    let h = (maxy.ceil() as i32).saturating_sub(y);
-                                    if w > 0 && h > 0 {
-                                        let rect = RectI { x, y, w, h };
+                                    if let Some(rect) = scaled_rect(local, *transform) {
                                         self.flush_if_pending(device, &bitmaps);
                                         device.fill_rect(rect, cr, cg, cb);
                                         if *wireframe {
@@ -407,89 +814,11 @@ impl CommandExecutor {
                                     }
                                 } else {
                                     mesh_tris = mesh_tris.saturating_add((mesh.indices.len() as u32) / 3);
-                                    let verts = transform_mesh_vertices(&mesh.verts, *transform);
-                                    self.draw_mesh(QueuedMesh {
-                                        kind: MeshKind::Solid,
-                                        state: MeshState {
-                                            texture: None,
-                                            blend: BlendMode::Opaque,
-                                            color: Some([cr, cg, cb]),
-                                            color_transform: None,
-                                        },
-                                        data: MeshData::Solid { verts, indices: mesh.indices.clone() },
-                                    });
-                                    if *wireframe {
-                                        self.draw_mesh(QueuedMesh {
-                                            kind: MeshKind::Wireframe,
-                                            state: MeshState {
-                                                texture: None,
-                                                blend: BlendMode::Opaque,
-                                                color: Some([255, 255, 255]),
-                                                color_transform: None,
-                                            },
-                                            data: MeshData::Solid {
-                                                verts: transform_mesh_vertices(&mesh.verts, *transform),
-                                                indices: mesh.indices.clone(),
-                                            },
-                                        });
-                                    }
-                                }
-                            } else if let Some((tx, ty)) = int_translation {
-                                mesh_tris = mesh_tris.saturating_add((mesh.indices.len() as u32) / 3);
-                                let verts = transform_mesh_vertices(&mesh.verts, *transform);
-                                self.draw_mesh(QueuedMesh {
-                                    kind: MeshKind::Solid,
-                                    state: MeshState {
-                                        texture: None,
-                                        blend: BlendMode::Opaque,
-                                        color: Some([cr, cg, cb]),
-                                        color_transform: None,
-                                    },
-                                    data: MeshData::Solid { verts, indices: mesh.indices.clone() },
-                                });
-                                if *wireframe {
-                                    self.draw_mesh(QueuedMesh {
-                                        kind: MeshKind::Wireframe,
-                                        state: MeshState {
-                                            texture: None,
-                                            blend: BlendMode::Opaque,
-                                            color: Some([255, 255, 255]),
-                                            color_transform: None,
-                                        },
-                                        data: MeshData::Solid {
-                                            verts: transform_mesh_vertices(&mesh.verts, *transform),
-                                            indices: mesh.indices.clone(),
-                                        },
-                                    });
+                                    self.draw_solid_mesh(&mesh.verts, &mesh.indices, *transform, [cr, cg, cb], fill_alpha, hatched, draw_blend, *wireframe);
                                 }
                             } else {
                                 mesh_tris = mesh_tris.saturating_add((mesh.indices.len() as u32) / 3);
-                                let verts = transform_mesh_vertices(&mesh.verts, *transform);
-                                self.draw_mesh(QueuedMesh {
-                                    kind: MeshKind::Solid,
-                                    state: MeshState {
-                                        texture: None,
-                                        blend: BlendMode::Opaque,
-                                        color: Some([cr, cg, cb]),
-                                        color_transform: None,
-                                    },
-                                    data: MeshData::Solid { verts, indices: mesh.indices.clone() },
-                                });
-                                if *wireframe {
-                                    self.draw_mesh(QueuedMesh {
-                                        kind: MeshKind::Wireframe,
-                                        state: MeshState {
-                                            texture: None,
-                                            blend: BlendMode::Opaque,
-                                            color: Some([255, 255, 255]),
-                                            color_transform: None,
-                                        },
-                                        data: MeshData::Solid {
-                                            verts: transform_mesh_vertices(&mesh.verts, *transform),
-                                            indices: mesh.indices.clone(),
-                                        },
-                                    });
-                                }
+                                self.draw_solid_mesh(&mesh.verts, &mesh.indices, *transform, [cr, cg, cb], fill_alpha, hatched, draw_blend, *wireframe);
                             }
                         } else {
                             shapes.record_invalid_fill_mesh();
@@ -504,6 +833,7 @@ impl CommandExecutor {
 
                     if used_fallback {
                         FILL_FALLBACK_COUNT.fetch_add(1, Ordering::Relaxed);
+                        fill_fallbacks = fill_fallbacks.saturating_add(1);
                         if missing_mesh || invalid_mesh {
                             let n = MESH_WARN_COUNT.fetch_add(1, Ordering::Relaxed);
                             if n < 8 {
@@ -529,15 +859,16 @@ impl CommandExecutor {
                 }
                 RenderCmd::DrawTextSolidFill { shape_key, fill_idx, transform, solid_rgba, color_transform, color_key, wireframe } => {
                     TEXT_DRAW_COUNT.fetch_add(1, Ordering::Relaxed);
+                    text_draws = text_draws.saturating_add(1);
                     let solid_rgba = solid_rgba.map(|rgba| apply_color_transform_rgba(rgba, *color_transform));
-                    let (fallback_r, fallback_g, fallback_b) = if let Some([r, g, b, a]) = solid_rgba {
-                        if a != 255 && FILL_ALPHA_WARN_COUNT.fetch_add(1, Ordering::Relaxed) < 4 {
-                            // Alpha blending for vector fills is future work; current Step 3 is opaque.
-                            runlog::warn_line("fill_alpha ignored; vector fills are opaque in Step 3");
-                        }
+                    let hatched = solid_rgba.is_none() && config::unsupported_fill_hatch_enabled();
+                    let fill_alpha = solid_rgba.map_or(255, |[_, _, _, a]| a);
+                    let (fallback_r, fallback_g, fallback_b) = if let Some([r, g, b, _]) = solid_rgba {
                         (r, g, b)
+                    } else if hatched {
+                        UNSUPPORTED_FILL_HATCH_RGB
                     } else {
-                        color_from_key(*color_key)
+                        fallback_color(*color_key)
                     };
                     // Early reject by transformed bounds (very common win for offscreen text).
                     if let Some(b) = shapes.get_bounds(*shape_key) {
@@ -556,32 +887,7 @@ impl CommandExecutor {
                         let verts_ok = !mesh.verts.is_empty();
                         if indices_ok && verts_ok {
                             mesh_tris = mesh_tris.saturating_add((mesh.indices.len() as u32) / 3);
-                            let verts = transform_mesh_vertices(&mesh.verts, *transform);
-                            self.draw_mesh(QueuedMesh {
-                                kind: MeshKind::Solid,
-                                state: MeshState {
-                                    texture: None,
-                                    blend: BlendMode::Opaque,
-                                    color: Some([cr, cg, cb]),
-                                    color_transform: None,
-                                },
-                                data: MeshData::Solid { verts, indices: mesh.indices.clone() },
-                            });
-                            if *wireframe {
-                                self.draw_mesh(QueuedMesh {
-                                    kind: MeshKind::Wireframe,
-                                    state: MeshState {
-                                        texture: None,
-                                        blend: BlendMode::Opaque,
-                                        color: Some([255, 255, 255]),
-                                        color_transform: None,
-                                    },
-                                    data: MeshData::Solid {
-                                        verts: transform_mesh_vertices(&mesh.verts, *transform),
-                                        indices: mesh.indices.clone(),
-                                    },
-                                });
-                            }
+                            self.draw_solid_mesh(&mesh.verts, &mesh.indices, *transform, [cr, cg, cb], fill_alpha, hatched, draw_blend, *wireframe);
                         } else {
                             shapes.record_invalid_fill_mesh();
                             invalid_mesh = true;
@@ -595,6 +901,7 @@ impl CommandExecutor {
 
                     if used_fallback {
                         TEXT_FALLBACK_COUNT.fetch_add(1, Ordering::Relaxed);
+                        text_fallbacks = text_fallbacks.saturating_add(1);
                         let n = TEXT_MESH_WARN_COUNT.fetch_add(1, Ordering::Relaxed);
                         if n < 8 {
                             let kind = if missing_mesh { "missing_mesh" } else { "invalid_mesh" };
@@ -616,6 +923,7 @@ impl CommandExecutor {
                 }
                 RenderCmd::DrawShapeStroke { shape_key, stroke_idx, transform, r, g, b, wireframe } => {
                     STROKE_DRAW_COUNT.fetch_add(1, Ordering::Relaxed);
+                    stroke_draws = stroke_draws.saturating_add(1);
                     // Early reject by transformed bounds (very common win for offscreen strokes).
                     if let Some(b) = shapes.get_bounds(*shape_key) {
                         let tr = rect_aabb_transformed(b, *transform);
@@ -630,34 +938,13 @@ impl CommandExecutor {
                     if let Some(mesh) = shapes.get_stroke_mesh(*shape_key, *stroke_idx as usize) {
                         let indices_ok = !mesh.indices.is_empty() && mesh.indices.len() % 3 == 0;
                         let verts_ok = !mesh.verts.is_empty();
-                        if indices_ok && verts_ok {
+                        let line_px = mesh.width * transform_scale(*transform);
+                        if mesh.line.len() >= 2 && line_px <= HAIRLINE_MAX_PX && draw_blend == DrawBlend::Normal {
+                            self.flush_if_pending(device, &bitmaps);
+                            draw_hairline(device, &mesh.line, mesh.closed, *transform, line_px, [*r, *g, *b]);
+                        } else if indices_ok && verts_ok {
                             mesh_tris = mesh_tris.saturating_add((mesh.indices.len() as u32) / 3);
-                            let verts = transform_mesh_vertices(&mesh.verts, *transform);
-                            self.draw_mesh(QueuedMesh {
-                                kind: MeshKind::Solid,
-                                state: MeshState {
-                                    texture: None,
-                                    blend: BlendMode::Opaque,
-                                    color: Some([*r, *g, *b]),
-                                    color_transform: None,
-                                },
-                                data: MeshData::Solid { verts, indices: mesh.indices.clone() },
-                            });
-                            if *wireframe {
-                                self.draw_mesh(QueuedMesh {
-                                    kind: MeshKind::Wireframe,
-                                    state: MeshState {
-                                        texture: None,
-                                        blend: BlendMode::Opaque,
-                                        color: Some([255, 255, 255]),
-                                        color_transform: None,
-                                    },
-                                    data: MeshData::Solid {
-                                        verts: transform_mesh_vertices(&mesh.verts, *transform),
-                                        indices: mesh.indices.clone(),
-                                    },
-                                });
-                            }
+                            self.draw_solid_mesh(&mesh.verts, &mesh.indices, *transform, [*r, *g, *b], 255, false, draw_blend, *wireframe);
                         } else {
                             shapes.record_invalid_stroke_mesh();
                             invalid_mesh = true;
@@ -671,6 +958,7 @@ impl CommandExecutor {
 
                     if used_fallback {
                         STROKE_FALLBACK_COUNT.fetch_add(1, Ordering::Relaxed);
+                        stroke_fallbacks = stroke_fallbacks.saturating_add(1);
                         let n = STROKE_WARN_COUNT.fetch_add(1, Ordering::Relaxed);
                         if n < 8 {
                             let kind = if missing_mesh { "missing_mesh" } else { "invalid_mesh" };
@@ -697,40 +985,80 @@ impl CommandExecutor {
                         }
                         continue;
                     }
-                    let mut next = *rect;
-                    if let Some(prev) = mask_stack.last() {
-                        let x0 = next.x.max(prev.x);
-                        let y0 = next.y.max(prev.y);
-                        let x1 = (next.x + next.w).min(prev.x + prev.w);
-                        let y1 = (next.y + next.h).min(prev.y + prev.h);
-                        next = RectI { x: x0, y: y0, w: (x1 - x0).max(0), h: (y1 - y0).max(0) };
-                    }
-                    mask_stack.push(next);
-                    device.set_scissor(Some(next));
+                    device.push_scissor(*rect);
+                    coverage_stack.push(false);
                 }
-                RenderCmd::PushMaskShape { .. } => {
+                RenderCmd::PushMaskShape { shape_key, transform } => {
                     self.flush_if_pending(device, &bitmaps);
-                    let n = MASK_WARN_COUNT.fetch_add(1, Ordering::Relaxed);
-                    if n < 4 {
-                        runlog::warn_line("shape masks unsupported; ignoring");
+                    if !config::masks_enabled() {
+                        let n = MASK_WARN_COUNT.fetch_add(1, Ordering::Relaxed);
+                        if n < 4 {
+                            runlog::warn_line("masks disabled; ignoring mask");
+                        }
+                        continue;
+                    }
+                    let bounds = shapes.get_bounds(*shape_key).map(|b| rect_aabb_transformed(b, *transform));
+                    let tris = shapes.get_total_tri_count(*shape_key);
+                    let area = bounds.map(|b| b.w.max(0) as u64 * b.h.max(0) as u64).unwrap_or(0);
+                    let oversized = tris > config::mask_max_tris() || area > config::mask_max_area() as u64;
+                    // Keep the stack balanced for the matching `PopMask` even when the mask is ignored.
+                    let mut next = RectI { x: 0, y: 0, w: sw, h: sh };
+                    let mut coverage = false;
+                    match bounds {
+                        Some(b) => {
+                            if oversized && MASK_OVERSIZED_WARN_COUNT.fetch_add(1, Ordering::Relaxed) == 0 {
+                                runlog::warn_line(&format!(
+                                    "mask_shape_oversized shape={} tris={} area={}; clipping to bounds",
+                                    shape_key, tris, area
+                                ));
+                            }
+                            // The bounds scissor always applies; small enough masks are also rasterized
+                            // into device coverage so irregular outlines clip exactly.
+                            next = b;
+                            if !oversized {
+                                if let Some((verts, indices)) = mask_coverage_mesh(&shapes, *shape_key, *transform) {
+                                    coverage = device.push_mask_coverage(&verts, &indices);
+                                }
+                            }
+                        }
+                        None => {
+                            let n = MASK_WARN_COUNT.fetch_add(1, Ordering::Relaxed);
+                            if n < 4 {
+                                runlog::warn_line(&format!("mask_shape_missing shape={}; ignoring", shape_key));
+                            }
+                        }
                     }
+                    device.push_scissor(next);
+                    coverage_stack.push(coverage);
                 }
                 RenderCmd::PopMask => {
                     self.flush_if_pending(device, &bitmaps);
-                    if mask_stack.pop().is_some() {
-                        let rect = mask_stack.last().copied();
-                        device.set_scissor(rect);
-                    } else {
+                    if coverage_stack.pop() == Some(true) {
+                        device.pop_mask_coverage();
+                    }
+                    if !device.pop_scissor() {
                         let n = MASK_WARN_COUNT.fetch_add(1, Ordering::Relaxed);
                         if n < 4 {
                             runlog::warn_line("mask stack underflow");
                         }
-                        device.set_scissor(None);
                     }
                 }
-                RenderCmd::BlitBitmap { bitmap_key, transform, uv, color_transform } => {
+                RenderCmd::PushBlend { blend } => {
+                    // No flush: queued meshes carry their blend in `MeshState`, so batches split on it.
+                    let next = if *blend == DrawBlend::Normal { draw_blend } else { *blend };
+                    blend_stack.push(next);
+                }
+                RenderCmd::PopBlend => {
+                    blend_stack.pop();
+                }
+                RenderCmd::BlitBitmap { bitmap_key, transform, uv, color_transform, smoothing } => {
                     if let Some(src) = bitmaps.get(*bitmap_key) {
-                        let use_blit = transform.is_identity() && uv.is_full() && color_transform.is_none();
+                        bitmap_draws = bitmap_draws.saturating_add(1);
+                        let use_blit = transform.is_identity()
+                            && !src.is_downscaled()
+                            && uv.is_full()
+                            && color_transform.is_none()
+                            && draw_blend == DrawBlend::Normal;
                         if use_blit {
                             self.flush_if_pending(device, &bitmaps);
                             device.blit_rgba(transform.tx.round() as i32, transform.ty.round() as i32, src);
@@ -745,8 +1073,8 @@ impl CommandExecutor {
                             continue;
                         }
 
-                        let w = src.width as f32;
-                        let h = src.height as f32;
+                        let w = src.source_width as f32;
+                        let h = src.source_height as f32;
                         let (x0, y0) = transform.apply(0.0, 0.0);
                         let (x1, y1) = transform.apply(w, 0.0);
                         let (x2, y2) = transform.apply(w, h);
@@ -761,9 +1089,11 @@ impl CommandExecutor {
                         let indices: [u16; 6] = [0, 1, 2, 0, 2, 3];
                         let state = MeshState {
                             texture: Some(*bitmap_key),
-                            blend: BlendMode::Opaque,
+                            blend: BlendMode::textured(draw_blend),
                             color: None,
                             color_transform: ColorTransformKey::from_transform(*color_transform),
+                            smoothing: *smoothing && config::bilinear_enabled(),
+                            repeat: false,
                         };
                         self.draw_mesh(QueuedMesh {
                             kind: MeshKind::Textured,
@@ -781,20 +1111,67 @@ impl CommandExecutor {
                     device.fill_tris_solid_affine(&DEBUG_AFFINE_VERTS, &DEBUG_AFFINE_INDICES, *transform, *r, *g, *b);
                     device.draw_tris_wireframe_affine(&DEBUG_AFFINE_VERTS, &DEBUG_AFFINE_INDICES, *transform, 255, 255, 255);
                 }
-                RenderCmd::DebugLoadingIndicator => {
+                RenderCmd::DebugStatsOverlay { text } => {
                     self.flush_if_pending(device, &bitmaps);
-                    // More intuitive "loading" indicator without text:
-                    // a bordered bar with an animated highlight moving left→right.
+                    // Drawn without scissor so masks from the movie can't hide it.
+                    device.set_scissor(None);
+                    const SCALE: i32 = 1;
+                    const PAD: i32 = 2;
+                    let line_h = (debug_font::GLYPH_H + 2) * SCALE;
+                    let lines = text.lines().count() as i32;
+                    let width = text.lines().map(|l| debug_font::text_width(l, SCALE)).max().unwrap_or(0);
+                    device.fill_rect(RectI { x: 0, y: 0, w: width + PAD * 2, h: lines * line_h + PAD * 2 - 2 * SCALE }, 0, 0, 0);
+                    for (i, line) in text.lines().enumerate() {
+                        debug_font::draw_text(device, PAD, PAD + i as i32 * line_h, line, SCALE, 255, 255, 160);
+                    }
+                    device.set_scissor(device.current_scissor());
+                }
+                RenderCmd::DebugTriHeatmap { shape_key, transform } => {
+                    let color = tri_heat_color(shapes.get_total_tri_count(*shape_key));
+                    for fi in 0..shapes.fill_count(*shape_key) {
+                        let Some(mesh) = shapes.get_fill_mesh(*shape_key, fi) else {
+                            continue;
+                        };
+                        if mesh.verts.is_empty() || mesh.indices.is_empty() || mesh.indices.len() % 3 != 0 {
+                            continue;
+                        }
+                        self.draw_solid_mesh(&mesh.verts, &mesh.indices, *transform, color, TRI_HEAT_ALPHA, false, DrawBlend::Normal, false);
+                    }
+                }
+                RenderCmd::DebugBoundsRect { rect, color_key } => {
+                    self.flush_if_pending(device, &bitmaps);
+                    let (cr, cg, cb) = color_from_key(*color_key);
+                    device.stroke_rect(*rect, cr, cg, cb);
+                }
+                RenderCmd::DebugErrorScreen { text } => {
+                    self.flush_if_pending(device, &bitmaps);
+                    device.set_scissor(None);
+                    const SCALE: i32 = 2;
+                    const PAD: i32 = 8;
+                    let line_h = (debug_font::GLYPH_H + 3) * SCALE;
+                    let max_lines = ((sh - PAD * 2) / line_h).max(1) as usize;
+                    let lines = debug_font::wrap_lines(text, sw - PAD * 2, SCALE);
+                    for (i, line) in lines.iter().take(max_lines).enumerate() {
+                        // First line is the heading.
+                        let (r, g, b) = if i == 0 { (255, 160, 120) } else { (255, 255, 255) };
+                        debug_font::draw_text(device, PAD, PAD + i as i32 * line_h, line, SCALE, r, g, b);
+                    }
+                    device.set_scissor(device.current_scissor());
+                }
+                RenderCmd::DebugLoadingIndicator { percent } => {
+                    self.flush_if_pending(device, &bitmaps);
+                    // "Loading" indicator: a bordered bar filled to the preload percentage when
+                    // it's known, otherwise an animated highlight moving left→right.
                     //
                     // NOTE: We intentionally keep this inside the executor so it stays
                     // device-agnostic and doesn't require a time source from the platform.
                     static TICK: AtomicU32 = AtomicU32::new(0);
                     let t = TICK.fetch_add(1, Ordering::Relaxed);
 
-                    // Bar geometry (centered for 400x240 top screen).
-                    let x0 = 90;
-                    let y0 = 108;
+                    // Bar geometry (horizontally centered; fixed rows for the 240px tall top screen).
                     let w = 220;
+                    let x0 = (sw - w) / 2;
+                    let y0 = 108;
                     let h = 24;
 
                     // Background + border
@@ -808,11 +1185,25 @@ impl CommandExecutor {
                     // Right border
                     device.fill_rect(RectI { x: x0 + w - 2, y: y0, w: 2, h }, 120, 120, 120);
 
-                    // Animated highlight segment inside the bar.
                     let inner_x = x0 + 4;
                     let inner_y = y0 + 4;
                     let inner_w = w - 8;
                     let inner_h = h - 8;
+
+                    // Known preload progress: fill to the percentage and print it under the bar.
+                    if let Some(percent) = *percent {
+                        let fill_w = inner_w * percent.min(100) as i32 / 100;
+                        if fill_w > 0 {
+                            device.fill_rect(RectI { x: inner_x, y: inner_y, w: fill_w, h: inner_h }, 200, 200, 200);
+                        }
+                        const SCALE: i32 = 2;
+                        let label = format!("{}%", percent);
+                        let label_x = (sw - debug_font::text_width(&label, SCALE)) / 2;
+                        debug_font::draw_text(device, label_x, y0 + h + 10, &label, SCALE, 200, 200, 200);
+                        continue;
+                    }
+
+                    // Animated highlight segment inside the bar.
                     let seg_w = 44;
                     let max_x = (inner_w - seg_w).max(1);
                     let seg_x = inner_x + ((t % (max_x as u32 + 1)) as i32);
@@ -820,7 +1211,7 @@ impl CommandExecutor {
 
                     // "Ellipsis" dots under the bar to make it obvious it's a waiting state.
                     let dots_y = y0 + h + 10;
-                    let dots_x = 182;
+                    let dots_x = sw / 2 - 18;
                     let phase = (t / 12) % 4; // 0..3
                     for i in 0..3 {
                         let on = (i as u32) < phase;
@@ -836,6 +1227,14 @@ impl CommandExecutor {
         LAST_MESH_TRIS.store(mesh_tris, Ordering::Relaxed);
         LAST_RECT_FASTPATH.store(rect_fastpath, Ordering::Relaxed);
         LAST_BOUNDS_FALLBACKS.store(bounds_fallbacks, Ordering::Relaxed);
+        LAST_FILL_DRAWS.store(fill_draws, Ordering::Relaxed);
+        LAST_FILL_FALLBACKS.store(fill_fallbacks, Ordering::Relaxed);
+        LAST_TEXT_DRAWS.store(text_draws, Ordering::Relaxed);
+        LAST_TEXT_FALLBACKS.store(text_fallbacks, Ordering::Relaxed);
+        LAST_STROKE_DRAWS.store(stroke_draws, Ordering::Relaxed);
+        LAST_STROKE_FALLBACKS.store(stroke_fallbacks, Ordering::Relaxed);
+        LAST_BITMAP_DRAWS.store(bitmap_draws, Ordering::Relaxed);
+        LAST_RADIAL_FILLS.store(radial_fills, Ordering::Relaxed);
 
         let frame = counter_fetch_add(&FRAME_COUNTER, 1).wrapping_add(1);
         if runlog::is_verbose() && frame % (DRAW_SUMMARY_FRAMES as u64) == 0 {
@@ -874,6 +1273,15 @@ pub struct DrawStats {
     pub mesh_tris: u32,
     pub rect_fastpath: u32,
     pub bounds_fallbacks: u32,
+    pub fill_draws: u32,
+    pub fill_fallbacks: u32,
+    pub text_draws: u32,
+    pub text_fallbacks: u32,
+    pub stroke_draws: u32,
+    pub stroke_fallbacks: u32,
+    pub bitmap_draws: u32,
+    /// Radial gradient fills drawn (per-pixel path, noticeably slower than solid).
+    pub radial_fills: u32,
 }
 
 pub fn last_draw_stats() -> DrawStats {
@@ -881,6 +1289,30 @@ pub fn last_draw_stats() -> DrawStats {
         mesh_tris: LAST_MESH_TRIS.load(Ordering::Relaxed),
         rect_fastpath: LAST_RECT_FASTPATH.load(Ordering::Relaxed),
         bounds_fallbacks: LAST_BOUNDS_FALLBACKS.load(Ordering::Relaxed),
+        fill_draws: LAST_FILL_DRAWS.load(Ordering::Relaxed),
+        fill_fallbacks: LAST_FILL_FALLBACKS.load(Ordering::Relaxed),
+        text_draws: LAST_TEXT_DRAWS.load(Ordering::Relaxed),
+        text_fallbacks: LAST_TEXT_FALLBACKS.load(Ordering::Relaxed),
+        stroke_draws: LAST_STROKE_DRAWS.load(Ordering::Relaxed),
+        stroke_fallbacks: LAST_STROKE_FALLBACKS.load(Ordering::Relaxed),
+        bitmap_draws: LAST_BITMAP_DRAWS.load(Ordering::Relaxed),
+        radial_fills: LAST_RADIAL_FILLS.load(Ordering::Relaxed),
+    }
+}
+
+/// Green for cheap shapes through yellow to red at `TRI_HEAT_MAX` triangles and above.
+fn tri_heat_color(tris: u32) -> [u8; 3] {
+    let t = tris.min(TRI_HEAT_MAX) * 510 / TRI_HEAT_MAX;
+    [t.min(255) as u8, (510 - t).min(255) as u8, 0]
+}
+
+/// Color for shapes drawn without their real paint: one muted `fallback_color`, or a hash of
+/// `key` when `fallback_key_colors` is on.
+pub(crate) fn fallback_color(key: u64) -> (u8, u8, u8) {
+    if config::fallback_key_colors_enabled() {
+        color_from_key(key)
+    } else {
+        config::fallback_color()
     }
 }
 
@@ -895,3 +1327,146 @@ fn color_from_key(mut k: u64) -> (u8, u8, u8) {
     let b = ((k >> 16) & 0xFF) as u8;
     (r, g, b)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::cache::bitmaps::BitmapSurface;
+    use crate::render::cache::shapes::{FillMesh, MAX_GRADIENT_STOPS};
+    use crate::render::device::mem::MemDevice;
+    use crate::render::frame::GradientSpread;
+
+    fn translation(tx: f32, ty: f32) -> Matrix2D {
+        Matrix2D { a: 1.0, b: 0.0, c: 0.0, d: 1.0, tx, ty }
+    }
+
+    #[test]
+    fn executes_fill_rect_and_solid_mesh_on_mem_device() {
+        let caches = SharedCaches::new();
+        // A right triangle: not an axis-aligned rect, so it takes the real mesh path.
+        let mesh = FillMesh {
+            verts: vec![Vertex2 { x: 0, y: 0 }, Vertex2 { x: 20, y: 0 }, Vertex2 { x: 0, y: 20 }],
+            indices: vec![0, 1, 2],
+            paint: FillPaint::SolidRGBA(0, 200, 0, 255),
+        };
+        let bounds = RectI { x: 0, y: 0, w: 20, h: 20 };
+        caches.shapes.lock().unwrap().insert_meshes(1, 1, bounds, vec![mesh], false, false, Vec::new(), false, false, false);
+
+        let mut packet = FramePacket::new();
+        packet.cmds.push(RenderCmd::FillRect { rect: RectI { x: 2, y: 2, w: 10, h: 6 }, color_key: 7, wireframe: false });
+        packet.cmds.push(RenderCmd::DrawShapeSolidFill {
+            shape_key: 1,
+            fill_idx: 0,
+            transform: translation(30.0, 10.0),
+            solid_rgba: Some([0, 200, 0, 255]),
+            color_transform: None,
+            color_key: 0,
+            wireframe: false,
+        });
+
+        let mut device = MemDevice::new(64, 48);
+        CommandExecutor::new().execute(&packet, &mut device, &caches);
+
+        let rect_rgb = fallback_color(7);
+        assert_eq!(device.pixel(2, 2), Some(rect_rgb));
+        assert_eq!(device.pixel(11, 7), Some(rect_rgb));
+        assert_eq!(device.pixel(12, 7), Some((0, 0, 0)));
+        assert_eq!(device.pixel(11, 8), Some((0, 0, 0)));
+
+        assert_eq!(device.pixel(31, 11), Some((0, 200, 0)));
+        assert_eq!(device.pixel(40, 14), Some((0, 200, 0)));
+        assert_eq!(device.pixel(33, 25), Some((0, 200, 0)));
+        // Beyond the hypotenuse and outside the triangle's bounds.
+        assert_eq!(device.pixel(45, 25), Some((0, 0, 0)));
+        assert_eq!(device.pixel(29, 15), Some((0, 0, 0)));
+    }
+
+    #[test]
+    fn repeating_bitmap_fill_tiles_instead_of_clamping() {
+        let caches = SharedCaches::new();
+        // Two texels: red then blue.
+        let mut surface = BitmapSurface::new(2, 1, vec![255, 0, 0, 255, 0, 0, 255, 255]);
+        surface.is_opaque = true;
+        caches.bitmaps.lock().unwrap().insert(9, surface);
+        let quad = |repeating| FillMesh {
+            verts: vec![Vertex2 { x: 0, y: 0 }, Vertex2 { x: 8, y: 0 }, Vertex2 { x: 8, y: 4 }, Vertex2 { x: 0, y: 4 }],
+            indices: vec![0, 1, 2, 0, 2, 3],
+            paint: FillPaint::Bitmap { bitmap_id: 1, bitmap_key: Some(9), matrix: translation(0.0, 0.0), smoothing: false, repeating },
+        };
+        let bounds = RectI { x: 0, y: 0, w: 8, h: 4 };
+        let mut shapes = caches.shapes.lock().unwrap();
+        shapes.insert_meshes(1, 1, bounds, vec![quad(true)], false, false, Vec::new(), false, false, false);
+        shapes.insert_meshes(2, 2, bounds, vec![quad(false)], false, false, Vec::new(), false, false, false);
+        drop(shapes);
+
+        let mut packet = FramePacket::new();
+        for (shape_key, ty) in [(1, 0.0), (2, 10.0)] {
+            packet.cmds.push(RenderCmd::DrawShapeSolidFill {
+                shape_key,
+                fill_idx: 0,
+                transform: translation(0.0, ty),
+                solid_rgba: None,
+                color_transform: None,
+                color_key: 0,
+                wireframe: false,
+            });
+        }
+        let mut device = MemDevice::new(16, 16);
+        CommandExecutor::new().execute(&packet, &mut device, &caches);
+
+        let (red, blue) = (Some((255, 0, 0)), Some((0, 0, 255)));
+        for x in 0..8 {
+            let tiled = if x % 2 == 0 { red } else { blue };
+            assert_eq!(device.pixel(x, 2), tiled, "repeating x={}", x);
+            let clamped = if x == 0 { red } else { blue };
+            assert_eq!(device.pixel(x, 12), clamped, "clamped x={}", x);
+        }
+    }
+
+    #[test]
+    fn gradient_fades_to_transparent_stop() {
+        // Opaque red fading to fully transparent red across a 64px quad.
+        let mut stops = [(0u8, [0u8; 4]); MAX_GRADIENT_STOPS];
+        stops[0] = (0, [255, 0, 0, 255]);
+        stops[1] = (255, [255, 0, 0, 0]);
+        let paint = GradientPaint {
+            stops,
+            stop_count: 2,
+            spread: GradientSpread::Pad,
+            local_to_gradient: Matrix2D { a: 2.0 / 64.0, b: 0.0, c: 0.0, d: 1.0, tx: -1.0, ty: 0.0 },
+        };
+        let lut = gradient_lut(&paint, None);
+        assert_eq!(lut[0], [255, 0, 0, 255]);
+        assert_eq!(lut[GRADIENT_LUT_SIZE - 1], [255, 0, 0, 0]);
+
+        let caches = SharedCaches::new();
+        let mesh = FillMesh {
+            verts: vec![Vertex2 { x: 0, y: 0 }, Vertex2 { x: 64, y: 0 }, Vertex2 { x: 64, y: 4 }, Vertex2 { x: 0, y: 4 }],
+            indices: vec![0, 1, 2, 0, 2, 3],
+            paint: FillPaint::LinearGradient(paint),
+        };
+        let bounds = RectI { x: 0, y: 0, w: 64, h: 4 };
+        caches.shapes.lock().unwrap().insert_meshes(1, 1, bounds, vec![mesh], false, false, Vec::new(), false, false, false);
+        let mut packet = FramePacket::new();
+        packet.cmds.push(RenderCmd::DrawShapeSolidFill {
+            shape_key: 1,
+            fill_idx: 0,
+            transform: translation(0.0, 0.0),
+            solid_rgba: None,
+            color_transform: None,
+            color_key: 0,
+            wireframe: false,
+        });
+
+        let mut device = MemDevice::new(64, 4);
+        device.fill_rect(RectI { x: 0, y: 0, w: 64, h: 4 }, 0, 0, 255);
+        CommandExecutor::new().execute(&packet, &mut device, &caches);
+
+        let (r, _, b) = device.pixel(0, 2).unwrap();
+        assert!(r >= 250 && b <= 5, "opaque end ({}, {})", r, b);
+        let (r, _, b) = device.pixel(32, 2).unwrap();
+        assert!((100..=155).contains(&r) && (100..=155).contains(&b), "midpoint ({}, {})", r, b);
+        let (r, _, b) = device.pixel(63, 2).unwrap();
+        assert!(r <= 8 && b >= 247, "transparent end ({}, {})", r, b);
+    }
+}
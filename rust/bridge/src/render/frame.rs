@@ -1,6 +1,6 @@
 use ruffle_core::Color;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ClearColor {
     pub r: u8,
     pub g: u8,
@@ -13,7 +13,7 @@ impl ClearColor {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct RectI {
     pub x: i32,
     pub y: i32,
@@ -21,6 +21,33 @@ pub struct RectI {
     pub h: i32,
 }
 
+impl RectI {
+    pub fn is_empty(&self) -> bool {
+        self.w <= 0 || self.h <= 0
+    }
+
+    /// Smallest rect covering both (empty rects are ignored).
+    pub fn union(self, other: RectI) -> RectI {
+        if self.is_empty() { return other; }
+        if other.is_empty() { return self; }
+        let x0 = self.x.min(other.x);
+        let y0 = self.y.min(other.y);
+        let x1 = (self.x + self.w).max(other.x + other.w);
+        let y1 = (self.y + self.h).max(other.y + other.h);
+        RectI { x: x0, y: y0, w: x1 - x0, h: y1 - y0 }
+    }
+
+    /// Overlap of both rects, or `None` when they don't touch.
+    pub fn intersect(self, other: RectI) -> Option<RectI> {
+        let x0 = self.x.max(other.x);
+        let y0 = self.y.max(other.y);
+        let x1 = (self.x + self.w).min(other.x + other.w);
+        let y1 = (self.y + self.h).min(other.y + other.h);
+        if x1 <= x0 || y1 <= y0 { return None; }
+        Some(RectI { x: x0, y: y0, w: x1 - x0, h: y1 - y0 })
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Matrix2D {
     pub a: f32,
@@ -49,12 +76,117 @@ impl Matrix2D {
         approx_eq_f32(self.b, 0.0) && approx_eq_f32(self.c, 0.0)
     }
 
+    /// Pure (possibly non-uniform or mirrored) scale plus translation with no degenerate axis,
+    /// so axis-aligned rects stay rects of non-zero size.
+    pub fn is_scale_translation(&self) -> bool {
+        self.is_axis_aligned() && !approx_eq_f32(self.a, 0.0) && !approx_eq_f32(self.d, 0.0)
+    }
+
     pub fn is_translation(&self) -> bool {
         approx_eq_f32(self.a, 1.0)
             && approx_eq_f32(self.d, 1.0)
             && approx_eq_f32(self.b, 0.0)
             && approx_eq_f32(self.c, 0.0)
     }
+
+    /// `self ∘ other`: the matrix that applies `other` first, then `self`.
+    pub fn multiply(&self, other: &Matrix2D) -> Matrix2D {
+        Matrix2D {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            tx: self.a * other.tx + self.c * other.ty + self.tx,
+            ty: self.b * other.tx + self.d * other.ty + self.ty,
+        }
+    }
+
+    /// Inverse transform, or `None` when the matrix is (nearly) singular.
+    pub fn invert(&self) -> Option<Matrix2D> {
+        let det = self.a * self.d - self.b * self.c;
+        if !det.is_finite() || det.abs() < 1.0e-12 {
+            return None;
+        }
+        let inv = 1.0 / det;
+        let a = self.d * inv;
+        let b = -self.b * inv;
+        let c = -self.c * inv;
+        let d = self.a * inv;
+        Some(Matrix2D {
+            a,
+            b,
+            c,
+            d,
+            tx: -(a * self.tx + c * self.ty),
+            ty: -(b * self.tx + d * self.ty),
+        })
+    }
+}
+
+/// Entries in `GradientFill::lut`.
+pub const GRADIENT_LUT_SIZE: usize = 256;
+
+/// Focal points are clamped to this magnitude; at ±1 the ramp denominator reaches zero.
+pub const MAX_FOCAL_POINT: f32 = 0.98;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GradientKind {
+    /// Ramp runs along gradient-space x from -1 to 1.
+    Linear,
+    /// Ramp runs from the focal point (`(focal_point, 0)`, origin when 0) out to radius 1.
+    Radial,
+}
+
+/// How the ramp continues outside `[0, 1]` (SWF `GradientSpread`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GradientSpread {
+    Pad,
+    Reflect,
+    Repeat,
+}
+
+/// Screen-space gradient ready for per-pixel rasterization.
+#[derive(Clone, Debug)]
+pub struct GradientFill {
+    pub kind: GradientKind,
+    pub spread: GradientSpread,
+    /// Maps screen pixel centers into gradient space (the SWF gradient square is `[-1, 1]²`).
+    pub screen_to_gradient: Matrix2D,
+    /// Radial focal point along gradient-space x, in `[-MAX_FOCAL_POINT, MAX_FOCAL_POINT]`.
+    pub focal_point: f32,
+    /// Straight-alpha ramp colors for `t` in `[0, 1]`, color transform already applied.
+    pub lut: [[u8; 4]; GRADIENT_LUT_SIZE],
+}
+
+impl GradientFill {
+    /// LUT index for gradient-space point `(u, v)`, with the spread mode applied.
+    #[inline(always)]
+    pub fn ramp_index(&self, u: f32, v: f32) -> usize {
+        let t = match self.kind {
+            GradientKind::Linear => (u + 1.0) * 0.5,
+            GradientKind::Radial if self.focal_point == 0.0 => (u * u + v * v).sqrt(),
+            GradientKind::Radial => {
+                // Distance from the focal point, relative to where the same ray leaves the unit circle.
+                let f = self.focal_point;
+                let du = u - f;
+                let len2 = du * du + v * v;
+                if len2 == 0.0 {
+                    0.0
+                } else {
+                    len2 / (-f * du + (f * f * du * du + len2 * (1.0 - f * f)).sqrt())
+                }
+            }
+        };
+        let t = match self.spread {
+            GradientSpread::Pad => t.clamp(0.0, 1.0),
+            GradientSpread::Repeat => t - t.floor(),
+            GradientSpread::Reflect => {
+                let m = t.rem_euclid(2.0);
+                if m > 1.0 { 2.0 - m } else { m }
+            }
+        };
+        ((t * (GRADIENT_LUT_SIZE - 1) as f32 + 0.5) as usize).min(GRADIENT_LUT_SIZE - 1)
+    }
 }
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -78,6 +210,18 @@ impl TexUvRect {
     }
 }
 
+/// How a draw combines with the framebuffer (the subset of SWF blend modes we rasterize).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DrawBlend {
+    /// Regular alpha-over compositing.
+    #[default]
+    Normal,
+    /// `dst + src*a`, saturating at white (glows, light effects).
+    Add,
+    /// `dst * src / 255` per channel, with alpha fading the source toward white (shadows, tints).
+    Multiply,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct ColorTransform {
     pub mul: [f32; 4],
@@ -92,6 +236,25 @@ pub struct TexVertex {
     pub v: f32,
 }
 
+/// Fractional bits used by `FixedVertex`.
+pub const FIXED_VERTEX_SHIFT: u32 = 16;
+
+/// Screen-space vertex in 16.16 fixed point.
+///
+/// Used by the sub-pixel solid path so slow motion doesn't snap to whole pixels.
+#[derive(Clone, Copy, Debug)]
+pub struct FixedVertex {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl FixedVertex {
+    pub fn from_f32(x: f32, y: f32) -> Self {
+        let scale = (1i32 << FIXED_VERTEX_SHIFT) as f32;
+        Self { x: (x * scale).round() as i32, y: (y * scale).round() as i32 }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct FramePacket {
     pub clear: ClearColor,
@@ -110,6 +273,44 @@ impl FramePacket {
         self.clear = ClearColor::from_ruffle(clear);
         self.cmds.clear();
     }
+
+    /// Stretch every screen-space position horizontally by `sx` (wide-mode surfaces).
+    ///
+    /// Overlay text keeps its pixel size; only geometry placed in movie space is scaled.
+    pub fn scale_x(&mut self, sx: i32) {
+        if sx == 1 {
+            return;
+        }
+        let fx = sx as f32;
+        for cmd in &mut self.cmds {
+            cmd.map_placement(
+                |rect| {
+                    rect.x *= sx;
+                    rect.w *= sx;
+                },
+                |m| {
+                    m.a *= fx;
+                    m.c *= fx;
+                    m.tx *= fx;
+                },
+            );
+        }
+    }
+
+    /// Shift draws horizontally for one stereo eye.
+    ///
+    /// Later draws sit on top, so they count as nearer: the shift grows with draw order up to
+    /// `max_dx` for the topmost draw. Overlays stay at screen depth.
+    pub fn offset_layers_x(&mut self, max_dx: f32) {
+        if max_dx == 0.0 {
+            return;
+        }
+        let n = self.cmds.len().max(1) as f32;
+        for (i, cmd) in self.cmds.iter_mut().enumerate() {
+            let dx = max_dx * (i + 1) as f32 / n;
+            cmd.map_placement(|rect| rect.x += dx.round() as i32, |m| m.tx += dx);
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -168,16 +369,29 @@ pub enum RenderCmd {
         rect: RectI,
     },
 
-    /// Push a shape mask (not yet supported; will warn + no-op).
+    /// Push a shape mask.
+    ///
+    /// The clip is the shape's transformed bounds intersected with the current mask; masks under
+    /// `mask_max_tris`/`mask_max_area` are also rasterized per pixel when the device supports it.
+    /// Shapes without cached bounds warn and leave the clip unchanged.
     PushMaskShape {
         shape_key: usize,
-        tx: i32,
-        ty: i32,
+        transform: Matrix2D,
     },
 
     /// Pop the most recent mask.
     PopMask,
 
+    /// Draw everything up to the matching `PopBlend` with `blend`.
+    ///
+    /// `Normal` inside another mode keeps the enclosing mode (groups aren't composited separately).
+    PushBlend {
+        blend: DrawBlend,
+    },
+
+    /// Pop the most recent blend mode.
+    PopBlend,
+
     /// Draw a cached RGBA bitmap at `(x, y)` without scaling (nearest).
     ///
     /// Step 3 will extend this to support transforms (scale/rotation) and
@@ -187,10 +401,37 @@ pub enum RenderCmd {
         transform: Matrix2D,
         uv: TexUvRect,
         color_transform: Option<ColorTransform>,
+        /// Movie asked for smoothing; transformed draws sample bilinearly unless `bilinear=0`.
+        smoothing: bool,
+    },
+
+    /// Developer overlay: small multi-line stats text in the top-left corner.
+    DebugStatsOverlay {
+        text: String,
+    },
+
+    /// Visual cue until we see real draw commands: a filled bar with `percent` when preload
+    /// progress is known, otherwise an animated one.
+    DebugLoadingIndicator {
+        percent: Option<u8>,
+    },
+
+    /// Engine error mode: the fatal message, word-wrapped across the whole surface.
+    DebugErrorScreen {
+        text: String,
+    },
+
+    /// Developer overlay: outline a drawn shape's transformed bounds in its per-shape color.
+    DebugBoundsRect {
+        rect: RectI,
+        color_key: u64,
     },
 
-    /// Visual cue until we see real draw commands.
-    DebugLoadingIndicator,
+    /// Developer overlay: tint a shape's fill meshes by its total triangle count.
+    DebugTriHeatmap {
+        shape_key: usize,
+        transform: Matrix2D,
+    },
 
     /// Developer overlay: draw a known affine-transformed rectangle mesh.
     DebugAffineRect {
@@ -202,6 +443,77 @@ pub enum RenderCmd {
 
 }
 
+impl RenderCmd {
+    /// Apply `rect` or `matrix` to whatever places this command on screen.
+    fn map_placement(&mut self, rect: impl FnOnce(&mut RectI), matrix: impl FnOnce(&mut Matrix2D)) {
+        match self {
+            RenderCmd::FillRect { rect: r, .. }
+            | RenderCmd::PushMaskRect { rect: r }
+            | RenderCmd::DebugBoundsRect { rect: r, .. } => rect(r),
+            RenderCmd::DrawShapeSolidFill { transform, .. }
+            | RenderCmd::DrawTextSolidFill { transform, .. }
+            | RenderCmd::DrawShapeStroke { transform, .. }
+            | RenderCmd::PushMaskShape { transform, .. }
+            | RenderCmd::BlitBitmap { transform, .. }
+            | RenderCmd::DebugTriHeatmap { transform, .. }
+            | RenderCmd::DebugAffineRect { transform, .. } => matrix(transform),
+            RenderCmd::PopMask
+            | RenderCmd::PushBlend { .. }
+            | RenderCmd::PopBlend
+            | RenderCmd::DebugStatsOverlay { .. }
+            | RenderCmd::DebugLoadingIndicator { .. }
+            | RenderCmd::DebugErrorScreen { .. } => {}
+        }
+    }
+}
+
 fn approx_eq_f32(a: f32, b: f32) -> bool {
     (a - b).abs() <= 0.0001
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_point(got: (f32, f32), want: (f32, f32)) {
+        assert!(
+            (got.0 - want.0).abs() < 1.0e-3 && (got.1 - want.1).abs() < 1.0e-3,
+            "got {:?}, want {:?}",
+            got,
+            want
+        );
+    }
+
+    #[test]
+    fn invert_round_trips_points() {
+        let m = Matrix2D { a: 1.5, b: 0.5, c: -0.25, d: 0.75, tx: 40.0, ty: -12.0 };
+        let inv = m.invert().expect("matrix is invertible");
+        for &(x, y) in &[(0.0, 0.0), (10.0, -3.5), (-120.25, 64.0), (399.0, 239.0)] {
+            let (sx, sy) = m.apply(x, y);
+            assert_point(inv.apply(sx, sy), (x, y));
+        }
+        let id = m.multiply(&inv);
+        assert!(id.is_identity(), "m * m^-1 = {:?}", id);
+    }
+
+    #[test]
+    fn invert_rejects_singular() {
+        let flat = Matrix2D { a: 2.0, b: 1.0, c: 4.0, d: 2.0, tx: 5.0, ty: 5.0 };
+        assert!(flat.invert().is_none());
+    }
+
+    #[test]
+    fn multiply_applies_right_operand_first() {
+        // Scale by 2, then translate by (10, 20).
+        let scale = Matrix2D { a: 2.0, b: 0.0, c: 0.0, d: 2.0, tx: 0.0, ty: 0.0 };
+        let translate = Matrix2D { a: 1.0, b: 0.0, c: 0.0, d: 1.0, tx: 10.0, ty: 20.0 };
+        let m = translate.multiply(&scale);
+        assert_point(m.apply(3.0, 4.0), (16.0, 28.0));
+        // The other order translates first: (3 + 10, 4 + 20) * 2.
+        assert_point(scale.multiply(&translate).apply(3.0, 4.0), (26.0, 48.0));
+
+        // A quarter turn (x -> y, y -> -x) after a translation.
+        let rotate = Matrix2D { a: 0.0, b: 1.0, c: -1.0, d: 0.0, tx: 0.0, ty: 0.0 };
+        assert_point(rotate.multiply(&translate).apply(1.0, 0.0), (-20.0, 11.0));
+    }
+}
@@ -1,10 +1,11 @@
 pub mod cache;
+pub(crate) mod debug_font;
 pub mod device;
 pub(crate) mod executor;
 mod frame;
 mod shared;
 
-pub use frame::{ColorTransform, FramePacket, Matrix2D, RenderCmd, RectI, TexUvRect};
+pub use frame::{ClearColor, ColorTransform, DrawBlend, FramePacket, GradientSpread, Matrix2D, RenderCmd, RectI, TexUvRect};
 pub use shared::SharedCaches;
 
 #[cfg(feature = "legacy_sw_render")]
@@ -13,6 +14,23 @@ use crate::render::device::RenderDevice;
 use crate::render::executor::CommandExecutor;
 #[cfg(feature = "legacy_sw_render")]
 use crate::render::device::fb3ds::Fb3dsDevice;
+#[cfg(feature = "legacy_sw_render")]
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::runlog;
+use crate::util::config;
+
+/// Upper bound for the swap-chain length hint (libctru uses 1 or 2; leave room for triple buffering).
+const MAX_FRAME_BUFFERS: usize = 4;
+
+/// Frames skipped because the device had no surface (null framebuffer).
+static FRAMES_NO_SURFACE: AtomicU32 = AtomicU32::new(0);
+
+/// Total frames skipped for lack of a surface, for status snapshots.
+pub fn frames_no_surface() -> u32 {
+    FRAMES_NO_SURFACE.load(Ordering::Relaxed)
+}
 
 /// High-level renderer facade used by the engine.
 ///
@@ -24,6 +42,72 @@ pub struct Renderer {
     exec: CommandExecutor,
     #[cfg(feature = "legacy_sw_render")]
     caches: SharedCaches,
+    /// Number of framebuffers the platform cycles through; 0 disables dirty-rect clears.
+    buffer_count: usize,
+    #[cfg(feature = "legacy_sw_render")]
+    dirty_history: DirtyHistory,
+    /// Launcher fade applied to the whole finished frame (255 = opaque).
+    global_alpha: u8,
+    /// Packet adjusted for the surface (wide stretch, stereo offsets); reused each frame.
+    #[cfg(feature = "legacy_sw_render")]
+    view_packet: FramePacket,
+    /// Stereo depth from the engine in `[0, 1]` (the 3D slider), scaled by `stereo_depth` config.
+    stereo_depth: f32,
+}
+
+/// What `DirtyHistory::stale` says the back buffer needs before drawing.
+#[cfg(feature = "legacy_sw_render")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StaleClear {
+    Full,
+    Rect(RectI),
+    None,
+}
+
+/// Dirty bounds of the last `buffer_count` frames, oldest first, plus the clear color and
+/// surface size they were recorded with (wide mode changes the size).
+#[cfg(feature = "legacy_sw_render")]
+struct DirtyHistory {
+    frames: VecDeque<Option<RectI>>,
+    last_clear: Option<ClearColor>,
+    last_surface: (i32, i32),
+}
+
+#[cfg(feature = "legacy_sw_render")]
+impl DirtyHistory {
+    fn new() -> Self {
+        Self { frames: VecDeque::with_capacity(MAX_FRAME_BUFFERS), last_clear: None, last_surface: (0, 0) }
+    }
+
+    fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// The region a buffer last drawn `buffer_count` frames ago may still show stale pixels in.
+    fn stale(&mut self, clear: ClearColor, surface: (i32, i32), buffer_count: usize) -> StaleClear {
+        if self.last_clear != Some(clear) || self.last_surface != surface {
+            self.frames.clear();
+            self.last_clear = Some(clear);
+            self.last_surface = surface;
+        }
+        if buffer_count == 0 || self.frames.len() < buffer_count {
+            return StaleClear::Full;
+        }
+        let stale = self
+            .frames
+            .iter()
+            .flatten()
+            .fold(None, |acc: Option<RectI>, r| Some(acc.map_or(*r, |a| a.union(*r))));
+        stale.map_or(StaleClear::None, StaleClear::Rect)
+    }
+
+    /// Record the bounds drawn this frame, keeping the last `buffer_count`.
+    fn push(&mut self, dirty: Option<RectI>, buffer_count: usize) {
+        self.frames.push_back(dirty);
+        while self.frames.len() > buffer_count.max(1) {
+            self.frames.pop_front();
+        }
+    }
 }
 
 impl Renderer {
@@ -39,20 +123,203 @@ impl Renderer {
             exec: CommandExecutor::new(),
             #[cfg(feature = "legacy_sw_render")]
             caches,
+            buffer_count: (config::frame_buffers() as usize).min(MAX_FRAME_BUFFERS),
+            #[cfg(feature = "legacy_sw_render")]
+            dirty_history: DirtyHistory::new(),
+            global_alpha: 255,
+            #[cfg(feature = "legacy_sw_render")]
+            view_packet: FramePacket::new(),
+            stereo_depth: 0.0,
+        }
+    }
+
+    /// Set the launcher fade in `[0.0, 1.0]`; returns the applied value.
+    ///
+    /// Applied after all draws, so content that already blends with alpha composes normally
+    /// and the result is simply faded toward black.
+    pub fn set_global_alpha(&mut self, alpha: f32) -> f32 {
+        let alpha = if alpha.is_finite() { alpha.clamp(0.0, 1.0) } else { 1.0 };
+        self.global_alpha = (alpha * 255.0).round() as u8;
+        self.global_alpha as f32 / 255.0
+    }
+
+    /// Set the stereo depth in `[0.0, 1.0]` (usually the 3D slider); returns the applied value.
+    ///
+    /// Eye separation is this times the `stereo_depth` config key, in pixels.
+    pub fn set_stereo_depth(&mut self, depth: f32) -> f32 {
+        self.stereo_depth = if depth.is_finite() { depth.clamp(0.0, 1.0) } else { 0.0 };
+        self.stereo_depth
+    }
+
+    /// Set how many framebuffers the platform rotates through; returns the clamped count.
+    ///
+    /// The buffer being drawn was last drawn `count` frames ago, so its stale pixels are covered
+    /// by the union of the last `count` frames' dirty bounds. 0 means unknown: always clear fully.
+    pub fn set_buffer_count(&mut self, count: usize) -> usize {
+        let count = count.min(MAX_FRAME_BUFFERS);
+        if count != self.buffer_count {
+            runlog::log_important(&format!("frame_buffers={}", count));
+        }
+        self.buffer_count = count;
+        #[cfg(feature = "legacy_sw_render")]
+        self.dirty_history.clear();
+        count
+    }
+
+    /// Clear whatever the current back buffer may still hold from earlier frames.
+    ///
+    /// Falls back to a full clear until `buffer_count` frames of history exist (startup, after a
+    /// count change) and whenever the clear color or surface size changes.
+    #[cfg(feature = "legacy_sw_render")]
+    fn clear_stale(&mut self, clear: ClearColor) {
+        let surface = (self.device.surface_width(), self.device.surface_height());
+        match self.dirty_history.stale(clear, surface, self.buffer_count) {
+            StaleClear::Full => self.device.clear(clear),
+            StaleClear::Rect(rect) => self.device.clear_rect(rect, clear),
+            StaleClear::None => {}
+        }
+    }
+
+    /// Drop a frame the device couldn't start. The buffer history no longer matches what is on
+    /// screen, so the next frame clears fully.
+    #[cfg(feature = "legacy_sw_render")]
+    fn skip_frame_no_surface(&mut self) {
+        FRAMES_NO_SURFACE.fetch_add(1, Ordering::Relaxed);
+        self.dirty_history.clear();
+        self.device.end_frame();
+    }
+
+    /// Draw both eyes, each draw shifted by its layer's share of half the eye separation.
+    ///
+    /// The eye buffers don't share dirty history, so stereo frames always clear fully.
+    #[cfg(feature = "legacy_sw_render")]
+    fn render_stereo(&mut self, packet: &FramePacket, caches: Option<&SharedCaches>) {
+        let half_separation = 0.5 * config::stereo_depth() * self.stereo_depth;
+        self.dirty_history.clear();
+        // Crossed disparity: nearer layers move right in the left eye and left in the right eye.
+        for (right_eye, dx) in [(false, half_separation), (true, -half_separation)] {
+            if !self.device.begin_eye_frame(right_eye) {
+                self.skip_frame_no_surface();
+                return;
+            }
+            self.device.clear(packet.clear);
+            self.view_packet.clone_from(packet);
+            self.view_packet.scale_x(self.device.surface_x_scale());
+            self.view_packet.offset_layers_x(dx);
+            self.exec.execute(&self.view_packet, &mut self.device, caches.unwrap_or(&self.caches));
+            self.device.fade_surface(self.global_alpha);
+            let _ = self.device.take_dirty_rect();
+            self.device.end_frame();
         }
     }
 
     pub fn render(&mut self, packet: &FramePacket) {
+        self.render_from(packet, None);
+    }
+
+    /// Like `render`, but shape and bitmap keys resolve in `caches` (replayed command traces).
+    pub fn render_with_caches(&mut self, packet: &FramePacket, caches: &SharedCaches) {
+        self.render_from(packet, Some(caches));
+    }
+
+    fn render_from(&mut self, packet: &FramePacket, caches: Option<&SharedCaches>) {
         #[cfg(feature = "legacy_sw_render")]
         {
-            self.device.begin_frame();
-            self.device.clear(packet.clear);
-            self.exec.execute(packet, &mut self.device, &self.caches);
+            if config::stereo_depth() > 0.0 {
+                self.render_stereo(packet, caches);
+                return;
+            }
+            if !self.device.begin_frame() {
+                self.skip_frame_no_surface();
+                return;
+            }
+            self.clear_stale(packet.clear);
+            // Packets are built in square-pixel viewport space; wide surfaces get a stretched copy.
+            let x_scale = self.device.surface_x_scale();
+            let caches = caches.unwrap_or(&self.caches);
+            if x_scale != 1 {
+                self.view_packet.clone_from(packet);
+                self.view_packet.scale_x(x_scale);
+                self.exec.execute(&self.view_packet, &mut self.device, caches);
+            } else {
+                self.exec.execute(packet, &mut self.device, caches);
+            }
+            self.device.fade_surface(self.global_alpha);
+            let dirty = self.device.take_dirty_rect();
+            self.dirty_history.push(dirty, self.buffer_count);
             self.device.end_frame();
         }
         #[cfg(not(feature = "legacy_sw_render"))]
         {
-            let _ = packet;
+            let _ = (packet, caches);
+        }
+    }
+
+    /// Copy the top-screen framebuffer (left eye) into `out` as row-major RGBA8.
+    ///
+    /// Returns `(width, height)`, or `None` when no framebuffer is available.
+    pub fn read_pixels(&self, out: &mut Vec<u8>) -> Option<(u32, u32)> {
+        #[cfg(feature = "legacy_sw_render")]
+        {
+            self.device.read_pixels(out)
+        }
+        #[cfg(not(feature = "legacy_sw_render"))]
+        {
+            out.clear();
+            None
+        }
+    }
+}
+
+#[cfg(all(test, feature = "legacy_sw_render"))]
+mod tests {
+    use super::*;
+    use crate::render::device::mem::MemDevice;
+
+    /// A rect sliding right across three rotating buffers, clearing each through the history.
+    #[test]
+    fn dirty_history_leaves_no_trails_with_three_buffers() {
+        const BUFFERS: usize = 3;
+        let clear = ClearColor { r: 0, g: 0, b: 0 };
+        let mut buffers: Vec<MemDevice> = (0..BUFFERS).map(|_| MemDevice::new(64, 16)).collect();
+        let mut history = DirtyHistory::new();
+        let mut full_clears = 0;
+        for frame in 0..12 {
+            let rect = RectI { x: 4 * frame, y: 4, w: 8, h: 6 };
+            let dev = &mut buffers[frame as usize % BUFFERS];
+            match history.stale(clear, (dev.surface_width(), dev.surface_height()), BUFFERS) {
+                StaleClear::Full => {
+                    full_clears += 1;
+                    dev.clear(clear);
+                }
+                StaleClear::Rect(stale) => dev.clear_rect(stale, clear),
+                StaleClear::None => {}
+            }
+            dev.fill_rect(rect, 255, 255, 255);
+            history.push(Some(rect), BUFFERS);
+
+            for y in 0..dev.surface_height() {
+                for x in 0..dev.surface_width() {
+                    let inside = x >= rect.x && x < rect.x + rect.w && y >= rect.y && y < rect.y + rect.h;
+                    let want = if inside { (255, 255, 255) } else { (0, 0, 0) };
+                    assert_eq!(dev.pixel(x, y), Some(want), "frame {} pixel ({}, {})", frame, x, y);
+                }
+            }
+        }
+        // Only the frames before the history filled up needed a full clear.
+        assert_eq!(full_clears, BUFFERS);
+    }
+
+    #[test]
+    fn dirty_history_resets_on_clear_color_change() {
+        let mut history = DirtyHistory::new();
+        let black = ClearColor { r: 0, g: 0, b: 0 };
+        let rect = RectI { x: 0, y: 0, w: 4, h: 4 };
+        for _ in 0..2 {
+            history.stale(black, (64, 16), 2);
+            history.push(Some(rect), 2);
         }
+        assert_eq!(history.stale(black, (64, 16), 2), StaleClear::Rect(rect));
+        assert_eq!(history.stale(ClearColor { r: 9, g: 9, b: 9 }, (64, 16), 2), StaleClear::Full);
     }
 }
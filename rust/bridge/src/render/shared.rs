@@ -2,6 +2,7 @@ use std::sync::{Arc, Mutex};
 
 use crate::render::cache::bitmaps::BitmapCache;
 use crate::render::cache::shapes::ShapeCache;
+use crate::util::config;
 
 /// Shared CPU-side render resources.
 ///
@@ -17,10 +18,13 @@ pub struct SharedCaches {
 }
 
 impl SharedCaches {
+    /// Build empty caches; the shape budget comes from `shape_cache_kb` (logged to the boottrace).
     pub fn new() -> Self {
+        let mut shapes = ShapeCache::new();
+        shapes.set_budget(config::shape_cache_kb() as usize * 1024);
         Self {
             bitmaps: Arc::new(Mutex::new(BitmapCache::new())),
-            shapes: Arc::new(Mutex::new(ShapeCache::new())),
+            shapes: Arc::new(Mutex::new(shapes)),
         }
     }
 }
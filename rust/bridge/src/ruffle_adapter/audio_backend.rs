@@ -0,0 +1,106 @@
+//! Audio backend that mixes Ruffle sounds and feeds them to the DSP.
+//!
+//! Ruffle's `AudioMixer` does the decoding and mixing; a dedicated thread pulls mixed
+//! stereo i16 frames through a mixer proxy and queues them into the ndsp wave buffers
+//! (`source/audio_ndsp.c`). `tick_and_render` never waits on audio: sound registration and
+//! `start_sound` only touch the mixer's shared state, and the thread is the only caller
+//! into ndsp after `open`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use ruffle_core::backend::audio::{AudioBackend, AudioMixer};
+use ruffle_core::impl_audio_mixer_backend;
+
+use crate::ffi::audio as dsp;
+use crate::runlog;
+
+/// Output rate; matches the most common SWF sound rate, so event sounds rarely resample.
+const SAMPLE_RATE: u32 = 22050;
+/// Frames per wave buffer (~23 ms at 22050 Hz). The C side keeps three in flight.
+const FRAMES_PER_BUFFER: u32 = 512;
+/// How long the mixer thread sleeps when every wave buffer is still queued.
+const MIXER_IDLE_SLEEP: Duration = Duration::from_millis(4);
+const MIXER_STACK_BYTES: usize = 64 * 1024;
+
+pub struct DspAudioBackend {
+    mixer: AudioMixer,
+    paused: Arc<AtomicBool>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl DspAudioBackend {
+    /// Open the DSP and start the mixer thread.
+    ///
+    /// Returns `None` when ndsp is unavailable or the thread can't be created; callers fall
+    /// back to `NullAudioBackend`.
+    pub fn new() -> Option<Self> {
+        if !dsp::open(SAMPLE_RATE, FRAMES_PER_BUFFER) {
+            runlog::warn_line("audio: ndsp init failed (missing dspfirm.cdc?)");
+            return None;
+        }
+
+        let mixer = AudioMixer::new(2, SAMPLE_RATE);
+        let proxy = mixer.make_proxy();
+        let paused = Arc::new(AtomicBool::new(false));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread_paused = paused.clone();
+        let thread_running = running.clone();
+        let spawned = thread::Builder::new()
+            .name("audio_mixer".to_string())
+            .stack_size(MIXER_STACK_BYTES)
+            .spawn(move || {
+                let mut buf = vec![0i16; FRAMES_PER_BUFFER as usize * 2];
+                while thread_running.load(Ordering::Acquire) {
+                    if thread_paused.load(Ordering::Acquire) || dsp::free_buffers() == 0 {
+                        thread::sleep(MIXER_IDLE_SLEEP);
+                        continue;
+                    }
+                    buf.fill(0);
+                    proxy.mix::<i16>(&mut buf);
+                    dsp::queue(&buf);
+                }
+            });
+
+        match spawned {
+            Ok(handle) => Some(Self { mixer, paused, running, thread: Some(handle) }),
+            Err(_) => {
+                runlog::warn_line("audio: mixer thread spawn failed");
+                dsp::close();
+                None
+            }
+        }
+    }
+}
+
+impl AudioBackend for DspAudioBackend {
+    impl_audio_mixer_backend!(mixer);
+
+    fn play(&mut self) {
+        self.paused.store(false, Ordering::Release);
+        dsp::set_paused(false);
+    }
+
+    fn pause(&mut self) {
+        self.paused.store(true, Ordering::Release);
+        dsp::set_paused(true);
+    }
+
+    fn position_resolution(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f64(FRAMES_PER_BUFFER as f64 / SAMPLE_RATE as f64))
+    }
+}
+
+impl Drop for DspAudioBackend {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(handle) = self.thread.take() {
+            let _ = handle.join();
+        }
+        dsp::close();
+    }
+}
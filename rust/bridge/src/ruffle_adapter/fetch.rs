@@ -0,0 +1,184 @@
+//! `NavigatorBackend::fetch` plumbing.
+//!
+//! Remote loads run a blocking httpc GET on a short-lived thread; the future Ruffle awaits
+//! just waits on a channel, so `poll_tasks` never stalls a frame on the network. Local
+//! (`file://`, `sdmc:/` and relative) loads read straight from the SD card.
+
+use std::borrow::Cow;
+use std::thread;
+
+use ruffle_core::backend::navigator::{ErrorResponse, OwnedFuture, SuccessResponse};
+use ruffle_core::loader::Error as LoaderError;
+
+use url::Url;
+
+use crate::ffi::fileio::read_file_bytes;
+use crate::ffi::http::{http_get, HTTP_ERR_TOO_LARGE};
+use crate::runlog;
+
+/// Largest body we accept; bigger responses fail instead of exhausting the heap.
+const FETCH_MAX_BYTES: usize = 4 * 1024 * 1024;
+const FETCH_STACK_BYTES: usize = 64 * 1024;
+
+/// A fully buffered response.
+pub struct FetchedResponse {
+    /// Where the body came from: the requested URL, or the redirect target.
+    url: String,
+    body: Vec<u8>,
+    status: u16,
+    redirected: bool,
+    chunk_taken: bool,
+}
+
+impl FetchedResponse {
+    pub fn new(url: String, body: Vec<u8>, status: u16) -> Self {
+        Self { url, body, status, redirected: false, chunk_taken: false }
+    }
+
+    /// A response to `requested` whose body httpc fetched from `final_url`; if they differ,
+    /// redirects were followed. A relative `Location` is resolved against the requested URL.
+    pub fn with_final_url(requested: &str, final_url: &str, body: Vec<u8>, status: u16) -> Self {
+        if final_url == requested {
+            return Self::new(requested.to_string(), body, status);
+        }
+        let resolved = Url::parse(requested)
+            .and_then(|base| base.join(final_url))
+            .map(|u| u.to_string())
+            .unwrap_or_else(|_| final_url.to_string());
+        Self { url: resolved, body, status, redirected: true, chunk_taken: false }
+    }
+}
+
+impl SuccessResponse for FetchedResponse {
+    fn url(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.url)
+    }
+
+    fn body(self: Box<Self>) -> OwnedFuture<Vec<u8>, LoaderError> {
+        Box::pin(async move { Ok(self.body) })
+    }
+
+    fn text_encoding(&self) -> Option<String> {
+        None
+    }
+
+    fn status(&self) -> u16 {
+        self.status
+    }
+
+    fn redirected(&self) -> bool {
+        self.redirected
+    }
+
+    fn next_chunk(&mut self) -> OwnedFuture<Option<Vec<u8>>, LoaderError> {
+        let chunk = if self.chunk_taken { None } else { Some(std::mem::take(&mut self.body)) };
+        self.chunk_taken = true;
+        Box::pin(async move { Ok(chunk) })
+    }
+
+    fn expected_length(&self) -> Result<Option<u64>, LoaderError> {
+        Ok(Some(self.body.len() as u64))
+    }
+}
+
+pub fn error_response(url: &str, kind: std::io::ErrorKind, msg: String) -> ErrorResponse {
+    runlog::warn_line(&format!("fetch failed {}: {}", url, msg));
+    ErrorResponse { url: url.to_string(), error: std::io::Error::new(kind, msg).into() }
+}
+
+/// GET an `http(s)://` URL on a worker thread.
+pub fn fetch_http(url: String) -> OwnedFuture<Box<dyn SuccessResponse>, ErrorResponse> {
+    let (tx, rx) = async_channel::bounded(1);
+    let thread_url = url.clone();
+    let spawned = thread::Builder::new()
+        .name("http_fetch".to_string())
+        .stack_size(FETCH_STACK_BYTES)
+        .spawn(move || {
+            let _ = tx.send_blocking(http_get(&thread_url, FETCH_MAX_BYTES));
+        });
+
+    Box::pin(async move {
+        if spawned.is_err() {
+            return Err(error_response(&url, std::io::ErrorKind::Other, "fetch thread spawn failed".to_string()));
+        }
+        match rx.recv().await {
+            Ok(Ok((status, final_url, body))) if (200..300).contains(&status) => {
+                runlog::log_line(&format!(
+                    "fetch ok {} status={} bytes={} final={}",
+                    url,
+                    status,
+                    body.len(),
+                    final_url
+                ));
+                let response = FetchedResponse::with_final_url(&url, &final_url, body, status);
+                Ok(Box::new(response) as Box<dyn SuccessResponse>)
+            }
+            Ok(Ok((status, _, _))) => {
+                Err(error_response(&url, std::io::ErrorKind::NotFound, format!("HTTP status {}", status)))
+            }
+            Ok(Err(HTTP_ERR_TOO_LARGE)) => Err(error_response(
+                &url,
+                std::io::ErrorKind::InvalidData,
+                format!("response larger than {} bytes", FETCH_MAX_BYTES),
+            )),
+            Ok(Err(code)) => {
+                Err(error_response(&url, std::io::ErrorKind::Other, format!("httpc error {}", code)))
+            }
+            Err(_) => Err(error_response(&url, std::io::ErrorKind::Other, "fetch thread exited".to_string())),
+        }
+    })
+}
+
+/// Map a resolved `file://` or `sdmc:/` URL to a path `read_file_bytes` understands.
+///
+/// `file:///sdmc:/flash/a.swf` keeps its device prefix; `file:///flash/a.swf` gets `sdmc:`.
+pub fn local_path(url: &Url) -> Option<String> {
+    match url.scheme() {
+        "file" => {
+            let path = percent_decode(url.path())?;
+            let trimmed = path.trim_start_matches('/');
+            if trimmed.split('/').next().is_some_and(|seg| seg.ends_with(':')) {
+                Some(trimmed.to_string())
+            } else {
+                Some(format!("sdmc:/{}", trimmed))
+            }
+        }
+        "sdmc" => percent_decode(url.as_str()),
+        _ => None,
+    }
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// Read a local file; the load completes on the next `poll_tasks`.
+pub fn fetch_file(url: String, path: String) -> OwnedFuture<Box<dyn SuccessResponse>, ErrorResponse> {
+    Box::pin(async move {
+        match read_file_bytes(&path) {
+            Some(body) if body.len() <= FETCH_MAX_BYTES => {
+                runlog::log_line(&format!("fetch ok {} bytes={}", path, body.len()));
+                Ok(Box::new(FetchedResponse::new(url, body, 200)) as Box<dyn SuccessResponse>)
+            }
+            Some(_) => Err(error_response(
+                &url,
+                std::io::ErrorKind::InvalidData,
+                format!("file larger than {} bytes", FETCH_MAX_BYTES),
+            )),
+            None => Err(error_response(&url, std::io::ErrorKind::NotFound, format!("could not read {}", path))),
+        }
+    })
+}
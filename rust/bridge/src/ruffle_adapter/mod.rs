@@ -1,4 +1,15 @@
+#[cfg(feature = "audio")]
+mod audio_backend;
+#[cfg(feature = "net")]
+mod fetch;
+#[cfg(feature = "net")]
+mod socket;
+#[cfg(feature = "storage")]
+mod storage;
 mod threed_backend;
 mod tessellate;
+mod tess_worker;
 
-pub use threed_backend::ThreeDSBackend;
+#[cfg(feature = "audio")]
+pub use audio_backend::DspAudioBackend;
+pub use threed_backend::{DiagCounters, ThreeDSBackend};
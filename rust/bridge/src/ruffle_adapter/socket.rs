@@ -0,0 +1,115 @@
+//! TCP sockets for AS2 `XMLSocket`.
+//!
+//! Each connection gets a thread that connects, reports the result as
+//! `SocketAction::Connect`, then shuttles bytes: outbound data from Ruffle's receiver is
+//! written as it arrives, inbound data is forwarded as `SocketAction::Data`. The thread
+//! exits (sending `SocketAction::Close`) when either side closes.
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::thread;
+use std::time::Duration;
+
+use async_channel::{Receiver, Sender};
+use ruffle_core::socket::{ConnectionState, SocketAction, SocketHandle};
+
+use crate::ffi::soc::ensure_soc;
+use crate::runlog;
+
+const SOCKET_STACK_BYTES: usize = 64 * 1024;
+/// Read timeout per loop; bounds how long outbound data waits behind a blocking read.
+const SOCKET_POLL: Duration = Duration::from_millis(10);
+const SOCKET_READ_CHUNK: usize = 4096;
+
+/// Start a connection thread for `handle`. Failures are reported through `sender`.
+pub fn connect(
+    host: String,
+    port: u16,
+    timeout: Duration,
+    handle: SocketHandle,
+    receiver: Receiver<Vec<u8>>,
+    sender: Sender<SocketAction>,
+) {
+    let thread_sender = sender.clone();
+    let spawned = thread::Builder::new()
+        .name("xml_socket".to_string())
+        .stack_size(SOCKET_STACK_BYTES)
+        .spawn(move || run_socket(&host, port, timeout, handle, receiver, thread_sender));
+    if spawned.is_err() {
+        runlog::warn_line("socket: thread spawn failed");
+        let _ = sender.send_blocking(SocketAction::Connect(handle, ConnectionState::Failed));
+    }
+}
+
+fn open_stream(host: &str, port: u16, timeout: Duration) -> Result<TcpStream, ConnectionState> {
+    if !ensure_soc() {
+        runlog::warn_line("socket: soc:U init failed");
+        return Err(ConnectionState::Failed);
+    }
+    let addrs = (host, port).to_socket_addrs().map_err(|_| ConnectionState::Failed)?;
+    let mut last = ConnectionState::Failed;
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) if e.kind() == ErrorKind::TimedOut => last = ConnectionState::TimedOut,
+            Err(_) => last = ConnectionState::Failed,
+        }
+    }
+    Err(last)
+}
+
+fn run_socket(
+    host: &str,
+    port: u16,
+    timeout: Duration,
+    handle: SocketHandle,
+    receiver: Receiver<Vec<u8>>,
+    sender: Sender<SocketAction>,
+) {
+    let mut stream = match open_stream(host, port, timeout) {
+        Ok(stream) => stream,
+        Err(state) => {
+            runlog::warn_line(&format!("socket: connect {}:{} {:?}", host, port, state));
+            let _ = sender.send_blocking(SocketAction::Connect(handle, state));
+            return;
+        }
+    };
+    let _ = stream.set_nodelay(true);
+    let _ = stream.set_read_timeout(Some(SOCKET_POLL));
+    runlog::log_line(&format!("socket: connected {}:{}", host, port));
+    if sender.send_blocking(SocketAction::Connect(handle, ConnectionState::Connected)).is_err() {
+        return;
+    }
+
+    let mut buf = vec![0u8; SOCKET_READ_CHUNK];
+    loop {
+        // Outbound: flush everything Ruffle queued since the last pass.
+        while let Ok(data) = receiver.try_recv() {
+            if stream.write_all(&data).is_err() {
+                let _ = sender.send_blocking(SocketAction::Close(handle));
+                return;
+            }
+        }
+        if receiver.is_closed() && receiver.is_empty() {
+            // Ruffle closed the socket.
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+
+        // Inbound.
+        match stream.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => {
+                if sender.send_blocking(SocketAction::Data(handle, buf[..n].to_vec())).is_err() {
+                    break;
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {}
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(_) => break,
+        }
+    }
+    runlog::log_line(&format!("socket: closed {}:{}", host, port));
+    let _ = stream.shutdown(Shutdown::Both);
+    let _ = sender.send_blocking(SocketAction::Close(handle));
+}
@@ -0,0 +1,144 @@
+//! SharedObject persistence on the SD card.
+//!
+//! Each key is one file under `sdmc:/flash/_storage/<swf_name>/`, holding the raw bytes
+//! Ruffle hands to `StorageBackend::put`. Keys (which contain `/`, e.g.
+//! `localhost/game.swf/highscores`) are escaped into a single file name, so no key can
+//! reach outside the movie's folder.
+
+use std::fs;
+use std::path::Path;
+
+use crate::runlog;
+
+const STORAGE_ROOT: &str = "sdmc:/flash/_storage";
+/// Longest escaped file name we write; FAT long names top out at 255.
+const MAX_NAME_LEN: usize = 200;
+
+/// Escape `raw` into one path component: `[a-z0-9_-]` and non-leading `.` pass through,
+/// everything else (uppercase letters included) becomes `%XX` with uppercase hex. FAT
+/// compares names case-insensitively, so uppercase can't pass through: `Save` and `save`
+/// would land in the same file. With it escaped the mapping stays injective even after case
+/// folding. Returns `None` for empty or over-long names.
+pub fn sanitize_component(raw: &str) -> Option<String> {
+    let mut out = String::with_capacity(raw.len());
+    for (i, b) in raw.bytes().enumerate() {
+        let keep = b.is_ascii_lowercase()
+            || b.is_ascii_digit()
+            || b == b'_'
+            || b == b'-'
+            || (b == b'.' && i > 0);
+        if keep {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    if out.is_empty() || out.len() > MAX_NAME_LEN {
+        return None;
+    }
+    Some(out)
+}
+
+/// Folder for a movie's SharedObjects, keyed by the SWF file name.
+pub fn movie_dir(root_path: &str) -> Option<String> {
+    let name = root_path.rsplit('/').next().unwrap_or(root_path);
+    Some(format!("{}/{}", STORAGE_ROOT, sanitize_component(name)?))
+}
+
+fn key_path(dir: &str, key: &str) -> Option<String> {
+    match sanitize_component(key) {
+        Some(name) => Some(format!("{}/{}", dir, name)),
+        None => {
+            runlog::warn_line(&format!("storage: rejected key len={}", key.len()));
+            None
+        }
+    }
+}
+
+/// Falls back to the `%bak` copy `put` leaves if it was interrupted mid-swap.
+pub fn get(dir: &str, key: &str) -> Option<Vec<u8>> {
+    let path = key_path(dir, key)?;
+    fs::read(&path).or_else(|_| fs::read(format!("{}%bak", path))).ok()
+}
+
+/// Write through a temp file and rename. FAT can't rename over a file, so the previous save
+/// is first moved to `%bak` and moved back if the rename fails: a failed `put` leaves the old
+/// save in place, and one cut off mid-swap leaves it readable through `get`.
+/// The `%tmp`/`%bak` suffixes can't come out of `sanitize_component`, so they never clobber a key.
+pub fn put(dir: &str, key: &str, value: &[u8]) -> bool {
+    let Some(path) = key_path(dir, key) else {
+        return false;
+    };
+    if fs::create_dir_all(dir).is_err() {
+        runlog::warn_line(&format!("storage: mkdir failed {}", dir));
+        return false;
+    }
+    let tmp = format!("{}%tmp", path);
+    let bak = format!("{}%bak", path);
+    let had_prev = Path::new(&path).exists();
+    if had_prev {
+        // Left by an interrupted swap; the save at `path` is newer.
+        let _ = fs::remove_file(&bak);
+    }
+    let ok = fs::write(&tmp, value).is_ok()
+        && (!had_prev || fs::rename(&path, &bak).is_ok())
+        && (fs::rename(&tmp, &path).is_ok() || {
+            if had_prev {
+                let _ = fs::rename(&bak, &path);
+            }
+            false
+        });
+    if ok {
+        let _ = fs::remove_file(&bak);
+    } else {
+        let _ = fs::remove_file(&tmp);
+        runlog::warn_line(&format!("storage: write failed {}", path));
+    }
+    ok
+}
+
+pub fn remove(dir: &str, key: &str) {
+    if let Some(path) = key_path(dir, key) {
+        let _ = fs::remove_file(format!("{}%bak", path));
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("storage_test_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn put_replaces_previous_save_without_leftovers() {
+        let dir = temp_dir("replace");
+        assert!(put(&dir, "game/save", b"one"));
+        assert!(put(&dir, "game/save", b"two"));
+        assert_eq!(get(&dir, "game/save").as_deref(), Some(&b"two"[..]));
+        let mut names: Vec<_> = fs::read_dir(&dir).unwrap().map(|e| e.unwrap().file_name()).collect();
+        names.sort();
+        assert_eq!(names, vec!["game%2Fsave"]);
+        remove(&dir, "game/save");
+        assert_eq!(get(&dir, "game/save"), None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn interrupted_swap_still_reads_backup() {
+        let dir = temp_dir("interrupted");
+        assert!(put(&dir, "save", b"old"));
+        // Cut off after the old save was parked but before the new one landed.
+        let path = key_path(&dir, "save").unwrap();
+        fs::rename(&path, format!("{}%bak", path)).unwrap();
+        assert_eq!(get(&dir, "save").as_deref(), Some(&b"old"[..]));
+        assert!(put(&dir, "save", b"new"));
+        assert_eq!(get(&dir, "save").as_deref(), Some(&b"new"[..]));
+        assert!(!Path::new(&format!("{}%bak", path)).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,126 @@
+//! Background tessellation queue.
+//!
+//! `register_shape` hands shapes to this worker so complex outlines don't stall the main
+//! thread (and trip the shape watchdog). The worker owns a copy of the path data, because
+//! `DistilledShape` borrows from the movie and can't outlive the `register_shape` call.
+
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+use ruffle_core::swf::{CharacterId, FillStyle, LineStyle, Rectangle, Twips};
+use ruffle_render::shape_utils::{DistilledShape, DrawCommand, DrawPath, FillRule};
+
+use crate::render::RectI;
+
+/// Worker stack size; meshes live on the heap, so this only needs to cover the tessellator's frames.
+const TESS_WORKER_STACK_BYTES: usize = 256 * 1024;
+
+/// Owned copy of one `DrawPath`.
+enum OwnedPath {
+    Fill { style: FillStyle, commands: Vec<DrawCommand>, winding_rule: FillRule },
+    Stroke { style: LineStyle, is_closed: bool, commands: Vec<DrawCommand> },
+}
+
+/// Owned copy of a `DistilledShape`, sendable to the worker thread.
+pub struct OwnedShape {
+    paths: Vec<OwnedPath>,
+    shape_bounds: Rectangle<Twips>,
+    edge_bounds: Rectangle<Twips>,
+    id: CharacterId,
+}
+
+impl OwnedShape {
+    pub fn from_distilled(shape: &DistilledShape<'_>) -> Self {
+        let paths = shape
+            .paths
+            .iter()
+            .map(|path| match path {
+                DrawPath::Fill { style, commands, winding_rule } => OwnedPath::Fill {
+                    style: (*style).clone(),
+                    commands: commands.clone(),
+                    winding_rule: *winding_rule,
+                },
+                DrawPath::Stroke { style, is_closed, commands } => OwnedPath::Stroke {
+                    style: (*style).clone(),
+                    is_closed: *is_closed,
+                    commands: commands.clone(),
+                },
+            })
+            .collect();
+        Self {
+            paths,
+            shape_bounds: shape.shape_bounds,
+            edge_bounds: shape.edge_bounds,
+            id: shape.id,
+        }
+    }
+
+    /// Borrowed view for the tessellator.
+    pub fn as_distilled(&self) -> DistilledShape<'_> {
+        let paths = self
+            .paths
+            .iter()
+            .map(|path| match path {
+                OwnedPath::Fill { style, commands, winding_rule } => DrawPath::Fill {
+                    style,
+                    commands: commands.clone(),
+                    winding_rule: *winding_rule,
+                },
+                OwnedPath::Stroke { style, is_closed, commands } => DrawPath::Stroke {
+                    style,
+                    is_closed: *is_closed,
+                    commands: commands.clone(),
+                },
+            })
+            .collect();
+        DistilledShape {
+            paths,
+            shape_bounds: self.shape_bounds,
+            edge_bounds: self.edge_bounds,
+            id: self.id,
+        }
+    }
+}
+
+/// One queued shape: the placeholder already sits in the cache under `key`.
+pub struct TessJob {
+    pub key: usize,
+    pub id: u32,
+    pub bounds: RectI,
+    pub content_hash: Option<u64>,
+    /// Flattening tolerance fixed at registration (stage quality may change before the job runs).
+    pub tol_px: f32,
+    pub shape: OwnedShape,
+}
+
+pub struct TessWorker {
+    tx: Sender<TessJob>,
+    _handle: JoinHandle<()>,
+}
+
+impl TessWorker {
+    /// Start the worker; `run` is called once per job, in submission order.
+    ///
+    /// Returns `None` if the thread couldn't be created (callers tessellate inline instead).
+    pub fn spawn<F>(mut run: F) -> Option<Self>
+    where
+        F: FnMut(TessJob) + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel::<TessJob>();
+        let handle = thread::Builder::new()
+            .name("tess_worker".to_string())
+            .stack_size(TESS_WORKER_STACK_BYTES)
+            .spawn(move || {
+                while let Ok(job) = rx.recv() {
+                    run(job);
+                }
+            })
+            .ok()?;
+        Some(Self { tx, _handle: handle })
+    }
+
+    /// Queue a job; hands it back if the worker has exited.
+    pub fn submit(&self, job: TessJob) -> Result<(), TessJob> {
+        self.tx.send(job).map_err(|err| err.0)
+    }
+}
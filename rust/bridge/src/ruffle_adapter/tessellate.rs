@@ -6,10 +6,15 @@
 //! - Output types must be renderer-owned: `Vec<Vertex2>` + `Vec<u16>`.
 //! - No per-frame allocations: tessellation runs at **register_shape** time.
 
-use crate::render::cache::shapes::{FillMesh, FillPaint, StrokeMesh, Vertex2};
+use crate::render::cache::shapes::{
+    FillMesh, FillPaint, GradientPaint, StrokeMesh, Vertex2, HAIRLINE_MAX_PX, MAX_GRADIENT_STOPS,
+};
+use crate::render::{GradientSpread, Matrix2D};
 use crate::runlog;
+use crate::util::config;
+use ruffle_render::quality::StageQuality;
 use ruffle_render::shape_utils::{DistilledShape, DrawCommand, DrawPath, FillRule};
-use ruffle_core::swf::{FillStyle, LineJoinStyle};
+use ruffle_core::swf::{FillStyle, Gradient, LineCapStyle, LineJoinStyle, LineStyle};
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::time::Instant;
 
@@ -37,8 +42,12 @@ const EARCUT_MAX_TOTAL_POINTS: usize = 256;
 const EARCUT_MAX_HOLES: usize = 8;
 const EARCUT_MAX_OUTER_POINTS: usize = 192;
 const CONVEX_FAN_MAX_OUTER_POINTS: usize = 128;
+/// Upper bound on arc segments for a round cap (a half circle).
+const MAX_ROUND_CAP_SEGMENTS: usize = 8;
 
 static UNSUPPORTED_FILL_WARNINGS: AtomicU32 = AtomicU32::new(0);
+static WINDING_FLIP_WARNINGS: AtomicU32 = AtomicU32::new(0);
+const MAX_WINDING_FLIP_WARNINGS: u32 = 8;
 
 type Point = (f32, f32);
 
@@ -65,7 +74,7 @@ pub struct StrokeOutput {
 /// Tessellate filled regions of a Ruffle distilled shape.
 ///
 /// Output coordinates are in **pixel units**, in the shape's local space.
-pub fn tessellate_fills(shape: &DistilledShape<'_>, shape_id: u32) -> Result<TessOutput, TessError> {
+pub fn tessellate_fills(shape: &DistilledShape<'_>, shape_id: u32, tol_px: f32) -> Result<TessOutput, TessError> {
     // Registration-time tessellation.
     // We output one mesh per Fill path so the renderer can draw multiple fills for a single shape.
     // Each Fill path comes with its own winding_rule.
@@ -81,25 +90,37 @@ pub fn tessellate_fills(shape: &DistilledShape<'_>, shape_id: u32) -> Result<Tes
     let mut logged_timeout = false;
     let mut logged_convex_fan = false;
 
-    let tol_px = tessellation_tolerance_px(shape);
     for path in &shape.paths {
         let fill_idx = fill_paths.saturating_add(1);
         let (commands, rule, paint) = match path {
             DrawPath::Fill { commands, winding_rule, style, .. } => {
                 let paint = match style {
-                    FillStyle::Color(color) => FillPaint::SolidRGBA(color.r, color.g, color.b, color.a),
-                    _ => {
-                        unsupported_fill_paints = unsupported_fill_paints.saturating_add(1);
-                        let count = UNSUPPORTED_FILL_WARNINGS.fetch_add(1, Ordering::Relaxed);
-                        if count < MAX_UNSUPPORTED_FILL_WARNINGS {
-                            runlog::warn_line(&format!(
-                                "fill_style unsupported shape={} fill_path={}",
-                                shape_id, fill_idx
-                            ));
-                        }
-                        FillPaint::Unsupported
+                    FillStyle::Color(color) => Some(FillPaint::SolidRGBA(color.r, color.g, color.b, color.a)),
+                    FillStyle::LinearGradient(gradient) => gradient_paint(gradient).map(FillPaint::LinearGradient),
+                    FillStyle::RadialGradient(gradient) => {
+                        gradient_paint(gradient).map(|paint| FillPaint::RadialGradient { paint, focal_point: None })
                     }
+                    FillStyle::FocalGradient { gradient, focal_point } => gradient_paint(gradient)
+                        .map(|paint| FillPaint::RadialGradient { paint, focal_point: Some(f32::from(*focal_point)) }),
+                    FillStyle::Bitmap { id, matrix, is_smoothed, is_repeating } => bitmap_matrix(matrix).map(|matrix| FillPaint::Bitmap {
+                        bitmap_id: *id,
+                        bitmap_key: None,
+                        matrix,
+                        smoothing: *is_smoothed,
+                        repeating: *is_repeating,
+                    }),
                 };
+                let paint = paint.unwrap_or_else(|| {
+                    unsupported_fill_paints = unsupported_fill_paints.saturating_add(1);
+                    let count = UNSUPPORTED_FILL_WARNINGS.fetch_add(1, Ordering::Relaxed);
+                    if count < MAX_UNSUPPORTED_FILL_WARNINGS {
+                        runlog::warn_line(&format!(
+                            "fill_style unsupported shape={} fill_path={}",
+                            shape_id, fill_idx
+                        ));
+                    }
+                    FillPaint::Unsupported
+                });
                 (commands, *winding_rule, paint)
             }
             _ => continue, // fills-only Step 2A
@@ -361,7 +382,9 @@ pub fn tessellate_fills(shape: &DistilledShape<'_>, shape_id: u32) -> Result<Tes
                     ));
                     return Err(TessError::TooManyVerts);
                 }
+                let tri_start = out_indices.len();
                 triangulate_convex_fan(base, ring_len, &mut out_indices);
+                warn_winding_flips(shape_id, "convex_fan", enforce_ccw_triangles(&out_verts, &mut out_indices[tri_start..]));
                 if !logged_convex_fan {
                     logged_convex_fan = true;
                     #[cfg(feature = "verbose_logs")]
@@ -553,6 +576,7 @@ pub fn tessellate_fills(shape: &DistilledShape<'_>, shape_id: u32) -> Result<Tes
                 return Err(TessError::EarcutFailed);
             }
 
+            let tri_start = out_indices.len();
             for &i in idx.iter() {
                 let vi = base + i;
                 if vi >= MAX_VERTS_PER_MESH {
@@ -564,6 +588,9 @@ pub fn tessellate_fills(shape: &DistilledShape<'_>, shape_id: u32) -> Result<Tes
                 }
                 out_indices.push(vi as u16);
             }
+            // Outer rings are CCW here, so earcut should already emit CCW triangles; anything
+            // else means the grouping/orientation step regressed.
+            warn_winding_flips(shape_id, "earcut", enforce_ccw_triangles(&out_verts, &mut out_indices[tri_start..]));
         }
 
         if timed_out {
@@ -610,10 +637,9 @@ pub fn tessellate_fills(shape: &DistilledShape<'_>, shape_id: u32) -> Result<Tes
     })
 }
 
-pub fn tessellate_strokes(shape: &DistilledShape<'_>, shape_id: u32) -> Result<StrokeOutput, TessError> {
+pub fn tessellate_strokes(shape: &DistilledShape<'_>, shape_id: u32, tol_px: f32) -> Result<StrokeOutput, TessError> {
     let mut strokes: Vec<StrokeMesh> = Vec::new();
     let mut any_failed = false;
-    let tol_px = tessellation_tolerance_px(shape);
     let mut stroke_paths = 0usize;
 
     for path in &shape.paths {
@@ -629,15 +655,15 @@ pub fn tessellate_strokes(shape: &DistilledShape<'_>, shape_id: u32) -> Result<S
             continue;
         };
 
+        // Zero-width strokes are SWF hairlines: always drawn 1px wide, as device lines only.
         let width_px = style.width().to_pixels() as f32;
-        if width_px <= 0.0 {
+        if width_px < 0.0 {
             continue;
         }
+        let hairline = width_px <= HAIRLINE_MAX_PX;
         let half_w = (width_px * 0.5).max(0.5);
-        let miter_limit = match style.join_style() {
-            LineJoinStyle::Miter(limit) => f32::from(limit).max(1.0),
-            _ => 4.0,
-        };
+        let join = style.join_style();
+        let caps = (style.start_cap(), style.end_cap());
 
         let mut polylines = flatten_commands_to_polylines(commands.iter(), tol_px, is_closed);
         for line in polylines.iter_mut() {
@@ -655,8 +681,16 @@ pub fn tessellate_strokes(shape: &DistilledShape<'_>, shape_id: u32) -> Result<S
             continue;
         }
 
+        let dash = dash_pattern(style);
         for line in polylines {
-            match build_stroke_mesh(&line, half_w, miter_limit, is_closed) {
+            let mesh = match dash.as_deref() {
+                Some(pattern) => build_dashed_stroke_mesh(&line, pattern, half_w, join, is_closed, caps),
+                None if width_px == 0.0 => None,
+                None => build_stroke_mesh(&line, half_w, join, is_closed, caps),
+            };
+            // Dashed strokes keep their meshes; the polyline would draw them solid.
+            let line = if hairline && dash.is_none() { line } else { Vec::new() };
+            match mesh {
                 Some(mesh) => {
                     strokes.push(StrokeMesh {
                         verts: mesh.verts,
@@ -664,6 +698,21 @@ pub fn tessellate_strokes(shape: &DistilledShape<'_>, shape_id: u32) -> Result<S
                         r: color.r,
                         g: color.g,
                         b: color.b,
+                        line,
+                        width: width_px,
+                        closed: is_closed,
+                    });
+                }
+                None if !line.is_empty() => {
+                    strokes.push(StrokeMesh {
+                        verts: Vec::new(),
+                        indices: Vec::new(),
+                        r: color.r,
+                        g: color.g,
+                        b: color.b,
+                        line,
+                        width: width_px,
+                        closed: is_closed,
                     });
                 }
                 None => {
@@ -685,8 +734,115 @@ pub fn tessellate_strokes(shape: &DistilledShape<'_>, shape_id: u32) -> Result<S
     Ok(StrokeOutput { strokes, any_failed })
 }
 
-fn tessellation_tolerance_px(_shape: &DistilledShape<'_>) -> f32 {
-    0.5
+/// Convert a SWF gradient into a renderer-owned paint.
+///
+/// The SWF gradient matrix maps the gradient square (±16384 twips) into shape space; we fold
+/// the twips→pixels scale in and invert it so the rasterizer can go local pixels → gradient.
+fn gradient_paint(gradient: &Gradient) -> Option<GradientPaint> {
+    if gradient.records.is_empty() {
+        return None;
+    }
+    const GRADIENT_HALF_SIZE_PX: f32 = 16384.0 / 20.0;
+    let m = &gradient.matrix;
+    let local_from_gradient = Matrix2D {
+        a: f32::from(m.a) * GRADIENT_HALF_SIZE_PX,
+        b: f32::from(m.b) * GRADIENT_HALF_SIZE_PX,
+        c: f32::from(m.c) * GRADIENT_HALF_SIZE_PX,
+        d: f32::from(m.d) * GRADIENT_HALF_SIZE_PX,
+        tx: m.tx.to_pixels() as f32,
+        ty: m.ty.to_pixels() as f32,
+    };
+    let local_to_gradient = local_from_gradient.invert()?;
+
+    let mut stops = [(0u8, [0u8; 4]); MAX_GRADIENT_STOPS];
+    let stop_count = gradient.records.len().min(MAX_GRADIENT_STOPS);
+    for (slot, record) in stops.iter_mut().zip(gradient.records.iter()) {
+        *slot = (record.ratio, [record.color.r, record.color.g, record.color.b, record.color.a]);
+    }
+    stops[..stop_count].sort_by_key(|stop| stop.0);
+
+    let spread = match gradient.spread {
+        ruffle_core::swf::GradientSpread::Pad => GradientSpread::Pad,
+        ruffle_core::swf::GradientSpread::Reflect => GradientSpread::Reflect,
+        ruffle_core::swf::GradientSpread::Repeat => GradientSpread::Repeat,
+    };
+    Some(GradientPaint { stops, stop_count: stop_count as u8, spread, local_to_gradient })
+}
+
+/// Convert a SWF bitmap fill matrix into shape-local pixels → bitmap texels.
+///
+/// The SWF matrix maps texels into shape twips (a scale of 20 is 1:1), so we fold the
+/// twips→pixels scale in and invert it.
+fn bitmap_matrix(m: &ruffle_core::swf::Matrix) -> Option<Matrix2D> {
+    let local_from_bitmap = Matrix2D {
+        a: f32::from(m.a) / 20.0,
+        b: f32::from(m.b) / 20.0,
+        c: f32::from(m.c) / 20.0,
+        d: f32::from(m.d) / 20.0,
+        tx: m.tx.to_pixels() as f32,
+        ty: m.ty.to_pixels() as f32,
+    };
+    local_from_bitmap.invert()
+}
+
+/// FNV-1a over formatted text, so hashing a shape needs no allocation.
+struct ContentHasher(u64);
+
+impl core::fmt::Write for ContentHasher {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for &byte in s.as_bytes() {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01B3);
+        }
+        Ok(())
+    }
+}
+
+/// Stable hash of a shape's bounds and draw paths (styles included, shape id excluded), plus
+/// the flattening tolerance so meshes tessellated at another quality are never shared.
+///
+/// `None` for shapes with bitmap fills: their character ids resolve per movie, so equal
+/// content does not imply equal bitmaps.
+pub fn shape_content_hash(shape: &DistilledShape<'_>, tol_px: f32) -> Option<u64> {
+    use core::fmt::Write;
+    let mut hasher = ContentHasher(0xCBF2_9CE4_8422_2325);
+    let _ = write!(hasher, "{:?} {:08x}", shape.shape_bounds, tol_px.to_bits());
+    for path in &shape.paths {
+        if matches!(path, DrawPath::Fill { style: FillStyle::Bitmap { .. }, .. }) {
+            return None;
+        }
+        let _ = write!(hasher, "{:?}", path);
+    }
+    Some(hasher.0)
+}
+
+/// Curve flattening tolerance for a shape drawn at 1:1.
+const BASE_TOLERANCE_PX: f32 = 0.5;
+
+/// Flattening tolerance (shape-local pixels) for a shape expected to be drawn at up to
+/// `max_scale`, so the on-screen deviation stays around `BASE_TOLERANCE_PX`.
+///
+/// Clamped so tiny scales don't collapse curves and huge ones don't explode vertex counts.
+pub fn tolerance_for_scale(max_scale: f32) -> f32 {
+    (BASE_TOLERANCE_PX / max_scale.max(0.01)).clamp(0.05, 4.0)
+}
+
+/// Flattening tolerance for shapes registered while the stage is at `quality`.
+///
+/// The draw transform isn't known at registration, so the expected scale comes from config
+/// (`tess_max_scale`); quality then scales the result before clamping:
+/// - `Low`: 4x (2px at 1:1), for content that drops quality for speed
+/// - `Medium`: 2x (1px)
+/// - `High` (the Flash default): 1x (0.5px)
+/// - `Best` and the `High8x8`/`High16x16` variants: 0.5x (0.25px)
+pub fn tolerance_for_quality(quality: StageQuality) -> f32 {
+    let factor = match quality {
+        StageQuality::Low => 4.0,
+        StageQuality::Medium => 2.0,
+        StageQuality::High => 1.0,
+        _ => 0.5,
+    };
+    (tolerance_for_scale(config::tess_max_scale()) * factor).clamp(0.05, 4.0)
 }
 
 // -----------------
@@ -970,7 +1126,103 @@ fn simplify_polyline(line: &mut Vec<(f32, f32)>) {
     }
 }
 
-fn build_stroke_mesh(points: &[(f32, f32)], half_w: f32, miter_limit: f32, closed: bool) -> Option<FillMesh> {
+/// Dash pattern for a stroke, as alternating on/off lengths in pixels.
+///
+/// SWF line styles carry no dash data, so today the only source is the `stroke_dash`
+/// config override (applied to every stroke).
+fn dash_pattern(_style: &LineStyle) -> Option<Vec<f32>> {
+    let pattern = config::stroke_dash();
+    if pattern.is_empty() {
+        return None;
+    }
+    Some(pattern.iter().map(|&px| px as f32).collect())
+}
+
+/// Split a polyline into its "on" runs for `pattern`.
+///
+/// The dash phase carries across vertices, so dashes don't restart at every corner.
+fn dash_polyline(points: &[(f32, f32)], closed: bool, pattern: &[f32]) -> Vec<Vec<(f32, f32)>> {
+    let mut pts = points.to_vec();
+    if closed && !approx_eq(pts[0], pts[pts.len() - 1]) {
+        pts.push(pts[0]);
+    }
+    let mut runs: Vec<Vec<(f32, f32)>> = Vec::new();
+    let mut current: Vec<(f32, f32)> = vec![pts[0]];
+    let mut on = true;
+    let mut dash_idx = 0usize;
+    let mut remaining = pattern[0];
+    let mut total_points = 1usize;
+    for w in pts.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        let len = ((b.0 - a.0) * (b.0 - a.0) + (b.1 - a.1) * (b.1 - a.1)).sqrt();
+        let mut t = 0.0f32;
+        while len - t > remaining {
+            t += remaining;
+            let f = t / len;
+            let p = (a.0 + (b.0 - a.0) * f, a.1 + (b.1 - a.1) * f);
+            // Off runs left `current` empty, so this starts the next dash.
+            current.push(p);
+            if on {
+                runs.push(core::mem::take(&mut current));
+            }
+            total_points += 1;
+            if total_points > MAX_POINTS_PER_STROKE {
+                return runs;
+            }
+            on = !on;
+            dash_idx = (dash_idx + 1) % pattern.len();
+            remaining = pattern[dash_idx];
+        }
+        remaining -= len - t;
+        if on {
+            current.push(b);
+            total_points += 1;
+        }
+    }
+    if on && current.len() >= 2 {
+        runs.push(current);
+    }
+    runs
+}
+
+/// Stroke each dash of a polyline as its own open run, merged into one mesh.
+fn build_dashed_stroke_mesh(
+    points: &[(f32, f32)],
+    pattern: &[f32],
+    half_w: f32,
+    join: LineJoinStyle,
+    closed: bool,
+    caps: (LineCapStyle, LineCapStyle),
+) -> Option<FillMesh> {
+    let mut verts: Vec<Vertex2> = Vec::new();
+    let mut indices: Vec<u16> = Vec::new();
+    for run in dash_polyline(points, closed, pattern) {
+        let Some(mesh) = build_stroke_mesh(&run, half_w, join, false, caps) else { continue; };
+        if verts.len() + mesh.verts.len() > MAX_VERTS_PER_MESH {
+            return None;
+        }
+        let base = verts.len() as u16;
+        verts.extend(mesh.verts);
+        indices.extend(mesh.indices.into_iter().map(|i| i + base));
+    }
+    if indices.is_empty() {
+        return None;
+    }
+    Some(FillMesh { verts, indices, paint: FillPaint::Unsupported })
+}
+
+/// Offset a polyline into a triangle strip of width `2 * half_w`.
+///
+/// Miter joins share one vertex pair per point; bevel and round joins use one quad per
+/// segment plus join geometry on the outer side of each corner (see `stroke_quads_with_joins`).
+/// `caps` are the `(start, end)` cap styles; they only apply to open polylines.
+fn build_stroke_mesh(
+    points: &[(f32, f32)],
+    half_w: f32,
+    join: LineJoinStyle,
+    closed: bool,
+    caps: (LineCapStyle, LineCapStyle),
+) -> Option<FillMesh> {
     if points.len() < 2 {
         return None;
     }
@@ -981,9 +1233,22 @@ fn build_stroke_mesh(points: &[(f32, f32)], half_w: f32, miter_limit: f32, close
     if pts.len() < 2 {
         return None;
     }
-    if pts.len() * 2 > MAX_VERTS_PER_MESH {
+    if pts.len() * 4 > MAX_VERTS_PER_MESH {
         return None;
     }
+    if !closed {
+        // Square caps: push the endpoints out by half the width along the end tangents, so the
+        // strip itself covers the cap with no extra geometry.
+        let last = pts.len() - 1;
+        if caps.0 == LineCapStyle::Square {
+            let t = normalize_vec((pts[0].0 - pts[1].0, pts[0].1 - pts[1].1));
+            pts[0] = (pts[0].0 + t.0 * half_w, pts[0].1 + t.1 * half_w);
+        }
+        if caps.1 == LineCapStyle::Square {
+            let t = normalize_vec((pts[last].0 - pts[last - 1].0, pts[last].1 - pts[last - 1].1));
+            pts[last] = (pts[last].0 + t.0 * half_w, pts[last].1 + t.1 * half_w);
+        }
+    }
 
     let count = pts.len();
     let seg_count = if closed { count } else { count - 1 };
@@ -1003,6 +1268,27 @@ fn build_stroke_mesh(points: &[(f32, f32)], half_w: f32, miter_limit: f32, close
         normals.push((nx, ny));
     }
 
+    let miter_limit = match join {
+        LineJoinStyle::Miter(limit) => f32::from(limit).max(1.0),
+        LineJoinStyle::Bevel | LineJoinStyle::Round => {
+            let (mut verts, mut indices) =
+                stroke_quads_with_joins(&pts, &normals, half_w, closed, matches!(join, LineJoinStyle::Round));
+            if !closed {
+                let last = count - 1;
+                let n_start = normals[0];
+                let n_end = normals[seg_count - 1];
+                let out_start = (-n_start.1, n_start.0);
+                let out_end = (n_end.1, -n_end.0);
+                append_stroke_cap(&mut verts, &mut indices, pts[0], n_start, out_start, half_w, 0, caps.0);
+                append_stroke_cap(&mut verts, &mut indices, pts[last], n_end, out_end, half_w, 4 * (seg_count - 1) + 2, caps.1);
+            }
+            if verts.len() > MAX_VERTS_PER_MESH {
+                return None;
+            }
+            return Some(FillMesh { verts, indices, paint: FillPaint::Unsupported });
+        }
+    };
+
     let mut verts: Vec<Vertex2> = Vec::with_capacity(count * 2);
     for i in 0..count {
         let p = pts[i];
@@ -1041,9 +1327,141 @@ fn build_stroke_mesh(points: &[(f32, f32)], half_w: f32, miter_limit: f32, close
         indices.extend_from_slice(&[i0, i2, i1, i1, i2, i3]);
     }
 
+    if !closed {
+        // Endpoint normals are the plain segment normals (no miter), so the strip ends at
+        // `p ± n * half_w` and the cap spans those two vertices.
+        let last = count - 1;
+        let n_start = normals[0];
+        let n_end = normals[count - 2];
+        // Outward directions: against the first segment, along the last one.
+        let out_start = (-n_start.1, n_start.0);
+        let out_end = (n_end.1, -n_end.0);
+        append_stroke_cap(&mut verts, &mut indices, pts[0], n_start, out_start, half_w, 0, caps.0);
+        append_stroke_cap(&mut verts, &mut indices, pts[last], n_end, out_end, half_w, 2 * last, caps.1);
+        if verts.len() > MAX_VERTS_PER_MESH {
+            return None;
+        }
+    }
+
     Some(FillMesh { verts, indices, paint: FillPaint::Unsupported })
 }
 
+/// One independent quad per segment, with bevel or round fill on the outer side of each join.
+///
+/// Segment `i` owns vertices `4 * i .. 4 * i + 4`: start `+n`, start `-n`, end `+n`, end `-n`.
+/// The inner side of a corner is covered by the overlapping quads.
+fn stroke_quads_with_joins(
+    pts: &[(f32, f32)],
+    normals: &[(f32, f32)],
+    half_w: f32,
+    closed: bool,
+    round: bool,
+) -> (Vec<Vertex2>, Vec<u16>) {
+    let count = pts.len();
+    let seg_count = normals.len();
+    let to_vertex = |x: f32, y: f32| Vertex2 { x: x.round() as i32, y: y.round() as i32 };
+    let mut verts: Vec<Vertex2> = Vec::with_capacity(seg_count * 4);
+    let mut indices: Vec<u16> = Vec::with_capacity(seg_count * 6);
+    for (i, &n) in normals.iter().enumerate() {
+        let p0 = pts[i];
+        let p1 = pts[(i + 1) % count];
+        let base = verts.len() as u16;
+        let (ox, oy) = (n.0 * half_w, n.1 * half_w);
+        verts.push(to_vertex(p0.0 + ox, p0.1 + oy));
+        verts.push(to_vertex(p0.0 - ox, p0.1 - oy));
+        verts.push(to_vertex(p1.0 + ox, p1.1 + oy));
+        verts.push(to_vertex(p1.0 - ox, p1.1 - oy));
+        indices.extend_from_slice(&[base, base + 2, base + 1, base + 1, base + 2, base + 3]);
+    }
+
+    let joins = if closed { 0..count } else { 1..count - 1 };
+    for i in joins {
+        let prev_seg = (i + seg_count - 1) % seg_count;
+        let next_seg = i % seg_count;
+        let n_prev = normals[prev_seg];
+        let n_next = normals[next_seg];
+        if n_prev == (0.0, 0.0) || n_next == (0.0, 0.0) {
+            continue;
+        }
+        // The outer side is the one the path turns away from.
+        let t_next = (n_next.1, -n_next.0);
+        let outer_is_left = t_next.0 * n_prev.0 + t_next.1 * n_prev.1 <= 0.0;
+        let side = if outer_is_left { 1.0 } else { -1.0 };
+        let a = (4 * prev_seg + 2 + usize::from(!outer_is_left)) as u16;
+        let b = (4 * next_seg + usize::from(!outer_is_left)) as u16;
+        let p = pts[i];
+        let center = verts.len() as u16;
+        verts.push(to_vertex(p.0, p.1));
+        if !round {
+            indices.extend_from_slice(&[center, a, b]);
+            continue;
+        }
+        // Arc from the previous segment's outer offset to the next one's.
+        let u = (n_prev.0 * side, n_prev.1 * side);
+        let w = (n_next.0 * side, n_next.1 * side);
+        let phi = (u.0 * w.1 - u.1 * w.0).atan2(u.0 * w.0 + u.1 * w.1);
+        let steps = ((phi.abs() / core::f32::consts::PI * MAX_ROUND_CAP_SEGMENTS as f32).ceil() as usize).max(1);
+        let mut prev = a;
+        for k in 1..steps {
+            let (s, c) = (phi * k as f32 / steps as f32).sin_cos();
+            let idx = verts.len() as u16;
+            verts.push(to_vertex(
+                p.0 + (u.0 * c - u.1 * s) * half_w,
+                p.1 + (u.0 * s + u.1 * c) * half_w,
+            ));
+            indices.extend_from_slice(&[center, prev, idx]);
+            prev = idx;
+        }
+        indices.extend_from_slice(&[center, prev, b]);
+    }
+    (verts, indices)
+}
+
+/// Append cap geometry at an open stroke endpoint `p`.
+///
+/// `left_idx` / `left_idx + 1` are the strip's `p + n * half_w` / `p - n * half_w` vertices and
+/// `out` is the unit direction pointing away from the stroke.
+fn append_stroke_cap(
+    verts: &mut Vec<Vertex2>,
+    indices: &mut Vec<u16>,
+    p: (f32, f32),
+    n: (f32, f32),
+    out: (f32, f32),
+    half_w: f32,
+    left_idx: usize,
+    cap: LineCapStyle,
+) {
+    if n == (0.0, 0.0) {
+        return;
+    }
+    let left = left_idx as u16;
+    let right = (left_idx + 1) as u16;
+    let to_vertex = |x: f32, y: f32| Vertex2 { x: x.round() as i32, y: y.round() as i32 };
+    match cap {
+        // Square caps were folded into the endpoints before offsetting.
+        LineCapStyle::None | LineCapStyle::Square => {}
+        LineCapStyle::Round => {
+            // Half circle from +n through `out` to -n, fanned around the endpoint.
+            let segments = ((half_w * 0.5).ceil() as usize + 2).min(MAX_ROUND_CAP_SEGMENTS);
+            let center = verts.len() as u16;
+            verts.push(to_vertex(p.0, p.1));
+            let mut prev = left;
+            for k in 1..segments {
+                let theta = core::f32::consts::PI * k as f32 / segments as f32;
+                let (s, c) = theta.sin_cos();
+                let idx = verts.len() as u16;
+                verts.push(to_vertex(
+                    p.0 + (n.0 * c + out.0 * s) * half_w,
+                    p.1 + (n.1 * c + out.1 * s) * half_w,
+                ));
+                indices.extend_from_slice(&[center, prev, idx]);
+                prev = idx;
+            }
+            indices.extend_from_slice(&[center, prev, right]);
+        }
+    }
+}
+
 fn normalize_vec(v: (f32, f32)) -> (f32, f32) {
     let len = (v.0 * v.0 + v.1 * v.1).sqrt();
     if len <= 0.0001 {
@@ -1236,6 +1654,42 @@ fn triangulate_convex_fan(base: usize, ring_len: usize, out_indices: &mut Vec<u1
     }
 }
 
+/// Force every triangle to positive signed area (CCW in `polygon_area_signed` terms).
+///
+/// The solid rasterizer doesn't care about winding today, but backface culling and
+/// coverage-based AA will, so fill meshes guarantee a single winding.
+/// Returns how many triangles had to be flipped. Degenerate triangles are left alone.
+fn enforce_ccw_triangles(verts: &[Vertex2], indices: &mut [u16]) -> u32 {
+    let mut flipped = 0u32;
+    for tri in indices.chunks_exact_mut(3) {
+        let (Some(a), Some(b), Some(c)) = (
+            verts.get(tri[0] as usize),
+            verts.get(tri[1] as usize),
+            verts.get(tri[2] as usize),
+        ) else {
+            continue;
+        };
+        let area2 = (b.x - a.x) as i64 * (c.y - a.y) as i64 - (b.y - a.y) as i64 * (c.x - a.x) as i64;
+        if area2 < 0 {
+            tri.swap(1, 2);
+            flipped += 1;
+        }
+    }
+    flipped
+}
+
+fn warn_winding_flips(shape_id: u32, stage: &str, flipped: u32) {
+    if flipped == 0 {
+        return;
+    }
+    if WINDING_FLIP_WARNINGS.fetch_add(1, Ordering::Relaxed) < MAX_WINDING_FLIP_WARNINGS {
+        runlog::warn_line(&format!(
+            "tess_winding_flipped shape={} stage={} tris={}",
+            shape_id, stage, flipped
+        ));
+    }
+}
+
 #[inline(always)]
 fn polygon_area_signed_f64(poly: &[Point]) -> f64 {
     let mut a = 0.0f64;
@@ -1291,6 +1745,7 @@ fn group_contours_more_correct(
         }
         // Use a point guaranteed to be inside the contour for containment tests.
         let p = sample_point_inside_contour(&contours[i]);
+        let area_i = polygon_area_abs(&contours[i]);
         let mut best: Option<usize> = None;
         let mut best_area = f32::INFINITY;
         for j in 0..contours.len() {
@@ -1300,8 +1755,9 @@ fn group_contours_more_correct(
                 return GroupContoursResult::CapTests;
             }
             if !bbox_contains(bbox[j], p) { continue; }
-            if !point_in_poly(p, &contours[j]) { continue; }
             let a = polygon_area_abs(&contours[j]);
+            if !can_parent(a, j, area_i, i) { continue; }
+            if !point_in_poly(p, &contours[j]) { continue; }
             if a < best_area {
                 best_area = a;
                 best = Some(j);
@@ -1376,6 +1832,7 @@ fn group_contours_fast_parent_depth(
             return GroupContoursResult::Timeout;
         }
         let p = sample_point_inside_contour(&contours[i]);
+        let area_i = polygon_area_abs(&contours[i]);
         let mut best: Option<usize> = None;
         let mut best_area = f32::INFINITY;
         for j in 0..contours.len() {
@@ -1385,8 +1842,9 @@ fn group_contours_fast_parent_depth(
                 return GroupContoursResult::CapTests;
             }
             if !bbox_contains(bbox[j], p) { continue; }
-            if !point_in_poly(p, &contours[j]) { continue; }
             let a = polygon_area_abs(&contours[j]);
+            if !can_parent(a, j, area_i, i) { continue; }
+            if !point_in_poly(p, &contours[j]) { continue; }
             if a < best_area {
                 best_area = a;
                 best = Some(j);
@@ -1433,6 +1891,14 @@ fn group_contours_fast_parent_depth(
     GroupContoursResult::Groups(groups)
 }
 
+/// Whether contour `j` (area `area_j`) may be the parent of contour `i`.
+///
+/// Parents must be strictly larger (ties broken by index): overlapping contours can each hold
+/// the other's sample point, and letting them claim each other makes the parent chains cyclic.
+fn can_parent(area_j: f32, j: usize, area_i: f32, i: usize) -> bool {
+    area_j > area_i || (area_j == area_i && j > i)
+}
+
 // Trivial grouping: pick the largest area contour as outer and treat the rest as holes.
 fn group_contours_trivial(contours: &[Vec<(f32, f32)>]) -> Vec<ContourGroup> {
     if contours.is_empty() {
@@ -1556,3 +2022,376 @@ fn append_contour_vertices(out_verts: &mut Vec<Vertex2>, contour: &[Point]) -> u
     }
     n
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::device::mem::MemDevice;
+    use crate::render::device::RenderDevice;
+    use ruffle_core::swf::{Color, Fixed8, Point as SwfPoint, Rectangle, Twips};
+
+    const GOLDEN_SIZE: usize = 64;
+    /// Pixels a raster may differ from its reference by: earcut is free to pick other diagonals,
+    /// and pixel centers that land exactly on a shared edge can flip.
+    const GOLDEN_TOLERANCE_PX: usize = 16;
+
+    fn px(v: f32) -> Twips {
+        Twips::new((v * 20.0).round() as i32)
+    }
+
+    fn fill_path<'a>(style: &'a FillStyle, contours: &[Vec<Point>], rule: FillRule) -> DrawPath<'a> {
+        let mut commands = Vec::new();
+        for contour in contours {
+            for (i, &(x, y)) in contour.iter().enumerate() {
+                let p = SwfPoint { x: px(x), y: px(y) };
+                commands.push(if i == 0 { DrawCommand::MoveTo(p) } else { DrawCommand::LineTo(p) });
+            }
+            let (x, y) = contour[0];
+            commands.push(DrawCommand::LineTo(SwfPoint { x: px(x), y: px(y) }));
+        }
+        DrawPath::Fill { style, commands, winding_rule: rule }
+    }
+
+    fn shape<'a>(paths: Vec<DrawPath<'a>>) -> DistilledShape<'a> {
+        let bounds = Rectangle { x_min: Twips::ZERO, x_max: px(64.0), y_min: Twips::ZERO, y_max: px(64.0) };
+        DistilledShape { paths, shape_bounds: bounds, edge_bounds: bounds, id: 1 }
+    }
+
+    fn tri_area2(verts: &[Vertex2], tri: &[u16]) -> i64 {
+        let (a, b, c) = (verts[tri[0] as usize], verts[tri[1] as usize], verts[tri[2] as usize]);
+        (b.x - a.x) as i64 * (c.y - a.y) as i64 - (b.y - a.y) as i64 * (c.x - a.x) as i64
+    }
+
+    fn xy(verts: &[Vertex2]) -> Vec<(i32, i32)> {
+        verts.iter().map(|v| (v.x, v.y)).collect()
+    }
+
+    /// A "C" opening to the right: concave, so it goes through earcut rather than the fan.
+    fn c_ring() -> Vec<Point> {
+        vec![(0.0, 0.0), (30.0, 0.0), (30.0, 10.0), (10.0, 10.0), (10.0, 20.0), (30.0, 20.0), (30.0, 30.0), (0.0, 30.0)]
+    }
+
+    fn square(x: f32, y: f32, size: f32) -> Vec<Point> {
+        vec![(x, y), (x + size, y), (x + size, y + size), (x, y + size)]
+    }
+
+    #[test]
+    fn earcut_emits_ccw_triangles_for_ccw_rings() {
+        let groups = [
+            ContourGroup { outer: c_ring(), holes: Vec::new() },
+            ContourGroup { outer: square(0.0, 0.0, 40.0), holes: vec![square(10.0, 10.0, 20.0)] },
+        ];
+        for mut group in groups {
+            orient_group_winding(&mut group);
+            assert!(polygon_area_signed(&group.outer) > 0.0);
+
+            let mut coords = Vec::new();
+            let mut verts = Vec::new();
+            let mut hole_starts = Vec::new();
+            append_contour(&mut coords, &mut verts, &group.outer);
+            for hole in &group.holes {
+                hole_starts.push(verts.len());
+                append_contour(&mut coords, &mut verts, hole);
+            }
+            let mut indices: Vec<u16> = earcut(&coords, &hole_starts, 2).unwrap().into_iter().map(|i| i as u16).collect();
+            assert!(!indices.is_empty());
+            for tri in indices.chunks_exact(3) {
+                assert!(tri_area2(&verts, tri) > 0, "earcut emitted a CW triangle {:?}", tri);
+            }
+            assert_eq!(enforce_ccw_triangles(&verts, &mut indices), 0);
+        }
+    }
+
+    #[test]
+    fn tessellate_fills_outputs_ccw_for_either_input_winding() {
+        let style = FillStyle::Color(Color { r: 255, g: 0, b: 0, a: 255 });
+        let mut reversed = c_ring();
+        reversed.reverse();
+        for ring in [c_ring(), reversed] {
+            let shape = shape(vec![fill_path(&style, &[ring], FillRule::NonZero)]);
+            let out = tessellate_fills(&shape, 1, 0.5).unwrap();
+            let mesh = &out.fills[0];
+            assert!(!mesh.indices.is_empty());
+            for tri in mesh.indices.chunks_exact(3) {
+                assert!(tri_area2(&mesh.verts, tri) > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn square_caps_extend_horizontal_segment_by_half_width() {
+        let points = [(10.0, 20.0), (40.0, 20.0)];
+        let expected = [(7, 23), (7, 17), (43, 23), (43, 17)];
+        // Miter joins offset per point; bevel joins go through the per-segment quads. Both
+        // lay the strip out as start +n, start -n, end +n, end -n for a single segment.
+        for join in [LineJoinStyle::Miter(Fixed8::from_f32(3.0)), LineJoinStyle::Bevel] {
+            let caps = (LineCapStyle::Square, LineCapStyle::Square);
+            let mesh = build_stroke_mesh(&points, 3.0, join, false, caps).unwrap();
+            assert_eq!(xy(&mesh.verts), expected);
+            assert_eq!(mesh.indices.len(), 6);
+        }
+
+        let mesh = build_stroke_mesh(&points, 3.0, LineJoinStyle::Bevel, false, (LineCapStyle::None, LineCapStyle::Square)).unwrap();
+        assert_eq!(xy(&mesh.verts[..4]), [(10, 23), (10, 17), (43, 23), (43, 17)]);
+    }
+
+    /// Rasterize a fill mesh in white on black as `#`/`.` rows.
+    fn rasterize(verts: &[Vertex2], indices: &[u16]) -> String {
+        let mut device = MemDevice::new(GOLDEN_SIZE as u32, GOLDEN_SIZE as u32);
+        device.fill_tris_solid(verts, indices, 0, 0, 255, 255, 255);
+        let mut out = String::with_capacity(GOLDEN_SIZE * (GOLDEN_SIZE + 1));
+        for row in device.pixels().chunks_exact(GOLDEN_SIZE * 4) {
+            out.extend(row.chunks_exact(4).map(|px| if px[0] == 0 { '.' } else { '#' }));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Compare against `testdata/tess/<name>.txt`; `BLESS_GOLDEN=1` rewrites the reference instead.
+    fn assert_golden(name: &str, actual: &str) {
+        let path = format!("{}/testdata/tess/{}.txt", env!("CARGO_MANIFEST_DIR"), name);
+        if std::env::var_os("BLESS_GOLDEN").is_some() {
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("{}: {} (run with BLESS_GOLDEN=1 to create it)", path, e));
+        let expected = expected.replace("\r\n", "\n");
+        assert_eq!(expected.len(), actual.len(), "{}: reference has a different size", name);
+        let diff = expected.bytes().zip(actual.bytes()).filter(|(a, b)| a != b).count();
+        assert!(diff <= GOLDEN_TOLERANCE_PX, "{}: {} pixels differ from the reference\n{}", name, diff, actual);
+    }
+
+    /// One solid fill path through `tessellate_fills`, rasterized.
+    fn tessellate_raster(contours: &[Vec<Point>], rule: FillRule) -> (TessOutput, String) {
+        let style = FillStyle::Color(Color { r: 255, g: 255, b: 255, a: 255 });
+        let shape = shape(vec![fill_path(&style, contours, rule)]);
+        let out = tessellate_fills(&shape, 1, 0.5).unwrap();
+        assert!(!out.any_failed);
+        assert_eq!(out.fills.len(), 1);
+        let raster = rasterize(&out.fills[0].verts, &out.fills[0].indices);
+        (out, raster)
+    }
+
+    /// The per-group half of `tessellate_fills`, for driving a grouping strategy directly.
+    fn triangulate_groups(groups: Vec<ContourGroup>) -> (Vec<Vertex2>, Vec<u16>) {
+        let mut verts = Vec::new();
+        let mut indices = Vec::new();
+        for mut group in groups {
+            orient_group_winding(&mut group);
+            let base = verts.len();
+            if group.holes.is_empty() && is_convex_ring(&group.outer, CONVEX_FAN_MAX_OUTER_POINTS) {
+                let ring_len = append_contour_vertices(&mut verts, &group.outer);
+                triangulate_convex_fan(base, ring_len, &mut indices);
+                continue;
+            }
+            let mut coords = Vec::new();
+            let mut hole_starts = Vec::new();
+            append_contour(&mut coords, &mut verts, &sanitize_ring_for_earcut(&group.outer));
+            for hole in &group.holes {
+                hole_starts.push(verts.len() - base);
+                append_contour(&mut coords, &mut verts, &sanitize_ring_for_earcut(hole));
+            }
+            indices.extend(earcut(&coords, &hole_starts, 2).unwrap().into_iter().map(|i| (base + i) as u16));
+        }
+        (verts, indices)
+    }
+
+    /// Outer square, a hole in it and an island inside the hole, all wound the same way.
+    fn nested_contours() -> Vec<Vec<Point>> {
+        vec![square(4.0, 4.0, 56.0), square(16.0, 16.0, 32.0), square(26.0, 26.0, 12.0)]
+    }
+
+    fn star(cx: f32, cy: f32, outer_r: f32, inner_r: f32) -> Vec<Point> {
+        (0..10)
+            .map(|i| {
+                let r = if i % 2 == 0 { outer_r } else { inner_r };
+                let a = core::f32::consts::PI * i as f32 / 5.0 - core::f32::consts::FRAC_PI_2;
+                (cx + r * a.cos(), cy + r * a.sin())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn golden_square_with_hole() {
+        let (out, raster) = tessellate_raster(&[square(8.0, 8.0, 48.0), square(24.0, 24.0, 16.0)], FillRule::EvenOdd);
+        assert_eq!(out.group_used_more_correct, 1);
+        assert_golden("square_with_hole", &raster);
+    }
+
+    #[test]
+    fn golden_star() {
+        let (out, raster) = tessellate_raster(&[star(32.0, 33.0, 29.0, 12.0)], FillRule::NonZero);
+        assert_eq!(out.group_used_more_correct, 1);
+        assert_golden("star", &raster);
+    }
+
+    #[test]
+    fn golden_concave_polygon() {
+        let ring: Vec<Point> = c_ring().into_iter().map(|(x, y)| (x * 1.8 + 5.0, y * 1.8 + 5.0)).collect();
+        let (out, raster) = tessellate_raster(&[ring], FillRule::NonZero);
+        assert_eq!(out.group_used_more_correct, 1);
+        assert_golden("concave", &raster);
+    }
+
+    #[test]
+    fn golden_nested_squares_evenodd_vs_nonzero() {
+        let contours = [square(6.0, 6.0, 52.0), square(22.0, 22.0, 20.0)];
+        let (_, evenodd) = tessellate_raster(&contours, FillRule::EvenOdd);
+        let (_, nonzero) = tessellate_raster(&contours, FillRule::NonZero);
+        // Same-direction nesting: a hole under even-odd, winding 2 (filled) under non-zero.
+        let center = 32 * (GOLDEN_SIZE + 1) + 32;
+        assert_eq!(evenodd.as_bytes()[center], b'.');
+        assert_eq!(nonzero.as_bytes()[center], b'#');
+        assert_golden("nested_evenodd", &evenodd);
+        assert_golden("nested_nonzero", &nonzero);
+    }
+
+    #[test]
+    fn golden_grouping_more_correct() {
+        let start = Instant::now();
+        let GroupContoursResult::Groups(groups) =
+            group_contours_more_correct(&nested_contours(), FillRule::EvenOdd, &start, FILL_PATH_BUDGET_MS)
+        else {
+            panic!("more_correct grouping gave up");
+        };
+        assert_eq!(groups.len(), 2);
+        let (verts, indices) = triangulate_groups(groups);
+        assert_golden("grouping_more_correct", &rasterize(&verts, &indices));
+    }
+
+    #[test]
+    fn golden_grouping_fast() {
+        let start = Instant::now();
+        let GroupContoursResult::Groups(groups) =
+            group_contours_fast_parent_depth(&nested_contours(), FillRule::EvenOdd, &start, FILL_PATH_BUDGET_MS)
+        else {
+            panic!("fast grouping gave up");
+        };
+        assert_eq!(groups.len(), 2);
+        let (verts, indices) = triangulate_groups(groups);
+        assert_golden("grouping_fast", &rasterize(&verts, &indices));
+    }
+
+    #[test]
+    fn golden_grouping_trivial() {
+        // Trivial grouping makes every smaller contour a hole of the largest, so feed it
+        // disjoint holes (an island would come out as a hole in the hole).
+        let contours = [square(4.0, 4.0, 56.0), square(10.0, 10.0, 18.0), square(36.0, 30.0, 18.0)];
+        let groups = group_contours_trivial(&contours);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].holes.len(), 2);
+        let (verts, indices) = triangulate_groups(groups);
+        assert_golden("grouping_trivial", &rasterize(&verts, &indices));
+    }
+
+    #[test]
+    fn overlapping_contours_group_without_parent_cycles() {
+        // Each square holds the other's sample point; identical squares tie on area.
+        for contours in [
+            vec![square(0.0, 0.0, 20.0), square(5.0, 5.0, 20.0)],
+            vec![square(0.0, 0.0, 20.0), square(0.0, 0.0, 20.0)],
+        ] {
+            for rule in [FillRule::EvenOdd, FillRule::NonZero] {
+                let start = Instant::now();
+                assert!(matches!(group_contours_fast_parent_depth(&contours, rule, &start, 1000), GroupContoursResult::Groups(_)));
+                assert!(matches!(group_contours_more_correct(&contours, rule, &start, 1000), GroupContoursResult::Groups(_)));
+            }
+        }
+    }
+
+    /// xorshift64*, so failures reproduce from the printed seed.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+        }
+
+        fn range(&mut self, lo: f32, hi: f32) -> f32 {
+            lo + (self.next() >> 40) as f32 / (1u64 << 24) as f32 * (hi - lo)
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+    }
+
+    /// Points at increasing angles around a center; `jitter` = 0 gives a convex ellipse,
+    /// anything larger a (usually concave) star-shaped ring.
+    fn radial_ring(rng: &mut Rng, n: usize, jitter: f32) -> Vec<Point> {
+        let (cx, cy) = (rng.range(-200.0, 200.0), rng.range(-200.0, 200.0));
+        let (rx, ry) = (rng.range(4.0, 120.0), rng.range(4.0, 120.0));
+        (0..n)
+            .map(|i| {
+                let a = core::f32::consts::TAU * i as f32 / n as f32;
+                let k = 1.0 - rng.range(0.0, jitter);
+                (cx + rx * k * a.cos(), cy + ry * k * a.sin())
+            })
+            .collect()
+    }
+
+    fn check_output(seed: u64, out: &TessOutput) {
+        for mesh in &out.fills {
+            assert_eq!(mesh.indices.len() % 3, 0, "seed {:#x}", seed);
+            assert!(mesh.indices.iter().all(|&i| (i as usize) < mesh.verts.len()), "seed {:#x}", seed);
+        }
+    }
+
+    #[test]
+    fn random_contours_never_break_tessellate_fills() {
+        let style = FillStyle::Color(Color { r: 0, g: 0, b: 255, a: 255 });
+        let mut fan_meshes = 0;
+        let mut earcut_meshes = 0;
+        for case in 0..600u64 {
+            let seed = 0x9E37_79B9_7F4A_7C15 ^ case.wrapping_mul(0xD1B5_4A32_D192_ED03);
+            let mut rng = Rng(seed | 1);
+            let rule = if rng.below(2) == 0 { FillRule::EvenOdd } else { FillRule::NonZero };
+            let contours: Vec<Vec<Point>> = match case % 4 {
+                // Single convex ring: the convex-fan path.
+                0 => {
+                    let n = 3 + rng.below(60);
+                    let ring = radial_ring(&mut rng, n, 0.0);
+                    assert!(is_convex_ring(&ring, CONVEX_FAN_MAX_OUTER_POINTS));
+                    vec![ring]
+                }
+                // Single concave ring: earcut.
+                1 => {
+                    let n = 6 + rng.below(120);
+                    vec![radial_ring(&mut rng, n, 0.7)]
+                }
+                // Many overlapping rings, sometimes past the more_correct limits.
+                2 => (0..1 + rng.below(24)).map(|_| {
+                    let n = 3 + rng.below(80);
+                    let jitter = rng.range(0.0, 0.8);
+                    radial_ring(&mut rng, n, jitter)
+                }).collect(),
+                // Raw noise: self-intersecting, duplicate and collinear points, oversized rings.
+                _ => (0..1 + rng.below(4)).map(|_| {
+                    let n = if rng.below(8) == 0 { 3000 + rng.below(2000) } else { 1 + rng.below(40) };
+                    let grid = rng.range(1.0, 50.0);
+                    (0..n).map(|_| ((rng.range(-5.0, 5.0) * grid).round(), (rng.range(-5.0, 5.0) * grid).round())).collect()
+                }).collect(),
+            };
+            let contours: Vec<Vec<Point>> = contours.into_iter().filter(|c| !c.is_empty()).collect();
+            if contours.is_empty() {
+                continue;
+            }
+            let shape = shape(vec![fill_path(&style, &contours, rule)]);
+            let Ok(out) = tessellate_fills(&shape, case as u32, 0.5) else { continue; };
+            check_output(seed, &out);
+            if case % 4 == 0 {
+                // A fan shares the first vertex between all triangles.
+                let mesh = &out.fills[0];
+                assert!(mesh.indices.chunks_exact(3).all(|tri| tri[0] == 0), "seed {:#x}", seed);
+                fan_meshes += 1;
+            } else if case % 4 == 1 {
+                earcut_meshes += 1;
+            }
+        }
+        assert!(fan_meshes > 100 && earcut_meshes > 100, "fan={} earcut={}", fan_meshes, earcut_meshes);
+    }
+}
@@ -30,7 +30,7 @@ use ruffle_render::backend::{
     RenderBackend, ViewportDimensions, Context3D, Context3DProfile,
     ShapeHandle, ShapeHandleImpl, PixelBenderOutput, PixelBenderTarget, BitmapCacheEntry,
 };
-use ruffle_render::bitmap::{Bitmap, BitmapHandle, SyncHandle, BitmapSource, PixelRegion, RgbaBufRead, BitmapHandleImpl};
+use ruffle_render::bitmap::{Bitmap, BitmapFormat, BitmapHandle, SyncHandle, BitmapSource, PixelRegion, RgbaBufRead, BitmapHandleImpl};
 use ruffle_render::commands::{CommandList, Command};
 use ruffle_render::error::Error as RenderError;
 use ruffle_render::quality::StageQuality;
@@ -38,14 +38,27 @@ use ruffle_render::shape_utils::{DistilledShape, DrawPath};
 use ruffle_render::pixel_bender::{PixelBenderShader, PixelBenderShaderHandle};
 use ruffle_render::pixel_bender_support::PixelBenderShaderArgument;
 
-use crate::render::{ColorTransform, FramePacket, Matrix2D, RenderCmd, RectI, SharedCaches, TexUvRect};
+use crate::render::{ColorTransform, DrawBlend, FramePacket, Matrix2D, RenderCmd, RectI, SharedCaches, TexUvRect};
 use crate::render::cache::shapes::{FillMesh, FillPaint, Vertex2};
-use crate::render::cache::bitmaps::BitmapSurface;
+use crate::render::cache::bitmaps::{AlphaMode, BitmapSurface};
+use crate::render::cache::persist;
+use crate::render::executor::fallback_color;
 use ruffle_core::swf::ColorTransform as SwfColorTransform;
+use ruffle_core::swf::BlendMode as SwfBlendMode;
 
 // Step 2A tessellator lives next to this backend inside ruffle_adapter/.
 use super::tessellate;
+use super::tess_worker::{OwnedShape, TessJob, TessWorker};
+#[cfg(feature = "net")]
+use super::fetch;
+#[cfg(feature = "net")]
+use super::socket;
+#[cfg(feature = "storage")]
+use super::storage;
 use crate::runlog;
+use crate::util::config;
+use crate::util::swf_tags::LoadProgress;
+use crate::util::text::trim_to;
 type ShapeKey = usize;
 
 fn shape_handle_from_impl<T: ShapeHandleImpl + 'static>(handle: Arc<T>) -> ShapeHandle {
@@ -56,7 +69,59 @@ fn shape_handle_from_impl<T: ShapeHandleImpl + 'static>(handle: Arc<T>) -> Shape
 const MAX_TRIS_PER_FRAME: u32 = 8000;
 const MAX_UNSUPPORTED_FILL_WARNINGS: u32 = 8;
 const SHAPE_WATCHDOG_MS: u64 = 15;
+/// Shapes drawn in this many consecutive submitted frames get pinned against eviction.
+const AUTO_PIN_FRAMES: u32 = 30;
+/// Auto-pinning stops once pinned meshes reach this fraction (1/N) of the shape budget.
+const AUTO_PIN_BUDGET_DIVISOR: usize = 4;
+/// Persisted shape caches live per build (tessellator changes invalidate them), per movie hash.
+const SHAPE_PERSIST_ROOT: &str = "sdmc:/flash/_runs/_shape_cache";
+/// Ignore keyboard re-opens this soon after the last open/close (chatty content spams them).
+const KEYBOARD_DEBOUNCE_MS: u128 = 250;
+/// `getURL` calls kept between engine ticks; a script spamming navigations can't grow the queue.
+#[cfg(feature = "net")]
+const MAX_PENDING_NAVIGATIONS: usize = 16;
+/// AVM `trace`/warning lines written to the run log per frame; the rest are only counted.
+const MAX_AVM_LOGS_PER_FRAME: u32 = 16;
 static UNSUPPORTED_FILL_DRAW_WARNINGS: AtomicU32 = AtomicU32::new(0);
+static BITMAP_FILL_MISS_WARNINGS: AtomicU32 = AtomicU32::new(0);
+static TESS_QUEUE_WARNINGS: AtomicU32 = AtomicU32::new(0);
+static BITMAP_STRAIGHT_ALPHA_LOGS: AtomicU32 = AtomicU32::new(0);
+static UNSUPPORTED_BLEND_WARNINGS: AtomicU32 = AtomicU32::new(0);
+
+/// One entry of a `CommandList` with nested `Command::Blend` lists spliced inline.
+enum FlatCommand<'a> {
+    Draw(&'a Command),
+    PushBlend(DrawBlend),
+    PopBlend,
+}
+
+/// Splice nested blend groups into `out`, bracketed by push/pop markers.
+fn flatten_commands<'a>(commands: &'a [Command], out: &mut Vec<FlatCommand<'a>>) {
+    for cmd in commands {
+        if let Command::Blend(inner, mode) = cmd {
+            out.push(FlatCommand::PushBlend(to_draw_blend(*mode)));
+            flatten_commands(&inner.commands, out);
+            out.push(FlatCommand::PopBlend);
+        } else {
+            out.push(FlatCommand::Draw(cmd));
+        }
+    }
+}
+
+/// Unsupported modes draw as `Normal` (with a warning).
+fn to_draw_blend(mode: SwfBlendMode) -> DrawBlend {
+    match mode {
+        SwfBlendMode::Normal | SwfBlendMode::Layer => DrawBlend::Normal,
+        SwfBlendMode::Add => DrawBlend::Add,
+        SwfBlendMode::Multiply => DrawBlend::Multiply,
+        other => {
+            if UNSUPPORTED_BLEND_WARNINGS.fetch_add(1, Ordering::Relaxed) < 4 {
+                runlog::warn_line(&format!("blend_mode_unsupported mode={:?}; drawing normal", other));
+            }
+            DrawBlend::Normal
+        }
+    }
+}
 
 fn to_color_transform(ct: SwfColorTransform) -> Option<ColorTransform> {
     if ct == SwfColorTransform::IDENTITY {
@@ -75,17 +140,6 @@ fn to_color_transform(ct: SwfColorTransform) -> Option<ColorTransform> {
     })
 }
 
-fn debug_color_from_key(mut k: u64) -> (u8, u8, u8) {
-    k = k.wrapping_mul(0x9E3779B185EBCA87);
-    k ^= k >> 33;
-    k = k.wrapping_mul(0xC2B2AE3D27D4EB4F);
-    k ^= k >> 29;
-    let r = (k & 0xFF) as u8;
-    let g = ((k >> 8) & 0xFF) as u8;
-    let b = ((k >> 16) & 0xFF) as u8;
-    (r, g, b)
-}
-
 fn rect_aabb_transformed(rect: RectI, transform: Matrix2D) -> RectI {
     let x0 = rect.x as f32;
     let y0 = rect.y as f32;
@@ -129,13 +183,117 @@ fn is_text_shape(shape: &DistilledShape<'_>) -> bool {
     shape.id == 0 && shape.paths.iter().all(|p| matches!(p, DrawPath::Fill { .. }))
 }
 
+/// Bitmap fills resolve their handles through the `BitmapSource`, which only exists during
+/// `register_shape`, so those shapes always tessellate inline.
+fn has_bitmap_fills(shape: &DistilledShape<'_>) -> bool {
+    shape
+        .paths
+        .iter()
+        .any(|p| matches!(p, DrawPath::Fill { style: ruffle_core::swf::FillStyle::Bitmap { .. }, .. }))
+}
+
+/// Worker-thread half of `register_shape`: tessellate a queued shape and swap its meshes in
+/// for the placeholder.
+///
+/// The shape cache lock is only held for the final insert, never while tessellating, so the
+/// main thread keeps drawing placeholders in the meantime.
+fn run_tess_job(job: TessJob, caches: &SharedCaches, shared: &Mutex<SharedState>) {
+    let TessJob { key, id, bounds, content_hash, tol_px, shape } = job;
+    let shape = shape.as_distilled();
+
+    let fills_start = Instant::now();
+    let (fills, fill_failed, fill_partial, group_used_more_correct, group_used_fast, group_used_trivial, unsupported_fill_paints) =
+        match tessellate::tessellate_fills(&shape, id, tol_px) {
+            Ok(res) => (
+                res.fills,
+                false,
+                res.any_failed,
+                res.group_used_more_correct,
+                res.group_used_fast,
+                res.group_used_trivial,
+                res.unsupported_fill_paints,
+            ),
+            Err(tessellate::TessError::NoContours) => (Vec::new(), false, false, 0, 0, 0, 0),
+            Err(_) => (Vec::new(), true, false, 0, 0, 0, 0),
+        };
+    let fills_ms = fills_start.elapsed().as_millis() as u64;
+
+    let strokes_start = Instant::now();
+    let (strokes, stroke_failed, stroke_partial) = if shape.paths.iter().any(|path| matches!(path, DrawPath::Stroke { .. })) {
+        match tessellate::tessellate_strokes(&shape, id, tol_px) {
+            Ok(res) => (res.strokes, false, res.any_failed),
+            Err(tessellate::TessError::NoContours) => (Vec::new(), false, false),
+            Err(_) => (Vec::new(), true, false),
+        }
+    } else {
+        (Vec::new(), false, false)
+    };
+    let strokes_ms = strokes_start.elapsed().as_millis() as u64;
+
+    {
+        let mut shapes_cache = caches.shapes.lock().unwrap();
+        if !shapes_cache.take_pending(key, id) {
+            if runlog::is_verbose() {
+                runlog::log_line(&format!("tess_worker stale id={} dropped", id));
+            }
+            return;
+        }
+        shapes_cache.insert_meshes(
+            key,
+            id,
+            bounds,
+            fills,
+            fill_failed,
+            fill_partial,
+            strokes,
+            stroke_failed,
+            stroke_partial,
+            false,
+        );
+        if let Some(hash) = content_hash.filter(|_| !fill_failed && !stroke_failed) {
+            shapes_cache.index_content(key, hash);
+        }
+    }
+
+    if fill_failed || stroke_failed {
+        if runlog::is_verbose() {
+            runlog::warn_line(&format!(
+                "tess_worker fallback_bounds id={} fill_failed={} stroke_failed={}",
+                id, fill_failed, stroke_failed
+            ));
+        }
+    } else if runlog::is_verbose() {
+        runlog::log_line(&format!(
+            "tess_worker done id={} fills_ms={} strokes_ms={} fill_partial={} stroke_partial={}",
+            id, fills_ms, strokes_ms, fill_partial, stroke_partial
+        ));
+    }
+
+    let mut s = shared.lock().unwrap();
+    s.diagnostics.total_tess_ms_fills = s.diagnostics.total_tess_ms_fills.saturating_add(fills_ms);
+    s.diagnostics.total_tess_ms_strokes = s.diagnostics.total_tess_ms_strokes.saturating_add(strokes_ms);
+    s.diagnostics.max_tess_ms_single_shape = s.diagnostics.max_tess_ms_single_shape.max(fills_ms.saturating_add(strokes_ms));
+    s.diagnostics.total_group_more_correct = s.diagnostics.total_group_more_correct.saturating_add(group_used_more_correct);
+    s.diagnostics.total_group_fast = s.diagnostics.total_group_fast.saturating_add(group_used_fast);
+    s.diagnostics.total_group_trivial = s.diagnostics.total_group_trivial.saturating_add(group_used_trivial);
+    s.diagnostics.total_unsupported_fill_paints = s
+        .diagnostics
+        .total_unsupported_fill_paints
+        .saturating_add(unsupported_fill_paints);
+}
+
 fn bitmap_to_surface(bitmap: Bitmap) -> BitmapSurface {
-    // Ruffle's Bitmap is expected to carry uncompressed RGBA8 pixels.
+    // Ruffle's Bitmap is expected to carry uncompressed RGB8 or RGBA8 pixels.
     // If the layout ever changes, we fall back to a visible magenta pattern.
     // Recent Ruffle versions expose dimensions via methods.
     let width = bitmap.width();
     let height = bitmap.height();
-    let mut rgba: Vec<u8> = bitmap.data().to_vec();
+    let format = bitmap.format();
+    let mut rgba: Vec<u8> = if format == BitmapFormat::Rgb {
+        bitmap.data().chunks_exact(3).flat_map(|px| [px[0], px[1], px[2], 255]).collect()
+    } else {
+        bitmap.data().to_vec()
+    };
     let expected = (width as usize).saturating_mul(height as usize).saturating_mul(4);
     if rgba.len() != expected {
         rgba = vec![0u8; expected];
@@ -151,14 +309,65 @@ fn bitmap_to_surface(bitmap: Bitmap) -> BitmapSurface {
             }
         }
     }
+    // Ruffle hands RGBA over premultiplied. A channel above its alpha can't be premultiplied,
+    // so such data is treated as straight instead of being blended too bright.
     let mut is_opaque = true;
-    for alpha in rgba.iter().skip(3).step_by(4) {
-        if *alpha != 255 {
+    let mut premultiplied = format == BitmapFormat::Rgba;
+    for px in rgba.chunks_exact(4) {
+        if px[3] != 255 {
             is_opaque = false;
-            break;
+            if px[0] > px[3] || px[1] > px[3] || px[2] > px[3] {
+                premultiplied = false;
+                break;
+            }
+        }
+    }
+    if !premultiplied && format == BitmapFormat::Rgba && BITMAP_STRAIGHT_ALPHA_LOGS.fetch_add(1, Ordering::Relaxed) < MAX_UNSUPPORTED_FILL_WARNINGS {
+        runlog::log_line(&format!("bitmap {}x{} not premultiplied; blending as straight alpha", width, height));
+    }
+    let alpha_mode = if premultiplied { AlphaMode::Premultiplied } else { AlphaMode::Straight };
+    let mut surface = BitmapSurface {
+        width,
+        height,
+        rgba,
+        is_opaque,
+        alpha_mode,
+        source_width: width,
+        source_height: height,
+    };
+    surface.downscale_to(config::bitmap_max_dim());
+    if surface.is_downscaled() {
+        runlog::log_line(&format!(
+            "bitmap {}x{} downscaled to {}x{}",
+            width, height, surface.width, surface.height
+        ));
+    }
+    surface
+}
+
+/// The pixels of `region` as tightly packed RGBA8, or `None` if it's empty, falls outside
+/// `bitmap`, or the format isn't plain RGB/RGBA.
+fn region_rgba(bitmap: &Bitmap, region: &PixelRegion) -> Option<Vec<u8>> {
+    let (w, h) = (bitmap.width() as usize, bitmap.height() as usize);
+    if region.x_min >= region.x_max || region.y_min >= region.y_max || region.x_max as usize > w || region.y_max as usize > h {
+        return None;
+    }
+    let bpp = if bitmap.format() == BitmapFormat::Rgb { 3 } else { 4 };
+    let data = bitmap.data();
+    if data.len() != w * h * bpp {
+        return None;
+    }
+    let (x0, x1) = (region.x_min as usize, region.x_max as usize);
+    let mut out = Vec::with_capacity((x1 - x0) * (region.y_max - region.y_min) as usize * 4);
+    for y in region.y_min as usize..region.y_max as usize {
+        let row = &data[bpp * (y * w + x0)..bpp * (y * w + x1)];
+        if bpp == 3 {
+            out.extend(row.chunks_exact(3).flat_map(|px| [px[0], px[1], px[2], 255]));
+        } else {
+            out.extend_from_slice(row);
         }
     }
-    BitmapSurface { width, height, rgba, is_opaque }
+    Some(out)
 }
 
 #[cfg(feature = "net")]
@@ -171,6 +380,8 @@ struct Diagnostics {
     shapes_registered: u32,
     bitmaps_registered: u32,
     frames_submitted: u32,
+    /// Ticks whose render was dropped by the adaptive frame-skip.
+    frames_render_skipped: u32,
     last_cmds_total: u32,
     last_cmds_shapes: u32,
     last_cmds_bitmaps: u32,
@@ -189,6 +400,26 @@ struct Diagnostics {
     input_counter: u64,
 }
 
+/// Numeric snapshot of `Diagnostics` for `bridge_engine_get_diagnostics` (no strings).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DiagCounters {
+    pub movie_loaded: bool,
+    pub swf_version: u8,
+    pub shapes_registered: u32,
+    pub bitmaps_registered: u32,
+    pub frames_submitted: u32,
+    pub frames_render_skipped: u32,
+    pub last_cmds_total: u32,
+    pub last_cmds_shapes: u32,
+    pub last_cmds_bitmaps: u32,
+    pub last_cmds_other: u32,
+    pub last_tris: u32,
+    pub total_tess_ms_fills: u64,
+    pub total_tess_ms_strokes: u64,
+    pub max_tess_ms_single_shape: u64,
+    pub input_counter: u64,
+}
+
 struct SharedState {
     frame: FramePacket,
     submit_called: bool,
@@ -198,10 +429,43 @@ struct SharedState {
     wireframe_once: bool,
     wireframe_hold: bool,
     debug_affine_overlay: bool,
+    /// Outline every drawn shape's transformed bounds.
+    bounds_overlay: bool,
+    /// Tint drawn shapes by triangle count.
+    tri_heatmap: bool,
+    stats_overlay: bool,
+    /// Last viewport Ruffle asked for (physical pixels); mouse input must be in this space.
+    viewport_w: u32,
+    viewport_h: u32,
+    viewport_scale: f64,
+    fullscreen: bool,
+    /// One virtual keyboard session at a time; further opens are ignored until it closes.
+    keyboard_open: bool,
+    keyboard_changed_at: Option<Instant>,
+    /// In-process clipboard (the 3DS has no system one), so copy/paste works within a movie.
+    clipboard: String,
+    /// Stage quality from Ruffle; picks the flattening tolerance for new shapes.
+    quality: StageQuality,
+    /// Persisted shape cache file for the current movie (`shape_cache_persist=1` only).
+    shape_persist_path: Option<String>,
+    /// Root movie preload progress for the loading indicator.
+    load_progress: LoadProgress,
+    /// AVM log lines written this frame, and those dropped over `MAX_AVM_LOGS_PER_FRAME`.
+    avm_logs_this_frame: u32,
+    avm_logs_dropped: u32,
+    /// SharedObject folder for the current movie (`None` until a movie is loaded).
+    #[cfg(feature = "storage")]
+    storage_dir: Option<String>,
+    /// Root movie URL; relative fetches resolve against it.
+    #[cfg(feature = "net")]
+    base_url: Option<Url>,
+    /// `getURL` requests (url, target) waiting for the engine to hand them to C.
+    #[cfg(feature = "net")]
+    pending_navigations: Vec<(String, String)>,
 }
 
 impl SharedState {
-    fn new() -> Self {
+    fn new(viewport_w: u32, viewport_h: u32) -> Self {
         Self {
             frame: FramePacket::new(),
             submit_called: false,
@@ -211,6 +475,27 @@ impl SharedState {
             wireframe_once: false,
             wireframe_hold: false,
             debug_affine_overlay: false,
+            bounds_overlay: false,
+            tri_heatmap: false,
+            stats_overlay: false,
+            viewport_w: viewport_w.max(1),
+            viewport_h: viewport_h.max(1),
+            viewport_scale: 1.0,
+            fullscreen: false,
+            keyboard_open: false,
+            keyboard_changed_at: None,
+            clipboard: String::new(),
+            quality: StageQuality::High,
+            shape_persist_path: None,
+            load_progress: LoadProgress::default(),
+            avm_logs_this_frame: 0,
+            avm_logs_dropped: 0,
+            #[cfg(feature = "storage")]
+            storage_dir: None,
+            #[cfg(feature = "net")]
+            base_url: None,
+            #[cfg(feature = "net")]
+            pending_navigations: Vec::new(),
         }
     }
 }
@@ -223,17 +508,33 @@ pub struct ThreeDSBackend {
     next_shape_id: Arc<AtomicU32>,
     next_bitmap_id: Arc<AtomicU32>,
     caches: SharedCaches,
+    /// Background tessellation (`async_tessellation=1`); `None` means shapes tessellate inline.
+    tess_worker: Option<Arc<TessWorker>>,
 }
 
 impl ThreeDSBackend {
-    pub fn new(caches: SharedCaches) -> Self {
+    /// `viewport_w`/`viewport_h` are the launch stage size; Ruffle may change them later.
+    pub fn new(caches: SharedCaches, viewport_w: u32, viewport_h: u32) -> Self {
+        let shared = Arc::new(Mutex::new(SharedState::new(viewport_w, viewport_h)));
+        let tess_worker = if config::async_tessellation_enabled() {
+            let worker_caches = caches.clone();
+            let worker_shared = Arc::clone(&shared);
+            let worker = TessWorker::spawn(move |job| run_tess_job(job, &worker_caches, &worker_shared)).map(Arc::new);
+            if worker.is_none() {
+                runlog::warn_line("tess_worker spawn failed; tessellating inline");
+            }
+            worker
+        } else {
+            None
+        };
         Self {
             #[cfg(feature = "net")]
             tasks: Arc::new(Mutex::new(Vec::new())),
-            shared: Arc::new(Mutex::new(SharedState::new())),
+            shared,
             next_shape_id: Arc::new(AtomicU32::new(1)),
             next_bitmap_id: Arc::new(AtomicU32::new(1)),
             caches,
+            tess_worker,
         }
     }
 
@@ -254,6 +555,137 @@ impl ThreeDSBackend {
         s.diagnostics.swf_version = swf_version;
     }
 
+    /// Start tracking preload progress over the root movie's tag stream (`SwfMovie::data()`).
+    pub fn begin_load_progress(&self, movie_data: &[u8]) {
+        let progress = LoadProgress::new(movie_data);
+        self.shared.lock().unwrap().load_progress = progress;
+    }
+
+    /// Percent of the root movie the preloader has processed, if it can be tracked.
+    pub fn load_progress(&self) -> Option<u8> {
+        self.shared.lock().unwrap().load_progress.percent()
+    }
+
+    /// Point SharedObject storage at the folder for `root_path`'s SWF name.
+    #[cfg(feature = "storage")]
+    pub fn set_storage_movie(&self, root_path: &str) {
+        let dir = storage::movie_dir(root_path);
+        if dir.is_none() {
+            runlog::warn_line("storage: unusable swf name; SharedObjects disabled");
+        }
+        self.shared.lock().unwrap().storage_dir = dir;
+    }
+
+    #[cfg(feature = "storage")]
+    fn storage_dir(&self) -> Option<String> {
+        self.shared.lock().unwrap().storage_dir.clone()
+    }
+
+    /// Remember the root movie URL so relative `loadMovie`/`XML.load` paths resolve next to it.
+    #[cfg(feature = "net")]
+    pub fn set_base_url(&self, root_url: &str) {
+        let base = Url::parse(root_url).ok();
+        if base.is_none() {
+            runlog::warn_line(&format!("navigator: unparsable root url {}", root_url));
+        }
+        self.shared.lock().unwrap().base_url = base;
+    }
+
+    /// Take queued `getURL` requests; the engine forwards them outside the player lock.
+    #[cfg(feature = "net")]
+    pub fn take_navigations(&self) -> Vec<(String, String)> {
+        std::mem::take(&mut self.shared.lock().unwrap().pending_navigations)
+    }
+
+    /// Stage the shape meshes persisted for this movie (keyed by a hash of its bytes), so
+    /// registrations with matching content skip tessellation. No-op unless `shape_cache_persist=1`.
+    pub fn warm_shape_cache(&self, movie_bytes: &[u8]) {
+        if !config::shape_cache_persist_enabled() {
+            return;
+        }
+        let start = Instant::now();
+        let path = format!(
+            "{}/{}/{:016x}.bin",
+            SHAPE_PERSIST_ROOT,
+            runlog::build_id(),
+            persist::fnv1a(movie_bytes)
+        );
+        let loaded = std::fs::read(&path).ok().and_then(|bytes| persist::decode(&bytes, persist::config_salt()));
+        match loaded {
+            Some(shapes) => {
+                let count = shapes.len();
+                self.caches.shapes.lock().unwrap().load_warm(shapes);
+                runlog::log_important(&format!(
+                    "shape_cache_warm loaded={} ms={} path={}",
+                    count,
+                    start.elapsed().as_millis(),
+                    path
+                ));
+            }
+            None => runlog::log_important(&format!("shape_cache_warm none path={}", path)),
+        }
+        self.shared.lock().unwrap().shape_persist_path = Some(path);
+    }
+
+    /// Write the tessellated shapes back to the current movie's persist file (if any).
+    ///
+    /// Goes through a temp file and rename so an interrupted save can't leave a torn file.
+    pub fn save_shape_cache(&self) {
+        let Some(path) = self.shared.lock().unwrap().shape_persist_path.clone() else {
+            return;
+        };
+        let start = Instant::now();
+        let (bytes, count) = {
+            let shapes_cache = self.caches.shapes.lock().unwrap();
+            let (_, budget, _, _) = shapes_cache.mem_stats();
+            shapes_cache.export_persisted(persist::config_salt(), budget)
+        };
+        let dir = path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or(SHAPE_PERSIST_ROOT);
+        let tmp = format!("{}.tmp", path);
+        let ok = std::fs::create_dir_all(dir).is_ok()
+            && std::fs::write(&tmp, &bytes).is_ok()
+            && {
+                let _ = std::fs::remove_file(&path);
+                std::fs::rename(&tmp, &path).is_ok()
+            };
+        if ok {
+            runlog::log_important(&format!(
+                "shape_cache_save shapes={} bytes={} ms={}",
+                count,
+                bytes.len(),
+                start.elapsed().as_millis()
+            ));
+        } else {
+            let _ = std::fs::remove_file(&tmp);
+            runlog::warn_line(&format!("shape_cache_save failed {}", path));
+        }
+    }
+
+    /// Drop everything tied to the previous player before a reload: cached shapes/bitmaps,
+    /// the pending frame and diagnostics. Shape/bitmap ids keep counting, so handles from the
+    /// old player can never alias new entries (late tessellation results are dropped).
+    pub fn reset_for_reload(&self) {
+        self.caches.shapes.lock().unwrap().clear();
+        self.caches.bitmaps.lock().unwrap().clear();
+        let mut s = self.shared.lock().unwrap();
+        s.frame = FramePacket::new();
+        s.submit_called = false;
+        s.seen_real_draw = false;
+        s.diagnostics = Diagnostics::default();
+        s.quality = StageQuality::High;
+        s.keyboard_open = false;
+        s.keyboard_changed_at = None;
+        #[cfg(feature = "net")]
+        s.pending_navigations.clear();
+    }
+
+    /// Recover the shared locks after a caught panic so error mode can still render.
+    pub fn clear_poison(&self) {
+        self.shared.clear_poison();
+        self.caches.shapes.clear_poison();
+        self.caches.bitmaps.clear_poison();
+    }
+
     pub fn set_fatal_error(&self, msg: String) {
         let mut s = self.shared.lock().unwrap();
         s.diagnostics.last_fatal = Some(msg);
@@ -263,6 +695,19 @@ impl ThreeDSBackend {
         let mut s = self.shared.lock().unwrap();
         s.submit_called = false;
         s.diagnostics.last_warning = None;
+        if s.avm_logs_dropped > 0 {
+            runlog::log_line(&format!(
+                "AVM output capped: {} lines dropped (max {} per frame)",
+                s.avm_logs_dropped, MAX_AVM_LOGS_PER_FRAME
+            ));
+        }
+        s.avm_logs_this_frame = 0;
+        s.avm_logs_dropped = 0;
+    }
+
+    pub fn record_render_skip(&self) {
+        let mut s = self.shared.lock().unwrap();
+        s.diagnostics.frames_render_skipped = s.diagnostics.frames_render_skipped.saturating_add(1);
     }
 
     pub fn record_input(&self, text: String) {
@@ -313,6 +758,92 @@ impl ThreeDSBackend {
         s.debug_affine_overlay
     }
 
+    pub fn toggle_bounds_overlay(&self) -> bool {
+        let mut s = self.shared.lock().unwrap();
+        s.bounds_overlay = !s.bounds_overlay;
+        s.bounds_overlay
+    }
+
+    pub fn toggle_tri_heatmap(&self) -> bool {
+        let mut s = self.shared.lock().unwrap();
+        s.tri_heatmap = !s.tri_heatmap;
+        s.tri_heatmap
+    }
+
+    /// The shape/bitmap caches this backend registers into (shared with the renderer).
+    pub fn caches(&self) -> &SharedCaches {
+        &self.caches
+    }
+
+    /// Current player viewport `(width, height)` in physical pixels.
+    pub fn viewport_size(&self) -> (u32, u32) {
+        let s = self.shared.lock().unwrap();
+        (s.viewport_w, s.viewport_h)
+    }
+
+    /// True while content has a virtual keyboard session open.
+    pub fn virtual_keyboard_open(&self) -> bool {
+        let s = self.shared.lock().unwrap();
+        s.keyboard_open
+    }
+
+    /// The launcher dismissed the keyboard on its own (e.g. user pressed Cancel).
+    pub fn virtual_keyboard_dismissed(&self) {
+        let mut s = self.shared.lock().unwrap();
+        if s.keyboard_open {
+            s.keyboard_open = false;
+            s.keyboard_changed_at = Some(Instant::now());
+            runlog::log_line("ui keyboard dismissed");
+        }
+    }
+
+    pub fn toggle_stats_overlay(&self) -> bool {
+        let mut s = self.shared.lock().unwrap();
+        s.stats_overlay = !s.stats_overlay;
+        s.stats_overlay
+    }
+
+    pub fn stats_overlay_enabled(&self) -> bool {
+        let s = self.shared.lock().unwrap();
+        s.stats_overlay
+    }
+
+    /// Short multi-line text for the on-screen stats overlay.
+    ///
+    /// Keep this cheap: it is built every frame while the overlay is on.
+    pub fn stats_overlay_text(&self, fps_x10: u32) -> String {
+        let (cmds, tris) = {
+            let s = self.shared.lock().unwrap();
+            (s.diagnostics.last_cmds_total, s.diagnostics.last_tris)
+        };
+        let d = crate::render::executor::last_draw_stats();
+        format!(
+            "FPS {}.{} CMD {} TRI {}\nFILL {}/{} TEXT {}/{}\nSTRK {}/{} BMP {} RECT {}",
+            fps_x10 / 10,
+            fps_x10 % 10,
+            cmds,
+            tris,
+            d.fill_fallbacks,
+            d.fill_draws,
+            d.text_fallbacks,
+            d.text_draws,
+            d.stroke_fallbacks,
+            d.stroke_draws,
+            d.bitmap_draws,
+            d.rect_fastpath,
+        )
+    }
+
+    /// Returns the budget actually applied by the shape cache.
+    pub fn set_shape_cache_budget(&self, bytes: usize) -> usize {
+        self.caches.shapes.lock().unwrap().set_budget(bytes)
+    }
+
+    /// Returns the budget actually applied by the bitmap cache.
+    pub fn set_bitmap_cache_budget(&self, bytes: usize) -> usize {
+        self.caches.bitmaps.lock().unwrap().set_budget(bytes)
+    }
+
     fn shape_timeout_fallback(
         &mut self,
         key: ShapeKey,
@@ -337,6 +868,22 @@ impl ThreeDSBackend {
         shape_handle_from_impl(Arc::clone(handle_impl))
     }
 
+    /// Resolve bitmap fill character ids to bitmap cache keys.
+    ///
+    /// Asking the source for the handle registers the bitmap with us if it wasn't yet, so the
+    /// key is the same one `RenderBitmap` draws use.
+    fn resolve_bitmap_fills(&mut self, fills: &mut [FillMesh], bitmap_source: &dyn BitmapSource, shape_id: u32) {
+        for mesh in fills.iter_mut() {
+            let FillPaint::Bitmap { bitmap_id, bitmap_key, .. } = &mut mesh.paint else { continue; };
+            *bitmap_key = bitmap_source
+                .bitmap_handle(*bitmap_id, self)
+                .map(|handle| Arc::as_ptr(&handle.0) as *const () as usize);
+            if bitmap_key.is_none() && BITMAP_FILL_MISS_WARNINGS.fetch_add(1, Ordering::Relaxed) < MAX_UNSUPPORTED_FILL_WARNINGS {
+                runlog::warn_line(&format!("bitmap_fill unresolved shape={} bitmap_id={}", shape_id, bitmap_id));
+            }
+        }
+    }
+
 
     pub fn is_ready(&self) -> bool {
         let s = self.shared.lock().unwrap();
@@ -384,6 +931,7 @@ impl ThreeDSBackend {
             shapes_registered: u32,
             bitmaps_registered: u32,
             frames_submitted: u32,
+            frames_render_skipped: u32,
             last_cmds_total: u32,
             last_cmds_shapes: u32,
             last_cmds_bitmaps: u32,
@@ -408,6 +956,7 @@ impl ThreeDSBackend {
                 shapes_registered: s.diagnostics.shapes_registered,
                 bitmaps_registered: s.diagnostics.bitmaps_registered,
                 frames_submitted: s.diagnostics.frames_submitted,
+                frames_render_skipped: s.diagnostics.frames_render_skipped,
                 last_cmds_total: s.diagnostics.last_cmds_total,
                 last_cmds_shapes: s.diagnostics.last_cmds_shapes,
                 last_cmds_bitmaps: s.diagnostics.last_cmds_bitmaps,
@@ -429,14 +978,27 @@ impl ThreeDSBackend {
         let (fill_missing, fill_invalid, fill_bounds) = shapes_cache.stats();
         let (stroke_missing, stroke_invalid, stroke_bounds) = shapes_cache.stroke_stats();
         let (cache_used_bytes, cache_budget_bytes, cache_evicted_entries, cache_evicted_bytes) = shapes_cache.mem_stats();
+        let cache_dedup_hits = shapes_cache.dedup_hits();
+        let cache_tess_pending = shapes_cache.pending_count();
+        let (cache_pinned, cache_pinned_bytes) = shapes_cache.pinned_stats();
+        let (cache_warm_hits, cache_warm_left) = shapes_cache.warm_stats();
+        drop(shapes_cache);
+        let (bmp_surfaces, (bmp_used_bytes, bmp_budget_bytes, bmp_evicted_entries, bmp_evicted_bytes)) = {
+            let bitmaps_cache = self.caches.bitmaps.lock().unwrap();
+            (bitmaps_cache.len(), bitmaps_cache.mem_stats())
+        };
         let draw_stats = crate::render::executor::last_draw_stats();
         let runlog_info = runlog::snapshot_info();
 
         let mut out = String::new();
         let mode = if diag.seen_real_draw { "OK" } else { "LD" };
         out.push_str(&format!(
-            "mode={} swf_v={} frames_submitted={}\n",
-            mode, diag.swf_version, diag.frames_submitted
+            "mode={} swf_v={} frames_submitted={} render_skipped={} no_surface={}\n",
+            mode,
+            diag.swf_version,
+            diag.frames_submitted,
+            diag.frames_render_skipped,
+            crate::render::frames_no_surface()
         ));
         out.push_str(&format!(
             "registered shapes={} bitmaps={}\n",
@@ -473,17 +1035,33 @@ impl ThreeDSBackend {
             stroke_bounds
         ));
         out.push_str(&format!(
-            "shape_cache_mem used_kb={} budget_kb={} evicted_entries={} evicted_kb={}\n",
+            "shape_cache_mem used_kb={} budget_kb={} evicted_entries={} evicted_kb={} dedup_hits={} tess_pending={} pinned={} pinned_kb={}\n",
             cache_used_bytes / 1024,
             cache_budget_bytes / 1024,
             cache_evicted_entries,
-            cache_evicted_bytes / 1024
+            cache_evicted_bytes / 1024,
+            cache_dedup_hits,
+            cache_tess_pending,
+            cache_pinned,
+            cache_pinned_bytes / 1024
+        ));
+        if cache_warm_hits > 0 || cache_warm_left > 0 {
+            out.push_str(&format!("shape_cache_warm hits={} unclaimed={}\n", cache_warm_hits, cache_warm_left));
+        }
+        out.push_str(&format!(
+            "bitmap_cache_mem used_kb={} surfaces={} budget_kb={} evicted_entries={} evicted_kb={}\n",
+            bmp_used_bytes / 1024,
+            bmp_surfaces,
+            bmp_budget_bytes / 1024,
+            bmp_evicted_entries,
+            bmp_evicted_bytes / 1024
         ));
         out.push_str(&format!(
-            "draw_stats mesh_tris={} rect_fastpath={} bounds_fallbacks={}\n",
+            "draw_stats mesh_tris={} rect_fastpath={} bounds_fallbacks={} radial_fills={}\n",
             draw_stats.mesh_tris,
             draw_stats.rect_fastpath,
-            draw_stats.bounds_fallbacks
+            draw_stats.bounds_fallbacks,
+            draw_stats.radial_fills
         ));
 
         if let Some(info) = runlog_info {
@@ -508,6 +1086,148 @@ impl ThreeDSBackend {
 
         out
     }
+
+    /// Compact JSON form of the diagnostics, cache stats and last draw stats for tooling.
+    ///
+    /// Hand-written with a fixed schema; bump `DIAGNOSTICS_JSON_VERSION` when fields change.
+    pub fn diag_counters(&self) -> DiagCounters {
+        let s = self.shared.lock().unwrap();
+        let d = &s.diagnostics;
+        DiagCounters {
+            movie_loaded: d.movie_loaded,
+            swf_version: d.swf_version,
+            shapes_registered: d.shapes_registered,
+            bitmaps_registered: d.bitmaps_registered,
+            frames_submitted: d.frames_submitted,
+            frames_render_skipped: d.frames_render_skipped,
+            last_cmds_total: d.last_cmds_total,
+            last_cmds_shapes: d.last_cmds_shapes,
+            last_cmds_bitmaps: d.last_cmds_bitmaps,
+            last_cmds_other: d.last_cmds_other,
+            last_tris: d.last_tris,
+            total_tess_ms_fills: d.total_tess_ms_fills,
+            total_tess_ms_strokes: d.total_tess_ms_strokes,
+            max_tess_ms_single_shape: d.max_tess_ms_single_shape,
+            input_counter: d.input_counter,
+        }
+    }
+
+    pub fn diagnostics_json(&self, frame: u64) -> String {
+        let mut out = String::with_capacity(1024);
+        {
+            let s = self.shared.lock().unwrap();
+            let d = &s.diagnostics;
+            out.push_str(&format!(
+                "{{\"v\":{},\"frame\":{},\"movie\":{{\"loaded\":{},\"swf_version\":{},\"seen_real_draw\":{}}}",
+                DIAGNOSTICS_JSON_VERSION, frame, d.movie_loaded, d.swf_version, s.seen_real_draw
+            ));
+            out.push_str(&format!(
+                ",\"registered\":{{\"shapes\":{},\"bitmaps\":{}}},\"frames_submitted\":{},\"frames_render_skipped\":{}",
+                d.shapes_registered, d.bitmaps_registered, d.frames_submitted, d.frames_render_skipped
+            ));
+            out.push_str(&format!(
+                ",\"last_frame\":{{\"cmds_total\":{},\"cmds_shapes\":{},\"cmds_bitmaps\":{},\"cmds_other\":{},\"tris\":{}}}",
+                d.last_cmds_total, d.last_cmds_shapes, d.last_cmds_bitmaps, d.last_cmds_other, d.last_tris
+            ));
+            out.push_str(&format!(
+                ",\"tess\":{{\"fills_ms\":{},\"strokes_ms\":{},\"max_shape_ms\":{}}}",
+                d.total_tess_ms_fills, d.total_tess_ms_strokes, d.max_tess_ms_single_shape
+            ));
+            out.push_str(&format!(
+                ",\"grouping\":{{\"more_correct\":{},\"fast\":{},\"trivial\":{},\"unsupported_fills\":{}}}",
+                d.total_group_more_correct, d.total_group_fast, d.total_group_trivial, d.total_unsupported_fill_paints
+            ));
+            out.push_str(&format!(
+                ",\"input_counter\":{},\"last_input\":{},\"last_warning\":{},\"last_fatal\":{}",
+                d.input_counter,
+                json_opt_str(d.last_input.as_deref()),
+                json_opt_str(d.last_warning.as_deref()),
+                json_opt_str(d.last_fatal.as_deref())
+            ));
+        }
+        {
+            let shapes_cache = self.caches.shapes.lock().unwrap();
+            let (fill_missing, fill_invalid, fill_bounds) = shapes_cache.stats();
+            let (stroke_missing, stroke_invalid, stroke_bounds) = shapes_cache.stroke_stats();
+            let (used, budget, evicted_entries, evicted_bytes) = shapes_cache.mem_stats();
+            let (pinned, pinned_bytes) = shapes_cache.pinned_stats();
+            out.push_str(&format!(
+                ",\"shape_cache\":{{\"entries\":{},\"used_bytes\":{},\"budget_bytes\":{},\"evicted_entries\":{},\"evicted_bytes\":{},\
+                 \"pinned\":{},\"pinned_bytes\":{},\"dedup_hits\":{},\"tess_pending\":{},\"fill_missing\":{},\"fill_invalid\":{},\"fill_bounds_fallbacks\":{},\
+                 \"stroke_missing\":{},\"stroke_invalid\":{},\"stroke_bounds_fallbacks\":{}}}",
+                shapes_cache.len(),
+                used,
+                budget,
+                evicted_entries,
+                evicted_bytes,
+                pinned,
+                pinned_bytes,
+                shapes_cache.dedup_hits(),
+                shapes_cache.pending_count(),
+                fill_missing,
+                fill_invalid,
+                fill_bounds,
+                stroke_missing,
+                stroke_invalid,
+                stroke_bounds
+            ));
+        }
+        {
+            let bitmaps_cache = self.caches.bitmaps.lock().unwrap();
+            let (used, budget, evicted_entries, evicted_bytes) = bitmaps_cache.mem_stats();
+            out.push_str(&format!(
+                ",\"bitmap_cache\":{{\"entries\":{},\"used_bytes\":{},\"budget_bytes\":{},\"evicted_entries\":{},\"evicted_bytes\":{}}}",
+                bitmaps_cache.len(),
+                used,
+                budget,
+                evicted_entries,
+                evicted_bytes
+            ));
+        }
+        let ds = crate::render::executor::last_draw_stats();
+        out.push_str(&format!(
+            ",\"draw\":{{\"mesh_tris\":{},\"rect_fastpath\":{},\"bounds_fallbacks\":{},\"fill_draws\":{},\"fill_fallbacks\":{},\
+             \"text_draws\":{},\"text_fallbacks\":{},\"stroke_draws\":{},\"stroke_fallbacks\":{},\"bitmap_draws\":{},\"radial_fills\":{}}}",
+            ds.mesh_tris,
+            ds.rect_fastpath,
+            ds.bounds_fallbacks,
+            ds.fill_draws,
+            ds.fill_fallbacks,
+            ds.text_draws,
+            ds.text_fallbacks,
+            ds.stroke_draws,
+            ds.stroke_fallbacks,
+            ds.bitmap_draws,
+            ds.radial_fills
+        ));
+        out.push('}');
+        out
+    }
+}
+
+/// Schema version of `ThreeDSBackend::diagnostics_json`.
+const DIAGNOSTICS_JSON_VERSION: u32 = 4;
+
+/// JSON string literal (or `null`) with the minimal escaping our ASCII-ish diagnostics need.
+fn json_opt_str(value: Option<&str>) -> String {
+    let Some(value) = value else {
+        return "null".to_string();
+    };
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
 }
 
 // --------------------------
@@ -532,21 +1252,34 @@ impl BitmapHandleImpl for ThreeDSBitmapHandleImpl {}
 
 impl RenderBackend for ThreeDSBackend {
     fn viewport_dimensions(&self) -> ViewportDimensions {
-        ViewportDimensions { width: 400, height: 240, scale_factor: 1.0 }
+        let s = self.shared.lock().unwrap();
+        ViewportDimensions { width: s.viewport_w, height: s.viewport_h, scale_factor: s.viewport_scale }
     }
 
-    fn set_viewport_dimensions(&mut self, _dimensions: ViewportDimensions) {}
+    fn set_viewport_dimensions(&mut self, dimensions: ViewportDimensions) {
+        let mut s = self.shared.lock().unwrap();
+        if s.viewport_w != dimensions.width || s.viewport_h != dimensions.height {
+            runlog::log_important(&format!(
+                "viewport {}x{} -> {}x{} scale={}",
+                s.viewport_w, s.viewport_h, dimensions.width, dimensions.height, dimensions.scale_factor
+            ));
+        }
+        s.viewport_w = dimensions.width.max(1);
+        s.viewport_h = dimensions.height.max(1);
+        s.viewport_scale = dimensions.scale_factor;
+    }
 
     /// Register and tessellate a shape at load time.
     ///
     /// Fail-fast safety: if tessellation/earcut work for this shape exceeds 15ms wall-clock,
     /// registration aborts immediately, logs a "Shape Timeout", and falls back to bounds-only rendering.
-    fn register_shape(&mut self, shape: DistilledShape<'_>, _bitmap: &dyn BitmapSource) -> ShapeHandle {
+    fn register_shape(&mut self, shape: DistilledShape<'_>, bitmap_source: &dyn BitmapSource) -> ShapeHandle {
         // Timing logs capture tessellation hotspots per shape so we can correlate slow meshes
         // with shape IDs/bounds without altering the render path.
         let id = self.next_shape_id.fetch_add(1, Ordering::Relaxed);
         let handle_impl = Arc::new(ThreeDSShapeHandleImpl { id });
         let key: ShapeKey = Arc::as_ptr(&handle_impl) as *const () as ShapeKey;
+        self.shared.lock().unwrap().load_progress.note_shape(shape.id);
 
         // Compute bounds in pixel units.
         let b = shape.shape_bounds;
@@ -597,11 +1330,50 @@ impl RenderBackend for ThreeDSBackend {
                 runlog::log_line(&format!("register_shape begin id={} b={} {} {} {}", id, bounds.x, bounds.y, bounds.w, bounds.h));
             }
 
+            // Byte-identical content (common for reused glyph shapes) shares the existing meshes.
+            let tol_px = tessellate::tolerance_for_quality(self.shared.lock().unwrap().quality);
+            let content_hash = tessellate::shape_content_hash(&shape, tol_px);
+            if let Some(hash) = content_hash {
+                if self.caches.shapes.lock().unwrap().alias_content(key, id, hash) {
+                    if runlog::is_verbose() {
+                        runlog::log_line(&format!("register_shape dedup_hit id={} hash={:016x}", id, hash));
+                    }
+                    let mut s = self.shared.lock().unwrap();
+                    s.diagnostics.shapes_registered = s.diagnostics.shapes_registered.saturating_add(1);
+                    return shape_handle_from_impl(handle_impl);
+                }
+                // Meshes persisted by a previous launch of this movie.
+                if self.caches.shapes.lock().unwrap().adopt_warm(key, id, hash) {
+                    if runlog::is_verbose() {
+                        runlog::log_line(&format!("register_shape warm_hit id={} hash={:016x}", id, hash));
+                    }
+                    let mut s = self.shared.lock().unwrap();
+                    s.diagnostics.shapes_registered = s.diagnostics.shapes_registered.saturating_add(1);
+                    return shape_handle_from_impl(handle_impl);
+                }
+            }
+
+            // Queue for the worker; the placeholder draws as bounds until the meshes land.
+            if let Some(worker) = self.tess_worker.as_ref().filter(|_| !has_bitmap_fills(&shape)) {
+                self.caches.shapes.lock().unwrap().insert_pending(key, id, bounds);
+                let job = TessJob { key, id, bounds, content_hash, tol_px, shape: OwnedShape::from_distilled(&shape) };
+                if worker.submit(job).is_ok() {
+                    runlog::stage(&format!("register_shape id={} queued", id), 0);
+                    let mut s = self.shared.lock().unwrap();
+                    s.diagnostics.shapes_registered = s.diagnostics.shapes_registered.saturating_add(1);
+                    return shape_handle_from_impl(handle_impl);
+                }
+                // Worker is gone; the inline path below overwrites the placeholder.
+                if TESS_QUEUE_WARNINGS.fetch_add(1, Ordering::Relaxed) < MAX_UNSUPPORTED_FILL_WARNINGS {
+                    runlog::warn_line(&format!("tess_worker queue closed id={}; tessellating inline", id));
+                }
+            }
+
             let shape_start = Instant::now();
 
             let fills_start = Instant::now();
-            let (fills, fill_failed, fill_partial, group_used_more_correct, group_used_fast, group_used_trivial, unsupported_fill_paints) =
-                match tessellate::tessellate_fills(&shape, id) {
+            let (mut fills, fill_failed, fill_partial, group_used_more_correct, group_used_fast, group_used_trivial, unsupported_fill_paints) =
+                match tessellate::tessellate_fills(&shape, id, tol_px) {
                 Ok(res) => (
                     res.fills,
                     false,
@@ -619,6 +1391,7 @@ impl RenderBackend for ThreeDSBackend {
                 Err(tessellate::TessError::EarcutDenied) => (Vec::new(), true, false, 0, 0, 0, 0),
                 Err(_) => (Vec::new(), true, false, 0, 0, 0, 0),
                 };
+            self.resolve_bitmap_fills(&mut fills, bitmap_source, id);
             let fills_ms = fills_start.elapsed().as_millis() as u64;
             let elapsed_ms = shape_start.elapsed().as_millis() as u64;
             if elapsed_ms > SHAPE_WATCHDOG_MS {
@@ -633,7 +1406,7 @@ impl RenderBackend for ThreeDSBackend {
                     return self.shape_timeout_fallback(key, id, bounds, elapsed_ms, "pre_strokes", &handle_impl);
                 }
                 let strokes_start = Instant::now();
-                let (strokes, stroke_failed, stroke_partial) = match tessellate::tessellate_strokes(&shape, id) {
+                let (strokes, stroke_failed, stroke_partial) = match tessellate::tessellate_strokes(&shape, id, tol_px) {
                     Ok(res) => (res.strokes, false, res.any_failed),
                     Err(tessellate::TessError::NoContours) => (Vec::new(), false, false),
                     Err(_) => (Vec::new(), true, false),
@@ -671,18 +1444,24 @@ impl RenderBackend for ThreeDSBackend {
                 (fill_count, stroke_count, fill_tris, stroke_tris)
             };
 
-            self.caches.shapes.lock().unwrap().insert_meshes(
-                key,
-                id,
-                bounds,
-                fills,
-                fill_failed,
-                fill_partial,
-                strokes,
-                stroke_failed,
-                stroke_partial,
-                text_shape,
-            );
+            {
+                let mut shapes_cache = self.caches.shapes.lock().unwrap();
+                shapes_cache.insert_meshes(
+                    key,
+                    id,
+                    bounds,
+                    fills,
+                    fill_failed,
+                    fill_partial,
+                    strokes,
+                    stroke_failed,
+                    stroke_partial,
+                    text_shape,
+                );
+                if let Some(hash) = content_hash.filter(|_| !fill_failed && !stroke_failed) {
+                    shapes_cache.index_content(key, hash);
+                }
+            }
 
             if runlog::is_verbose() {
                 runlog::log_line(&format!(
@@ -767,7 +1546,10 @@ impl RenderBackend for ThreeDSBackend {
         // Wireframe is a one-shot flag.
         s.wireframe_once = false;
 
+        // Lock caches once per frame, bitmaps before shapes like the executor.
+        let bitmaps_cache = self.caches.bitmaps.lock().unwrap();
         let shapes_cache = self.caches.shapes.lock().unwrap();
+        let (viewport_w, viewport_h) = (s.viewport_w as i32, s.viewport_h as i32);
 
         let mut total: u32 = 0;
         let mut shapes: u32 = 0;
@@ -780,15 +1562,34 @@ impl RenderBackend for ThreeDSBackend {
             println!("[3DS] submit_frame: {} commands", commands.commands.len());
         }
 
+        // Bounds outlines go after the content so shapes don't paint over them.
+        let mut bounds_rects: Vec<(RectI, u64)> = Vec::new();
+
         let mut mask_pending_rect: Option<RectI> = None;
+        let mut mask_pending_shape: Option<(ShapeKey, Matrix2D)> = None;
         let mut mask_mode = false;
 
-        for (i, cmd) in commands.commands.iter().enumerate() {
+        let mut flat = Vec::with_capacity(commands.commands.len());
+        flatten_commands(&commands.commands, &mut flat);
+
+        for (i, entry) in flat.iter().enumerate() {
+            let cmd = match entry {
+                FlatCommand::Draw(cmd) => *cmd,
+                FlatCommand::PushBlend(blend) => {
+                    s.frame.cmds.push(RenderCmd::PushBlend { blend: *blend });
+                    continue;
+                }
+                FlatCommand::PopBlend => {
+                    s.frame.cmds.push(RenderCmd::PopBlend);
+                    continue;
+                }
+            };
             total = total.saturating_add(1);
             match cmd {
                 Command::PushMask => {
                     mask_mode = true;
                     mask_pending_rect = None;
+                    mask_pending_shape = None;
                     other = other.saturating_add(1);
                     if s.dump_next_frame && i < 32 {
                         println!("  {i}: PushMask");
@@ -797,6 +1598,8 @@ impl RenderBackend for ThreeDSBackend {
                 Command::ActivateMask => {
                     if let Some(rect) = mask_pending_rect.take() {
                         s.frame.cmds.push(RenderCmd::PushMaskRect { rect });
+                    } else if let Some((shape_key, transform)) = mask_pending_shape.take() {
+                        s.frame.cmds.push(RenderCmd::PushMaskShape { shape_key, transform });
                     } else {
                         runlog::warn_line("mask activate without rect; ignoring");
                     }
@@ -861,17 +1664,32 @@ impl RenderBackend for ThreeDSBackend {
                         tx: transform.matrix.tx.to_pixels() as f32,
                         ty: transform.matrix.ty.to_pixels() as f32,
                     };
+                    if mask_mode {
+                        // Mask content is never drawn; the executor decides how to clip with it.
+                        mask_pending_shape = Some((key, matrix));
+                        continue;
+                    }
                     let color_transform = to_color_transform(transform.color_transform);
 
                     if let Some(b) = shapes_cache.get_bounds(key) {
                         // Per-shape early reject using transformed bounds.
                         // This avoids pushing per-fill commands for offscreen sprites.
                         let tr = rect_aabb_transformed(b, matrix);
-                        if tr.x + tr.w <= 0 || tr.y + tr.h <= 0 || tr.x >= 400 || tr.y >= 240 {
+                        if tr.x + tr.w <= 0 || tr.y + tr.h <= 0 || tr.x >= viewport_w || tr.y >= viewport_h {
                             continue;
                         }
+                        if s.bounds_overlay {
+                            bounds_rects.push((tr, key as u64));
+                        }
 
                         shapes_cache.touch(key);
+                        if shapes_cache.note_drawn(key, s.diagnostics.frames_submitted) == AUTO_PIN_FRAMES {
+                            let (_, pinned_bytes) = shapes_cache.pinned_stats();
+                            let (_, budget, _, _) = shapes_cache.mem_stats();
+                            if pinned_bytes + shapes_cache.bytes_estimate(key) <= budget / AUTO_PIN_BUDGET_DIVISOR {
+                                shapes_cache.pin(key);
+                            }
+                        }
 
                         let is_text = shapes_cache.is_text_shape(key);
                         if shapes_cache.has_mesh(key) {
@@ -911,14 +1729,13 @@ impl RenderBackend for ThreeDSBackend {
                             // Emit one draw cmd per fill mesh.
                             for fi in 0..fill_count {
                                 let color_key = (key as u64) ^ ((fi as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
-                                let solid_rgba = shapes_cache
-                                    .get_fill_mesh(key, fi)
-                                    .map(|mesh| match mesh.paint {
-                                        crate::render::cache::shapes::FillPaint::SolidRGBA(r, g, b, a) => Some([r, g, b, a]),
-                                        crate::render::cache::shapes::FillPaint::Unsupported => None,
-                                    })
-                                    .unwrap_or(None);
-                                if solid_rgba.is_none() {
+                                let paint = shapes_cache.get_fill_mesh(key, fi).map(|mesh| mesh.paint);
+                                // Gradients are resolved by the executor straight from the cached mesh paint.
+                                let solid_rgba = match paint {
+                                    Some(FillPaint::SolidRGBA(r, g, b, a)) => Some([r, g, b, a]),
+                                    _ => None,
+                                };
+                                if !paint.as_ref().map_or(false, FillPaint::is_drawable) {
                                     let warn_count = UNSUPPORTED_FILL_DRAW_WARNINGS.fetch_add(1, Ordering::Relaxed);
                                     if warn_count < MAX_UNSUPPORTED_FILL_WARNINGS {
                                         runlog::warn_line(&format!(
@@ -953,6 +1770,9 @@ impl RenderBackend for ThreeDSBackend {
                                 shapes_cache.get_total_tri_count(key),
                             );
                             tris_budget = tris_budget.saturating_sub(shape_tris);
+                            if s.tri_heatmap {
+                                s.frame.cmds.push(RenderCmd::DebugTriHeatmap { shape_key: key, transform: matrix });
+                            }
 
                             if shapes_cache.is_tess_partial(key) && s.diagnostics.last_warning.is_none() {
                                 s.diagnostics.last_warning = Some("tri_part".to_string());
@@ -991,8 +1811,8 @@ impl RenderBackend for ThreeDSBackend {
                                 s.diagnostics.last_warning = Some("str_part".to_string());
                             }
                         } else if shapes_cache.is_stroke_failed(key) {
-                            let color_key = (key as u64) ^ 0xA5A5_5A5A_F0F0_0F0F;
-                            let (r, g, b) = debug_color_from_key(color_key);
+                            // Same color as the shape's fill fallback so a failed shape reads as one block.
+                            let (r, g, b) = fallback_color(key as u64);
                             s.frame.cmds.push(RenderCmd::DrawShapeStroke {
                                 shape_key: key,
                                 stroke_idx: 0,
@@ -1014,7 +1834,7 @@ impl RenderBackend for ThreeDSBackend {
                         println!("  {i}: RenderShape");
                     }
                 }
-                Command::RenderBitmap { bitmap, transform, .. } => {
+                Command::RenderBitmap { bitmap, transform, smoothing, .. } => {
                     bitmaps = bitmaps.saturating_add(1);
                     s.seen_real_draw = true;
 
@@ -1032,12 +1852,13 @@ impl RenderBackend for ThreeDSBackend {
                     let color_transform = to_color_transform(transform.color_transform);
 
                     // Only push a blit if the bitmap exists; otherwise keep a short warning.
-                    if self.caches.bitmaps.lock().unwrap().contains_key(key) {
+                    if bitmaps_cache.contains_key(key) {
                         s.frame.cmds.push(RenderCmd::BlitBitmap {
                             bitmap_key: key,
                             transform: matrix,
                             uv: TexUvRect::full(),
                             color_transform,
+                            smoothing: *smoothing,
                         });
                     } else if s.diagnostics.last_warning.is_none() {
                         s.diagnostics.last_warning = Some("miss_bmp".to_string());
@@ -1056,6 +1877,10 @@ impl RenderBackend for ThreeDSBackend {
             }
         }
 
+        for (rect, color_key) in bounds_rects {
+            s.frame.cmds.push(RenderCmd::DebugBoundsRect { rect, color_key });
+        }
+
         if s.dump_next_frame {
             s.dump_next_frame = false;
             println!("[3DS] totals: cmds={total} shapes={shapes} bitmaps={bitmaps} other={other}");
@@ -1089,6 +1914,9 @@ impl RenderBackend for ThreeDSBackend {
             height,
             rgba: vec![0u8; (width as usize) * (height as usize) * 4],
             is_opaque: false,
+            alpha_mode: AlphaMode::Premultiplied,
+            source_width: width,
+            source_height: height,
         };
         self.caches.bitmaps.lock().unwrap().insert(key, surface);
 
@@ -1110,9 +1938,21 @@ impl RenderBackend for ThreeDSBackend {
         Ok(BitmapHandle(handle_impl))
     }
 
-    fn update_texture(&mut self, handle: &BitmapHandle, bitmap: Bitmap, _region: PixelRegion) -> Result<(), RenderError> {
-        // Step 3 bootstrap: we ignore partial region updates and replace the full surface.
+    /// Copies only `region` into the cached surface when possible. A size change, a downscaled
+    /// surface or a change in alpha layout falls back to replacing the whole surface.
+    fn update_texture(&mut self, handle: &BitmapHandle, bitmap: Bitmap, region: PixelRegion) -> Result<(), RenderError> {
         let key = Arc::as_ptr(&handle.0) as *const () as usize;
+        if let Some(pixels) = region_rgba(&bitmap, &region) {
+            let mut bitmaps = self.caches.bitmaps.lock().unwrap();
+            if let Some(surface) = bitmaps.get_mut(key) {
+                if surface.width == bitmap.width()
+                    && surface.height == bitmap.height()
+                    && surface.write_region(region.x_min, region.y_min, region.width(), region.height(), &pixels)
+                {
+                    return Ok(());
+                }
+            }
+        }
         let surface = bitmap_to_surface(bitmap);
         self.caches.bitmaps.lock().unwrap().insert(key, surface);
         Ok(())
@@ -1130,7 +1970,20 @@ impl RenderBackend for ThreeDSBackend {
         "3DS"
     }
 
-    fn set_quality(&mut self, _quality: StageQuality) {}
+    /// Affects shapes registered afterwards (see `tessellate::tolerance_for_quality`); meshes
+    /// already cached keep their tolerance.
+    fn set_quality(&mut self, quality: StageQuality) {
+        let mut s = self.shared.lock().unwrap();
+        if s.quality != quality {
+            runlog::log_important(&format!(
+                "stage quality {:?} -> {:?} tess_tol_px={:.2}",
+                s.quality,
+                quality,
+                tessellate::tolerance_for_quality(quality)
+            ));
+            s.quality = quality;
+        }
+    }
 
     fn compile_pixelbender_shader(&mut self, _shader: PixelBenderShader) -> Result<PixelBenderShaderHandle, RenderError> {
         Err(RenderError::Unimplemented("PixelBender".into()))
@@ -1153,19 +2006,63 @@ impl RenderBackend for ThreeDSBackend {
 
 #[cfg(feature = "net")]
 impl NavigatorBackend for ThreeDSBackend {
-    fn navigate_to_url(&self, _url: &str, _target: &str, _vars: Option<(NavigationMethod, IndexMap<String, String>)>) {}
+    fn navigate_to_url(&self, url: &str, target: &str, vars: Option<(NavigationMethod, IndexMap<String, String>)>) {
+        let mut url = url.to_string();
+        if let Some((method, vars)) = vars {
+            if method == NavigationMethod::Get && !vars.is_empty() {
+                url.push(if url.contains('?') { '&' } else { '?' });
+                let query: Vec<String> = vars
+                    .iter()
+                    .map(|(k, v)| format!("{}={}", form_encode(k), form_encode(v)))
+                    .collect();
+                url.push_str(&query.join("&"));
+            } else if method == NavigationMethod::Post {
+                runlog::warn_line(&format!("navigate POST vars dropped: {}", url));
+            }
+        }
+        let mut s = self.shared.lock().unwrap();
+        if s.pending_navigations.len() >= MAX_PENDING_NAVIGATIONS {
+            runlog::warn_line(&format!("navigate queue full, dropped: {}", url));
+            return;
+        }
+        runlog::log_line(&format!("navigate url={} target={}", url, target));
+        s.pending_navigations.push((url, target.to_string()));
+    }
 
-    fn fetch(&self, _request: Request) -> Pin<Box<dyn Future<Output = Result<Box<dyn SuccessResponse>, ErrorResponse>>>> {
-        Box::pin(async move {
-            Err(ErrorResponse {
-                url: "".to_string(),
-                error: std::io::Error::new(std::io::ErrorKind::NotFound, "Navigator fetch unimplemented").into(),
-            })
-        })
+    fn fetch(&self, request: Request) -> Pin<Box<dyn Future<Output = Result<Box<dyn SuccessResponse>, ErrorResponse>>>> {
+        let raw = request.url().to_string();
+        if request.method() != NavigationMethod::Get {
+            runlog::warn_line(&format!("fetch {:?} unsupported, sending GET: {}", request.method(), raw));
+        }
+        let resolved = match self.resolve_url(&raw) {
+            Ok(resolved) => resolved,
+            Err(_) => {
+                return Box::pin(async move {
+                    Err(fetch::error_response(&raw, std::io::ErrorKind::NotFound, "unresolvable URL".to_string()))
+                });
+            }
+        };
+        let url = resolved.as_str().to_string();
+        match resolved.scheme() {
+            "http" | "https" => fetch::fetch_http(url),
+            _ => match fetch::local_path(&resolved) {
+                Some(path) => fetch::fetch_file(url, path),
+                None => Box::pin(async move {
+                    Err(fetch::error_response(&url, std::io::ErrorKind::NotFound, "unsupported URL scheme".to_string()))
+                }),
+            },
+        }
     }
 
+    /// Absolute URLs parse as-is; anything else is joined onto the root movie's URL.
     fn resolve_url(&self, url: &str) -> Result<Url, url::ParseError> {
-        Url::parse(url)
+        match Url::parse(url) {
+            Ok(parsed) => Ok(parsed),
+            Err(err) => match self.shared.lock().unwrap().base_url.as_ref() {
+                Some(base) => base.join(url),
+                None => Err(err),
+            },
+        }
     }
 
     fn spawn_future(&mut self, future: Pin<Box<dyn Future<Output = Result<(), DialogLoaderError>>>>) {
@@ -1177,16 +2074,28 @@ impl NavigatorBackend for ThreeDSBackend {
 
     fn pre_process_url(&self, url: Url) -> Url { url }
 
-    fn connect_socket(&mut self, _host: String, _port: u16, _timeout: Duration, _handle: SocketHandle, _receiver: Receiver<Vec<u8>>, _sender: Sender<SocketAction>) {
-        runlog::warn_line("navigator connect_socket unimplemented");
+    fn connect_socket(&mut self, host: String, port: u16, timeout: Duration, handle: SocketHandle, receiver: Receiver<Vec<u8>>, sender: Sender<SocketAction>) {
+        runlog::log_line(&format!("navigator connect_socket {}:{}", host, port));
+        socket::connect(host, port, timeout, handle, receiver, sender);
     }
 }
 
 #[cfg(feature = "storage")]
 impl StorageBackend for ThreeDSBackend {
-    fn get(&self, _key: &str) -> Option<Vec<u8>> { None }
-    fn put(&mut self, _key: &str, _value: &[u8]) -> bool { false }
-    fn remove_key(&mut self, _key: &str) {}
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        storage::get(&self.storage_dir()?, key)
+    }
+    fn put(&mut self, key: &str, value: &[u8]) -> bool {
+        match self.storage_dir() {
+            Some(dir) => storage::put(&dir, key, value),
+            None => false,
+        }
+    }
+    fn remove_key(&mut self, key: &str) {
+        if let Some(dir) = self.storage_dir() {
+            storage::remove(&dir, key);
+        }
+    }
 }
 
 impl UiBackend for ThreeDSBackend {
@@ -1194,14 +2103,47 @@ impl UiBackend for ThreeDSBackend {
     fn set_mouse_visible(&mut self, _visible: bool) {}
     fn set_mouse_cursor(&mut self, _cursor: MouseCursor) {}
 
-    fn clipboard_content(&mut self) -> String { String::new() }
-    fn set_clipboard_content(&mut self, _content: String) {}
+    fn clipboard_content(&mut self) -> String { self.shared.lock().unwrap().clipboard.clone() }
+    fn set_clipboard_content(&mut self, content: String) { self.shared.lock().unwrap().clipboard = content; }
 
-    fn set_fullscreen(&mut self, _is_full: bool) -> Result<(), Cow<'static, str>> { Ok(()) }
+    fn set_fullscreen(&mut self, is_full: bool) -> Result<(), Cow<'static, str>> {
+        // The 3DS screen is always "fullscreen"; just remember the request so mouse mapping
+        // and diagnostics follow whatever viewport the player switches to.
+        let mut s = self.shared.lock().unwrap();
+        if s.fullscreen != is_full {
+            runlog::log_line(&format!("ui set_fullscreen {}", is_full));
+        }
+        s.fullscreen = is_full;
+        Ok(())
+    }
     fn display_root_movie_download_failed_message(&self, _unknown: bool, _msg: String) {}
     fn message(&self, _message: &str) {}
-    fn open_virtual_keyboard(&self) {}
-    fn close_virtual_keyboard(&self) {}
+    fn open_virtual_keyboard(&self) {
+        let mut s = self.shared.lock().unwrap();
+        if s.keyboard_open {
+            return;
+        }
+        let debounced = s
+            .keyboard_changed_at
+            .map(|t| t.elapsed().as_millis() < KEYBOARD_DEBOUNCE_MS)
+            .unwrap_or(false);
+        if debounced {
+            return;
+        }
+        s.keyboard_open = true;
+        s.keyboard_changed_at = Some(Instant::now());
+        runlog::log_line("ui keyboard open");
+    }
+
+    fn close_virtual_keyboard(&self) {
+        let mut s = self.shared.lock().unwrap();
+        if !s.keyboard_open {
+            return;
+        }
+        s.keyboard_open = false;
+        s.keyboard_changed_at = Some(Instant::now());
+        runlog::log_line("ui keyboard close");
+    }
 
     fn language(&self) -> LanguageIdentifier { "en-US".parse().unwrap() }
     fn display_unsupported_video(&self, _url: Url) {}
@@ -1214,18 +2156,52 @@ impl UiBackend for ThreeDSBackend {
     fn close_file_dialog(&mut self) {}
 }
 
+impl ThreeDSBackend {
+    /// Spend one line of this frame's AVM log budget; false once it's used up.
+    fn admit_avm_log(&self) -> bool {
+        let mut s = self.shared.lock().unwrap();
+        if s.avm_logs_this_frame < MAX_AVM_LOGS_PER_FRAME {
+            s.avm_logs_this_frame += 1;
+            true
+        } else {
+            s.avm_logs_dropped = s.avm_logs_dropped.saturating_add(1);
+            false
+        }
+    }
+}
+
+/// AS `trace()` output goes to the run log (boottrace) and the HUD trace ring, AVM warnings
+/// to `warnings.txt`; one line each, capped per frame.
 impl LogBackend for ThreeDSBackend {
-    fn avm_trace(&self, message: &str) { println!("[AVM] {}", message); }
-    fn avm_warning(&self, message: &str) { println!("[AVM Warn] {}", message); }
+    fn avm_trace(&self, message: &str) {
+        if self.admit_avm_log() {
+            runlog::avm_trace(&message.replace('\n', "\\n"));
+        }
+    }
+
+    fn avm_warning(&self, message: &str) {
+        if self.admit_avm_log() {
+            runlog::warn_line(&format!("AVM warning: {}", message.replace('\n', "\\n")));
+        }
+    }
 }
 
 // --------------------------
 // Small helpers
 // --------------------------
 
-fn trim_to(s: &str, n: usize) -> &str {
-    if s.len() <= n { return s; }
-    &s[..n]
+/// `application/x-www-form-urlencoded` escaping for `getURL` GET variables.
+#[cfg(feature = "net")]
+fn form_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'*' => out.push(b as char),
+            b' ' => out.push('+'),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
 }
 
 #[cfg(feature = "net")]
@@ -1249,3 +2225,47 @@ const VTABLE: RawWakerVTable = RawWakerVTable::new(
 fn dummy_waker() -> RawWaker {
     RawWaker::new(std::ptr::null(), &VTABLE)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn virtual_keyboard_opens_once_and_debounces_reopen() {
+        let backend = ThreeDSBackend::new(SharedCaches::new(), 400, 240);
+        let backdate = |ms: u64| {
+            let mut s = backend.shared.lock().unwrap();
+            s.keyboard_changed_at = s.keyboard_changed_at.and_then(|t| t.checked_sub(Duration::from_millis(ms)));
+        };
+
+        backend.open_virtual_keyboard();
+        assert!(backend.virtual_keyboard_open());
+        // A second open while one session is up is ignored rather than stacking.
+        let opened_at = backend.shared.lock().unwrap().keyboard_changed_at;
+        backend.open_virtual_keyboard();
+        assert_eq!(backend.shared.lock().unwrap().keyboard_changed_at, opened_at);
+
+        backend.close_virtual_keyboard();
+        assert!(!backend.virtual_keyboard_open());
+        // Content re-opening right after a close is debounced...
+        backend.open_virtual_keyboard();
+        assert!(!backend.virtual_keyboard_open());
+        // ...but goes through once the debounce window has passed.
+        backdate(KEYBOARD_DEBOUNCE_MS as u64 + 50);
+        backend.open_virtual_keyboard();
+        assert!(backend.virtual_keyboard_open());
+
+        // The launcher dismissing it counts as a close; closing again is a no-op.
+        backend.virtual_keyboard_dismissed();
+        assert!(!backend.virtual_keyboard_open());
+        let closed_at = backend.shared.lock().unwrap().keyboard_changed_at;
+        backend.close_virtual_keyboard();
+        assert_eq!(backend.shared.lock().unwrap().keyboard_changed_at, closed_at);
+
+        // A reload forgets the debounce so the new movie can open one immediately.
+        backend.reset_for_reload();
+        backend.open_virtual_keyboard();
+        assert!(backend.virtual_keyboard_open());
+    }
+}
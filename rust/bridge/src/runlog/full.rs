@@ -3,7 +3,7 @@ use std::fs::{self, OpenOptions};
 use std::io::{BufWriter, Write};
 use std::path::Path;
 use std::sync::{Mutex, OnceLock};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use core::fmt::Write as FmtWrite;
 
 const BUILD_ID: &str = "PATCH_010_STEP3_SOLID_COLOR";
@@ -21,10 +21,82 @@ const STATUS_FLUSH_MS: u64 = 200;
 const BOOTTRACE_BUF_MAX: usize = 2048;
 const CONSOLE_QUEUE_MAX: usize = 64;
 const RECENT_WARNINGS_MAX: usize = 8;
+const AVM_TRACE_MAX: usize = 32;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Level { Info, Warn, Error }
 
+/// Time attributed to one stage name: from each `stage()` call to the next one.
+struct StageTiming {
+    name: String,
+    count: u32,
+    total_us: u64,
+    max_us: u64,
+}
+
+/// Append-only log file that rolls over to `<stem>.1.txt` (shifting older rolls up) once it
+/// passes `max_bytes`; checked on flush so a roll never splits a buffered line.
+struct RollingFile {
+    path: String,
+    out: Option<BufWriter<std::fs::File>>,
+    bytes: u64,
+    max_bytes: u64,
+    keep: u32,
+}
+
+impl RollingFile {
+    fn open(path: &str, max_bytes: u64, keep: u32) -> Option<Self> {
+        let file = open_append(path)?;
+        let bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Some(Self { path: path.to_string(), out: Some(BufWriter::new(file)), bytes, max_bytes, keep })
+    }
+
+    /// `boottrace.txt` -> `boottrace.<n>.txt`.
+    fn roll_path(&self, n: u32) -> String {
+        match self.path.strip_suffix(".txt") {
+            Some(stem) => format!("{}.{}.txt", stem, n),
+            None => format!("{}.{}", self.path, n),
+        }
+    }
+
+    fn roll(&mut self) {
+        // Close before renaming; the SD driver may refuse to rename an open file.
+        self.out = None;
+        if self.keep == 0 {
+            let _ = fs::remove_file(&self.path);
+        } else {
+            let _ = fs::remove_file(self.roll_path(self.keep));
+            for n in (1..self.keep).rev() {
+                let _ = fs::rename(self.roll_path(n), self.roll_path(n + 1));
+            }
+            let _ = fs::rename(&self.path, self.roll_path(1));
+        }
+        self.out = open_append(&self.path).map(BufWriter::new);
+        self.bytes = 0;
+    }
+}
+
+impl Write for RollingFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let Some(out) = self.out.as_mut() else {
+            return Ok(buf.len());
+        };
+        let n = out.write(buf)?;
+        self.bytes += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if let Some(out) = self.out.as_mut() {
+            out.flush()?;
+        }
+        if self.max_bytes > 0 && self.bytes >= self.max_bytes {
+            self.roll();
+        }
+        Ok(())
+    }
+}
+
 struct RunLog {
     swf_name: String,
     run_dir: String,
@@ -36,9 +108,9 @@ struct RunLog {
     seq: u64,
     verbosity: u8, // 0=off, 1=important only, 2=verbose
 
-    boottrace: BufWriter<std::fs::File>,
+    boottrace: RollingFile,
     status: BufWriter<std::fs::File>,
-    warnings: BufWriter<std::fs::File>,
+    warnings: RollingFile,
 
     // buffered boottrace pending (reduces write calls)
     bt_buf: String,
@@ -59,6 +131,15 @@ struct RunLog {
     // console ring buffer of important lines for C HUD
     console_q: VecDeque<String>,
     recent_warnings: VecDeque<String>,
+    // last AS trace() lines for the C HUD
+    avm_trace_q: VecDeque<String>,
+
+    // per-frame timing rows (`frame_times_csv`), opened on the first row
+    frame_csv: Option<BufWriter<std::fs::File>>,
+
+    // stage histogram, written to stage_times.txt at shutdown; a handful of names, so a Vec
+    stage_times: Vec<StageTiming>,
+    stage_started: Option<Instant>,
 }
 
 static RUNLOG: OnceLock<Mutex<Option<RunLog>>> = OnceLock::new();
@@ -115,9 +196,10 @@ pub fn init_for_swf(root_path: &str) {
     let status_path = format!("{}/status_snapshot.txt", run_dir);
     let warnings_path = format!("{}/warnings.txt", run_dir);
 
-    let boottrace_file = open_append(&boottrace_path).unwrap();
+    let (max_bytes, keep) = crate::util::config::runlog_rotation();
+    let boottrace_file = RollingFile::open(&boottrace_path, max_bytes, keep).unwrap();
     let status_file = open_append(&status_path).unwrap();
-    let warnings_file = open_append(&warnings_path).unwrap();
+    let warnings_file = RollingFile::open(&warnings_path, max_bytes, keep).unwrap();
 
     let mut rl = RunLog {
         swf_name: swf_name.clone(),
@@ -128,9 +210,9 @@ pub fn init_for_swf(root_path: &str) {
         warnings_path: warnings_path.clone(),
         seq: 0,
         verbosity: 2,
-        boottrace: BufWriter::new(boottrace_file),
+        boottrace: boottrace_file,
         status: BufWriter::new(status_file),
-        warnings: BufWriter::new(warnings_file),
+        warnings: warnings_file,
         bt_buf: String::new(),
         last_flush_ms: 0,
         last_force_flush_ms: 0,
@@ -143,6 +225,10 @@ pub fn init_for_swf(root_path: &str) {
         last_status_flush_ms: 0,
         console_q: VecDeque::new(),
         recent_warnings: VecDeque::new(),
+        avm_trace_q: VecDeque::new(),
+        frame_csv: None,
+        stage_times: Vec::new(),
+        stage_started: None,
     };
 
     // Build info + pointer file to quickly find the run folder
@@ -264,6 +350,21 @@ pub fn log_important(msg: &str) { log_impl(Level::Info, msg, true); }
 pub fn warn_line(msg: &str) { log_impl(Level::Warn, msg, true); }
 pub fn error_line(msg: &str) { log_impl(Level::Error, msg, true); }
 
+/// One AS `trace()` line: logged like `log_line` and kept in the HUD trace ring.
+pub fn avm_trace(msg: &str) {
+    log_line(&format!("AVM trace: {}", msg));
+    if let Some(lock) = RUNLOG.get() {
+        if let Ok(mut guard) = lock.lock() {
+            if let Some(rl) = guard.as_mut() {
+                if rl.avm_trace_q.len() >= AVM_TRACE_MAX {
+                    rl.avm_trace_q.pop_front();
+                }
+                rl.avm_trace_q.push_back(msg.to_string());
+            }
+        }
+    }
+}
+
 /// Update current stage for hang diagnosis.
 /// This updates memory every call; SD write is rate-limited and can be forced.
 pub fn stage(stage: &str, frame: u64) {
@@ -272,6 +373,11 @@ pub fn stage(stage: &str, frame: u64) {
             let Some(rl) = guard.as_mut() else {
                 return;
             };
+            // Charge the time since the previous call to the stage that was running.
+            let now = Instant::now();
+            if let Some(started) = rl.stage_started.replace(now) {
+                record_stage_time(rl, now.duration_since(started).as_micros() as u64);
+            }
             // Avoid per-frame allocations: stage() is called every frame, so reuse buffer storage.
             if rl.last_stage == stage {
                 rl.last_stage_frame = frame;
@@ -292,6 +398,41 @@ pub fn stage(stage: &str, frame: u64) {
     }
 }
 
+fn record_stage_time(rl: &mut RunLog, us: u64) {
+    let timing = match rl.stage_times.iter().position(|t| t.name == rl.last_stage) {
+        Some(i) => &mut rl.stage_times[i],
+        None => {
+            rl.stage_times.push(StageTiming { name: rl.last_stage.clone(), count: 0, total_us: 0, max_us: 0 });
+            rl.stage_times.last_mut().unwrap()
+        }
+    };
+    timing.count += 1;
+    timing.total_us += us;
+    timing.max_us = timing.max_us.max(us);
+}
+
+/// `stage_times.txt`: one line per stage name, most total time first.
+fn write_stage_times(rl: &mut RunLog) {
+    if rl.stage_times.is_empty() {
+        return;
+    }
+    rl.stage_times.sort_by(|a, b| b.total_us.cmp(&a.total_us));
+    let mut text = String::from("stage count total_ms max_ms\n");
+    for t in &rl.stage_times {
+        let _ = writeln!(
+            &mut text,
+            "{} {} {}.{:03} {}.{:03}",
+            t.name,
+            t.count,
+            t.total_us / 1000,
+            t.total_us % 1000,
+            t.max_us / 1000,
+            t.max_us % 1000
+        );
+    }
+    write_all_unbuffered(&format!("{}/stage_times.txt", rl.run_dir), &text);
+}
+
 pub fn status_snapshot(text: &str) {
     if let Some(lock) = RUNLOG.get() {
         if let Ok(mut guard) = lock.lock() {
@@ -344,6 +485,32 @@ pub fn tick() {
     }
 }
 
+/// Append one row to `frame_times.csv` in the run bundle. Times are in microseconds: `tick_us`
+/// is player logic, `render_us` is Ruffle's render pass plus our renderer (0 when skipped).
+///
+/// Rows go through a `BufWriter`, so the SD card sees one write per few hundred frames.
+pub fn frame_times_row(frame: u64, dt_ms: u32, tick_us: u32, render_us: u32, tris: u32) {
+    if let Some(lock) = RUNLOG.get() {
+        if let Ok(mut guard) = lock.lock() {
+            let Some(rl) = guard.as_mut() else {
+                return;
+            };
+            if rl.frame_csv.is_none() {
+                let path = format!("{}/frame_times.csv", rl.run_dir);
+                let Some(file) = open_append(&path) else {
+                    return;
+                };
+                let mut out = BufWriter::new(file);
+                let _ = writeln!(out, "frame,dt_ms,tick_us,render_us,tris");
+                rl.frame_csv = Some(out);
+            }
+            if let Some(out) = rl.frame_csv.as_mut() {
+                let _ = writeln!(out, "{},{},{},{},{}", frame, dt_ms, tick_us, render_us, tris);
+            }
+        }
+    }
+}
+
 /// Drain pending console lines into `out` as newline separated UTF-8.
 /// Returns number of bytes written.
 pub fn drain_console(out: &mut [u8]) -> usize {
@@ -353,25 +520,60 @@ pub fn drain_console(out: &mut [u8]) -> usize {
             let Some(rl) = guard.as_mut() else {
                 return 0;
             };
-            let mut written = 0usize;
-            while let Some(line) = rl.console_q.pop_front() {
-                let bytes = line.as_bytes();
-                if written + bytes.len() + 1 > out.len() { // + '\n'
-                    // Put it back if it doesn't fit
-                    rl.console_q.push_front(line);
-                    break;
-                }
-                out[written..written+bytes.len()].copy_from_slice(bytes);
-                written += bytes.len();
-                out[written] = b'\n';
-                written += 1;
-            }
-            return written;
+            return drain_lines(&mut rl.console_q, out);
         }
     }
     0
 }
 
+/// Drain the recent-warnings ring (at most `RECENT_WARNINGS_MAX` lines, oldest first) into
+/// `out` like `drain_console`. Drained warnings no longer appear in status snapshots.
+pub fn drain_recent_warnings(out: &mut [u8]) -> usize {
+    if out.is_empty() { return 0; }
+    if let Some(lock) = RUNLOG.get() {
+        if let Ok(mut guard) = lock.lock() {
+            let Some(rl) = guard.as_mut() else {
+                return 0;
+            };
+            return drain_lines(&mut rl.recent_warnings, out);
+        }
+    }
+    0
+}
+
+/// Drain the AS trace ring (at most `AVM_TRACE_MAX` lines, oldest first) into `out` like
+/// `drain_console`.
+pub fn drain_avm_trace(out: &mut [u8]) -> usize {
+    if out.is_empty() { return 0; }
+    if let Some(lock) = RUNLOG.get() {
+        if let Ok(mut guard) = lock.lock() {
+            let Some(rl) = guard.as_mut() else {
+                return 0;
+            };
+            return drain_lines(&mut rl.avm_trace_q, out);
+        }
+    }
+    0
+}
+
+/// Move whole lines from `q` into `out`, each followed by '\n'; lines that don't fit stay queued.
+fn drain_lines(q: &mut VecDeque<String>, out: &mut [u8]) -> usize {
+    let mut written = 0usize;
+    while let Some(line) = q.pop_front() {
+        let bytes = line.as_bytes();
+        if written + bytes.len() + 1 > out.len() { // + '\n'
+            // Put it back if it doesn't fit
+            q.push_front(line);
+            break;
+        }
+        out[written..written+bytes.len()].copy_from_slice(bytes);
+        written += bytes.len();
+        out[written] = b'\n';
+        written += 1;
+    }
+    written
+}
+
 pub fn set_verbosity(level: u8) {
     if let Some(lock) = RUNLOG.get() {
         if let Ok(mut guard) = lock.lock() {
@@ -406,6 +608,13 @@ pub fn get_verbosity() -> u8 {
 
 pub fn is_verbose() -> bool { get_verbosity() >= 2 }
 
+/// Folder of the current run bundle (`None` before `init_for_swf`).
+pub fn run_dir() -> Option<String> {
+    let lock = RUNLOG.get()?;
+    let guard = lock.lock().ok()?;
+    guard.as_ref().map(|rl| rl.run_dir.clone())
+}
+
 #[derive(Clone, Debug)]
 pub struct RunlogSnapshot {
     pub last_stage: String,
@@ -446,6 +655,10 @@ fn shutdown_locked(rl: &mut RunLog) {
     }
     let _ = rl.status.flush();
     let _ = rl.warnings.flush();
+    if let Some(out) = rl.frame_csv.as_mut() {
+        let _ = out.flush();
+    }
+    write_stage_times(rl);
     maybe_flush_stage(rl, true);
 }
 
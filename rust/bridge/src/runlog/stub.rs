@@ -5,6 +5,7 @@ const BUILD_ID: &str = "RUNLOG_DISABLED";
 const BASE_ID: &str = "RUNLOG_DISABLED";
 const CONSOLE_QUEUE_MAX: usize = 64;
 const RECENT_WARNINGS_MAX: usize = 8;
+const AVM_TRACE_MAX: usize = 32;
 
 #[derive(Clone, Debug)]
 pub struct RunlogSnapshot {
@@ -16,6 +17,7 @@ pub struct RunlogSnapshot {
 struct RunlogStub {
     console_q: VecDeque<String>,
     recent_warnings: VecDeque<String>,
+    avm_trace_q: VecDeque<String>,
     verbosity: u8,
     last_stage: String,
     last_stage_frame: u64,
@@ -28,6 +30,7 @@ fn with_runlog<T>(f: impl FnOnce(&mut RunlogStub) -> T) -> T {
         Mutex::new(RunlogStub {
             console_q: VecDeque::new(),
             recent_warnings: VecDeque::new(),
+            avm_trace_q: VecDeque::new(),
             verbosity: 1,
             last_stage: String::new(),
             last_stage_frame: 0,
@@ -44,6 +47,7 @@ pub fn init_for_swf(_root_path: &str) {
     with_runlog(|rl| {
         rl.console_q.clear();
         rl.recent_warnings.clear();
+        rl.avm_trace_q.clear();
         rl.last_stage.clear();
         rl.last_stage_frame = 0;
     });
@@ -62,6 +66,16 @@ pub fn warn_line(msg: &str) {
 }
 pub fn error_line(msg: &str) { log_impl(msg, true); }
 
+pub fn avm_trace(msg: &str) {
+    log_line(msg);
+    with_runlog(|rl| {
+        if rl.avm_trace_q.len() >= AVM_TRACE_MAX {
+            rl.avm_trace_q.pop_front();
+        }
+        rl.avm_trace_q.push_back(msg.to_string());
+    });
+}
+
 fn log_impl(msg: &str, important: bool) {
     with_runlog(|rl| {
         if rl.verbosity == 0 {
@@ -88,28 +102,46 @@ pub fn status_snapshot(_text: &str) {}
 
 pub fn tick() {}
 
+pub fn frame_times_row(_frame: u64, _dt_ms: u32, _tick_us: u32, _render_us: u32, _tris: u32) {}
+
 pub fn drain_console(out: &mut [u8]) -> usize {
     if out.is_empty() {
         return 0;
     }
-    with_runlog(|rl| {
-        let mut written = 0usize;
-        while let Some(line) = rl.console_q.pop_front() {
-            let bytes = line.as_bytes();
-            let needed = bytes.len().saturating_add(1);
-            if written + needed > out.len() {
-                rl.console_q.push_front(line);
-                break;
-            }
-            out[written..written + bytes.len()].copy_from_slice(bytes);
-            written += bytes.len();
-            if written < out.len() {
-                out[written] = b'\n';
-                written += 1;
-            }
+    with_runlog(|rl| drain_lines(&mut rl.console_q, out))
+}
+
+pub fn drain_recent_warnings(out: &mut [u8]) -> usize {
+    if out.is_empty() {
+        return 0;
+    }
+    with_runlog(|rl| drain_lines(&mut rl.recent_warnings, out))
+}
+
+pub fn drain_avm_trace(out: &mut [u8]) -> usize {
+    if out.is_empty() {
+        return 0;
+    }
+    with_runlog(|rl| drain_lines(&mut rl.avm_trace_q, out))
+}
+
+fn drain_lines(q: &mut VecDeque<String>, out: &mut [u8]) -> usize {
+    let mut written = 0usize;
+    while let Some(line) = q.pop_front() {
+        let bytes = line.as_bytes();
+        let needed = bytes.len().saturating_add(1);
+        if written + needed > out.len() {
+            q.push_front(line);
+            break;
+        }
+        out[written..written + bytes.len()].copy_from_slice(bytes);
+        written += bytes.len();
+        if written < out.len() {
+            out[written] = b'\n';
+            written += 1;
         }
-        written
-    })
+    }
+    written
 }
 
 pub fn set_verbosity(level: u8) {
@@ -124,6 +156,9 @@ pub fn get_verbosity() -> u8 {
 
 pub fn is_verbose() -> bool { get_verbosity() >= 2 }
 
+/// No run bundle without the `runlog` feature.
+pub fn run_dir() -> Option<String> { None }
+
 pub fn snapshot_info() -> Option<RunlogSnapshot> {
     Some(with_runlog(|rl| RunlogSnapshot {
         last_stage: rl.last_stage.clone(),
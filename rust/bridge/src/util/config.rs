@@ -1,31 +1,452 @@
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicPtr, AtomicU32, Ordering};
 
 const CONFIG_PATH: &str = "sdmc:/flash/renderer.cfg";
 
+/// Max entries in the `stroke_dash` pattern.
+pub const MAX_STROKE_DASH: usize = 8;
+
+/// Upper bound for `runlog_keep`.
+#[cfg(feature = "storage")]
+pub const MAX_RUNLOG_KEEP: u32 = 9;
+
+/// What the circle pad drives (`pad_mode`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PadMode {
+    /// Move the virtual mouse cursor.
+    Mouse,
+    /// Hold arrow keys while the stick is pushed past the threshold.
+    Keys,
+    Off,
+}
+
+/// 3DS buttons the launcher reports by index (`bridge_engine_button`), named as in the
+/// `button_<name>` config keys.
+pub const BUTTON_NAMES: [&str; BUTTON_COUNT] = [
+    "a", "b", "x", "y", "l", "r", "start", "select", "dup", "ddown", "dleft", "dright", "zl", "zr",
+];
+pub const BUTTON_COUNT: usize = 14;
+
+/// What a 3DS button does in the movie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonAction {
+    None,
+    /// Left mouse button at the current cursor position.
+    MouseLeft,
+    /// Flash keycode (see `key_descriptor_from_keycode`).
+    Key(i32),
+}
+
+/// A→Space, B→Escape, Start→Enter, D-pad→arrows, ZL→Shift, ZR→left click. X/Y/L/R/Select stay
+/// unmapped because the launcher uses them for debug controls.
+const DEFAULT_BUTTONS: [ButtonAction; BUTTON_COUNT] = [
+    ButtonAction::Key(32),
+    ButtonAction::Key(27),
+    ButtonAction::None,
+    ButtonAction::None,
+    ButtonAction::None,
+    ButtonAction::None,
+    ButtonAction::Key(13),
+    ButtonAction::None,
+    ButtonAction::Key(38),
+    ButtonAction::Key(40),
+    ButtonAction::Key(37),
+    ButtonAction::Key(39),
+    ButtonAction::Key(16),
+    ButtonAction::MouseLeft,
+];
+
 #[derive(Debug, Clone, Copy)]
 pub struct RenderConfig {
     pub textured_bitmaps: bool,
     pub masks_enabled: bool,
+    pub subpixel_shapes: bool,
+    pub unsupported_fill_hatch: bool,
+    /// Color of bounds rects and fills drawn for shapes that failed to tessellate, `0xRRGGBB`.
+    pub fallback_color: u32,
+    /// Hash each shape key to its own fallback color instead (debugging).
+    pub fallback_key_colors: bool,
+    pub frame_buffers: u32,
+    pub mask_max_tris: u32,
+    pub mask_max_area: u32,
+    pub frame_skip: bool,
+    pub frame_skip_max: u32,
+    /// Alternating on/off lengths in pixels; only the first `stroke_dash_len` are valid.
+    pub stroke_dash: [u16; MAX_STROKE_DASH],
+    pub stroke_dash_len: usize,
+    pub tess_max_scale: f32,
+    pub async_tessellation: bool,
+    pub bilinear: bool,
+    /// Max eye separation in pixels at full 3D slider; 0 renders mono.
+    pub stereo_depth: f32,
+    pub aa_edges: bool,
+    pub pad_mode: PadMode,
+    pub buttons: [ButtonAction; BUTTON_COUNT],
+    pub key_repeat: bool,
+    pub key_repeat_delay_ms: u32,
+    pub key_repeat_interval_ms: u32,
+    /// Bitmaps wider or taller than this are downscaled at registration; 0 keeps full size.
+    pub bitmap_max_dim: u32,
+    /// Shape mesh cache budget in KiB.
+    pub shape_cache_kb: u32,
+    pub shape_cache_persist: bool,
+    /// Run-log text files roll over past this many KiB; 0 never rolls.
+    pub runlog_max_kb: u32,
+    /// Rolled run-log files kept per log (`boottrace.1.txt` is the newest).
+    pub runlog_keep: u32,
+    pub frame_times_csv: bool,
 }
 
 impl Default for RenderConfig {
     fn default() -> Self {
-        Self { textured_bitmaps: true, masks_enabled: true }
+        Self {
+            textured_bitmaps: true,
+            masks_enabled: true,
+            subpixel_shapes: false,
+            unsupported_fill_hatch: true,
+            fallback_color: 0x5A6470,
+            fallback_key_colors: false,
+            frame_buffers: 2,
+            mask_max_tris: 1024,
+            mask_max_area: 400 * 240,
+            frame_skip: false,
+            frame_skip_max: 2,
+            stroke_dash: [0; MAX_STROKE_DASH],
+            stroke_dash_len: 0,
+            tess_max_scale: 1.0,
+            async_tessellation: false,
+            bilinear: true,
+            stereo_depth: 0.0,
+            aa_edges: false,
+            pad_mode: PadMode::Mouse,
+            buttons: DEFAULT_BUTTONS,
+            key_repeat: false,
+            key_repeat_delay_ms: 400,
+            key_repeat_interval_ms: 80,
+            bitmap_max_dim: 1024,
+            shape_cache_kb: 8 * 1024,
+            shape_cache_persist: false,
+            runlog_max_kb: 1024,
+            runlog_keep: 3,
+            frame_times_csv: false,
+        }
     }
 }
 
-static CONFIG: OnceLock<RenderConfig> = OnceLock::new();
+/// Current parsed config. Replaced wholesale by `reload_render_config`; superseded configs are
+/// leaked on purpose so `&'static` borrows handed out earlier stay valid (one small struct
+/// per manual reload).
+static CONFIG: AtomicPtr<RenderConfig> = AtomicPtr::new(core::ptr::null_mut());
 
+/// Render toggles that can change while a movie runs (`set_live_flags`). Seeded from
+/// `renderer.cfg` on first use; `LIVE_INIT` marks that seeding has happened.
+static LIVE_FLAGS: AtomicU32 = AtomicU32::new(0);
+const LIVE_INIT: u32 = 1 << 31;
+pub const LIVE_TEXTURED_BITMAPS: u32 = 1 << 0;
+pub const LIVE_MASKS: u32 = 1 << 1;
+pub const LIVE_SUBPIXEL_SHAPES: u32 = 1 << 2;
+pub const LIVE_UNSUPPORTED_FILL_HATCH: u32 = 1 << 3;
+pub const LIVE_BILINEAR: u32 = 1 << 4;
+pub const LIVE_AA_EDGES: u32 = 1 << 5;
+
+/// Values read from `renderer.cfg` (or defaults), fixed for the life of the process.
+///
+/// The toggles covered by `set_live_flags` may differ at runtime; read those through their
+/// accessors instead of this struct.
 pub fn render_config() -> &'static RenderConfig {
-    CONFIG.get_or_init(read_config)
+    let current = CONFIG.load(Ordering::Acquire);
+    if !current.is_null() {
+        // Safety: non-null values come from `Box::leak`/`Box::into_raw` and are never freed.
+        return unsafe { &*current };
+    }
+    let fresh = Box::into_raw(Box::new(read_config()));
+    match CONFIG.compare_exchange(core::ptr::null_mut(), fresh, Ordering::AcqRel, Ordering::Acquire) {
+        Ok(_) => unsafe { &*fresh },
+        Err(existing) => {
+            // Another thread parsed first; ours was never shared.
+            drop(unsafe { Box::from_raw(fresh) });
+            unsafe { &*existing }
+        }
+    }
+}
+
+/// Re-read `renderer.cfg` and swap it in. Live toggles reset to the file's values; keys read
+/// only when the engine or run log is created (`frame_buffers`, `shape_cache_kb`, `pad_mode`,
+/// `async_tessellation`, `runlog_*`) keep their current effect until the next launch.
+pub fn reload_render_config() -> &'static RenderConfig {
+    let fresh: &'static RenderConfig = Box::leak(Box::new(read_config()));
+    CONFIG.store(fresh as *const RenderConfig as *mut RenderConfig, Ordering::Release);
+    LIVE_FLAGS.store(file_flags(), Ordering::Relaxed);
+    fresh
+}
+
+fn file_flags() -> u32 {
+    let cfg = render_config();
+    [
+        (cfg.textured_bitmaps, LIVE_TEXTURED_BITMAPS),
+        (cfg.masks_enabled, LIVE_MASKS),
+        (cfg.subpixel_shapes, LIVE_SUBPIXEL_SHAPES),
+        (cfg.unsupported_fill_hatch, LIVE_UNSUPPORTED_FILL_HATCH),
+        (cfg.bilinear, LIVE_BILINEAR),
+        (cfg.aa_edges, LIVE_AA_EDGES),
+    ]
+    .iter()
+    .filter(|(on, _)| *on)
+    .fold(LIVE_INIT, |acc, (_, bit)| acc | bit)
+}
+
+/// Current live toggles (`LIVE_*` bits).
+pub fn live_flags() -> u32 {
+    let flags = LIVE_FLAGS.load(Ordering::Relaxed);
+    if flags & LIVE_INIT != 0 {
+        return flags & !LIVE_INIT;
+    }
+    // Racing seeders compute the same value, so losing the exchange is harmless.
+    let seeded = file_flags();
+    let _ = LIVE_FLAGS.compare_exchange(0, seeded, Ordering::Relaxed, Ordering::Relaxed);
+    LIVE_FLAGS.load(Ordering::Relaxed) & !LIVE_INIT
+}
+
+/// Turn the `LIVE_*` bits in `mask` on or off to match `values`; other toggles keep their
+/// state. Takes effect from the next rendered frame. Returns the resulting flags.
+pub fn set_live_flags(mask: u32, values: u32) -> u32 {
+    let mask = mask & !LIVE_INIT;
+    let current = live_flags();
+    let next = (current & !mask) | (values & mask);
+    LIVE_FLAGS.store(next | LIVE_INIT, Ordering::Relaxed);
+    next
+}
+
+fn live(bit: u32) -> bool {
+    live_flags() & bit != 0
 }
 
 pub fn textured_bitmaps_enabled() -> bool {
-    render_config().textured_bitmaps
+    live(LIVE_TEXTURED_BITMAPS)
 }
 
 pub fn masks_enabled() -> bool {
-    render_config().masks_enabled
+    live(LIVE_MASKS)
+}
+
+pub fn subpixel_shapes_enabled() -> bool {
+    live(LIVE_SUBPIXEL_SHAPES)
+}
+
+pub fn unsupported_fill_hatch_enabled() -> bool {
+    live(LIVE_UNSUPPORTED_FILL_HATCH)
+}
+
+/// Fallback color for failed shapes as `(r, g, b)`.
+pub fn fallback_color() -> (u8, u8, u8) {
+    let c = render_config().fallback_color;
+    ((c >> 16) as u8, (c >> 8) as u8, c as u8)
+}
+
+/// Per-shape hashed fallback colors, for telling broken shapes apart.
+pub fn fallback_key_colors_enabled() -> bool {
+    render_config().fallback_key_colors
+}
+
+pub fn frame_buffers() -> u32 {
+    render_config().frame_buffers
+}
+
+pub fn mask_max_tris() -> u32 {
+    render_config().mask_max_tris
+}
+
+pub fn mask_max_area() -> u32 {
+    render_config().mask_max_area
+}
+
+pub fn frame_skip_enabled() -> bool {
+    render_config().frame_skip
+}
+
+pub fn frame_skip_max() -> u32 {
+    render_config().frame_skip_max
+}
+
+/// Largest draw scale shapes are expected to reach; drives the flattening tolerance.
+pub fn tess_max_scale() -> f32 {
+    render_config().tess_max_scale
+}
+
+/// Tessellate shapes on a worker thread, drawing bounds placeholders until meshes are ready.
+pub fn async_tessellation_enabled() -> bool {
+    render_config().async_tessellation
+}
+
+/// Bilinear sampling for smoothed bitmap draws; off forces nearest everywhere.
+pub fn bilinear_enabled() -> bool {
+    live(LIVE_BILINEAR)
+}
+
+/// Max stereo eye separation in pixels; 0 disables the right-eye render.
+pub fn stereo_depth() -> f32 {
+    render_config().stereo_depth
+}
+
+/// Anti-alias solid triangle edges (slower; blends partially covered boundary pixels).
+pub fn aa_edges_enabled() -> bool {
+    live(LIVE_AA_EDGES)
+}
+
+/// Circle pad mapping at startup (the launcher can change it per movie).
+pub fn pad_mode() -> PadMode {
+    render_config().pad_mode
+}
+
+/// Synthesize repeat key-downs for held keys.
+pub fn key_repeat_enabled() -> bool {
+    render_config().key_repeat
+}
+
+/// Hold time before the first repeat, and the gap between repeats after it.
+pub fn key_repeat_timing_ms() -> (u32, u32) {
+    let cfg = render_config();
+    (cfg.key_repeat_delay_ms, cfg.key_repeat_interval_ms)
+}
+
+/// Action for button index `id` (see `BUTTON_NAMES`); out-of-range ids do nothing.
+pub fn button_action(id: usize) -> ButtonAction {
+    render_config().buttons.get(id).copied().unwrap_or(ButtonAction::None)
+}
+
+/// Parse a `button_*` value: `none`, `mouse`, a key name, a single letter/digit, or a keycode.
+#[cfg(feature = "storage")]
+fn parse_button_action(value: &str) -> Option<ButtonAction> {
+    let v = value.to_ascii_lowercase();
+    let keycode = match v.as_str() {
+        "none" | "off" => return Some(ButtonAction::None),
+        "mouse" | "click" => return Some(ButtonAction::MouseLeft),
+        "backspace" => 8,
+        "tab" => 9,
+        "enter" | "return" => 13,
+        "shift" => 16,
+        "control" | "ctrl" => 17,
+        "pause" => 19,
+        "escape" | "esc" => 27,
+        "space" => 32,
+        "left" => 37,
+        "up" => 38,
+        "right" => 39,
+        "down" => 40,
+        _ if v.len() == 1 && v.as_bytes()[0].is_ascii_alphanumeric() => v.as_bytes()[0].to_ascii_uppercase() as i32,
+        _ => v.parse::<i32>().ok().filter(|code| *code > 0)?,
+    };
+    Some(ButtonAction::Key(keycode))
+}
+
+/// Largest bitmap side kept at registration; 0 disables downscaling.
+pub fn bitmap_max_dim() -> u32 {
+    render_config().bitmap_max_dim
+}
+
+/// Initial shape mesh cache budget in KiB (`ShapeCache::set_budget` clamps it).
+pub fn shape_cache_kb() -> u32 {
+    render_config().shape_cache_kb
+}
+
+/// Save tessellated shapes per movie and reload them on the next launch.
+pub fn shape_cache_persist_enabled() -> bool {
+    render_config().shape_cache_persist
+}
+
+/// Size cap for `boottrace.txt`/`warnings.txt` in bytes (0 = unbounded) and how many rolled
+/// copies to keep.
+#[cfg_attr(not(feature = "runlog"), allow(dead_code))]
+pub fn runlog_rotation() -> (u64, u32) {
+    let cfg = render_config();
+    (cfg.runlog_max_kb as u64 * 1024, cfg.runlog_keep)
+}
+
+/// Write one `frame_times.csv` row per tick to the run bundle.
+pub fn frame_times_csv_enabled() -> bool {
+    render_config().frame_times_csv
+}
+
+/// Dash pattern forced onto every stroke; empty means solid strokes.
+pub fn stroke_dash() -> &'static [u16] {
+    let cfg = render_config();
+    &cfg.stroke_dash[..cfg.stroke_dash_len]
+}
+
+/// Write a fully commented `renderer.cfg` listing every key with its default, so users can
+/// discover the options by opening the file. Never overwrites, and never creates the folder:
+/// if `sdmc:/flash` is missing or read-only the write just fails quietly.
+#[cfg(feature = "storage")]
+fn write_template(cfg: &RenderConfig) {
+    use std::io::Write;
+    let on = |v: bool| if v { 1 } else { 0 };
+    let text = format!(
+        "# Ruffle 3DS renderer config. Uncomment a line to change it; values shown are the defaults.
+# Booleans accept 1/0, true/false, on/off, yes/no. See docs/Project_Guide.md for details.
+#
+# textured_bitmaps={}
+# masks_enabled={}
+# subpixel_shapes={}
+# unsupported_fill_hatch={}
+# fallback_color={:06x}
+# fallback_key_colors={}
+# bilinear={}
+# aa_edges={}
+# frame_buffers={}
+# mask_max_tris={}
+# mask_max_area={}
+# frame_skip={}
+# frame_skip_max={}
+# tess_max_scale={}
+# stroke_dash=4,4
+# async_tessellation={}
+# stereo_depth={}
+# pad_mode=mouse
+# button_a=space
+# button_zr=mouse
+# key_repeat={}
+# key_repeat_delay={}
+# key_repeat_interval={}
+# bitmap_max_dim={}
+# shape_cache_kb={}
+# shape_cache_persist={}
+# runlog_max_kb={}
+# runlog_keep={}
+# frame_times_csv={}
+",
+        on(cfg.textured_bitmaps),
+        on(cfg.masks_enabled),
+        on(cfg.subpixel_shapes),
+        on(cfg.unsupported_fill_hatch),
+        cfg.fallback_color,
+        on(cfg.fallback_key_colors),
+        on(cfg.bilinear),
+        on(cfg.aa_edges),
+        cfg.frame_buffers,
+        cfg.mask_max_tris,
+        cfg.mask_max_area,
+        on(cfg.frame_skip),
+        cfg.frame_skip_max,
+        cfg.tess_max_scale,
+        on(cfg.async_tessellation),
+        cfg.stereo_depth,
+        on(cfg.key_repeat),
+        cfg.key_repeat_delay_ms,
+        cfg.key_repeat_interval_ms,
+        cfg.bitmap_max_dim,
+        cfg.shape_cache_kb,
+        on(cfg.shape_cache_persist),
+        cfg.runlog_max_kb,
+        cfg.runlog_keep,
+        on(cfg.frame_times_csv),
+    );
+    let written = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(CONFIG_PATH)
+        .and_then(|mut f| f.write_all(text.as_bytes()));
+    if written.is_ok() {
+        crate::runlog::log_important(&format!("wrote default config template {}", CONFIG_PATH));
+    }
 }
 
 fn read_config() -> RenderConfig {
@@ -37,7 +458,12 @@ fn read_config() -> RenderConfig {
     {
         let text = match std::fs::read_to_string(CONFIG_PATH) {
             Ok(text) => text,
-            Err(_) => return cfg,
+            Err(e) => {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    write_template(&cfg);
+                }
+                return cfg;
+            }
         };
 
         for raw_line in text.lines() {
@@ -60,6 +486,177 @@ fn read_config() -> RenderConfig {
                     "1" | "true" | "TRUE" | "on" | "ON" | "yes" | "YES"
                 );
             }
+            if key.eq_ignore_ascii_case("subpixel_shapes") {
+                cfg.subpixel_shapes = matches!(
+                    value,
+                    "1" | "true" | "TRUE" | "on" | "ON" | "yes" | "YES"
+                );
+            }
+            if key.eq_ignore_ascii_case("frame_buffers") {
+                if let Ok(count) = value.parse::<u32>() {
+                    cfg.frame_buffers = count;
+                }
+            }
+            if key.eq_ignore_ascii_case("mask_max_tris") {
+                if let Ok(tris) = value.parse::<u32>() {
+                    cfg.mask_max_tris = tris;
+                }
+            }
+            if key.eq_ignore_ascii_case("mask_max_area") {
+                if let Ok(area) = value.parse::<u32>() {
+                    cfg.mask_max_area = area;
+                }
+            }
+            if key.eq_ignore_ascii_case("frame_skip") {
+                cfg.frame_skip = matches!(
+                    value,
+                    "1" | "true" | "TRUE" | "on" | "ON" | "yes" | "YES"
+                );
+            }
+            if key.eq_ignore_ascii_case("frame_skip_max") {
+                if let Ok(max) = value.parse::<u32>() {
+                    cfg.frame_skip_max = max;
+                }
+            }
+            if key.eq_ignore_ascii_case("tess_max_scale") {
+                if let Ok(scale) = value.parse::<f32>() {
+                    if scale.is_finite() && scale > 0.0 {
+                        cfg.tess_max_scale = scale;
+                    }
+                }
+            }
+            if key.eq_ignore_ascii_case("async_tessellation") {
+                cfg.async_tessellation = matches!(
+                    value,
+                    "1" | "true" | "TRUE" | "on" | "ON" | "yes" | "YES"
+                );
+            }
+            if key.eq_ignore_ascii_case("bilinear") {
+                cfg.bilinear = matches!(
+                    value,
+                    "1" | "true" | "TRUE" | "on" | "ON" | "yes" | "YES"
+                );
+            }
+            if key.eq_ignore_ascii_case("stereo_depth") {
+                if let Ok(depth) = value.parse::<f32>() {
+                    if depth.is_finite() && depth >= 0.0 {
+                        cfg.stereo_depth = depth;
+                    }
+                }
+            }
+            if key.eq_ignore_ascii_case("aa_edges") {
+                cfg.aa_edges = matches!(
+                    value,
+                    "1" | "true" | "TRUE" | "on" | "ON" | "yes" | "YES"
+                );
+            }
+            if key.eq_ignore_ascii_case("pad_mode") {
+                if value.eq_ignore_ascii_case("mouse") {
+                    cfg.pad_mode = PadMode::Mouse;
+                } else if value.eq_ignore_ascii_case("keys") {
+                    cfg.pad_mode = PadMode::Keys;
+                } else if value.eq_ignore_ascii_case("off") {
+                    cfg.pad_mode = PadMode::Off;
+                }
+            }
+            if key.eq_ignore_ascii_case("key_repeat") {
+                cfg.key_repeat = matches!(
+                    value,
+                    "1" | "true" | "TRUE" | "on" | "ON" | "yes" | "YES"
+                );
+            }
+            if key.eq_ignore_ascii_case("key_repeat_delay") {
+                if let Ok(ms) = value.parse::<u32>() {
+                    cfg.key_repeat_delay_ms = ms;
+                }
+            }
+            if key.eq_ignore_ascii_case("key_repeat_interval") {
+                if let Ok(ms) = value.parse::<u32>() {
+                    if ms > 0 {
+                        cfg.key_repeat_interval_ms = ms;
+                    }
+                }
+            }
+            let button_name = match (key.get(..7), key.get(7..)) {
+                (Some(prefix), Some(name)) if prefix.eq_ignore_ascii_case("button_") => Some(name),
+                _ => None,
+            };
+            if let Some(name) = button_name {
+                let slot = BUTTON_NAMES.iter().position(|b| name.eq_ignore_ascii_case(b));
+                if let (Some(slot), Some(action)) = (slot, parse_button_action(value)) {
+                    cfg.buttons[slot] = action;
+                }
+            }
+            if key.eq_ignore_ascii_case("bitmap_max_dim") {
+                if let Ok(px) = value.parse::<u32>() {
+                    cfg.bitmap_max_dim = px;
+                }
+            }
+            if key.eq_ignore_ascii_case("shape_cache_kb") {
+                if let Ok(kb) = value.parse::<u32>() {
+                    cfg.shape_cache_kb = kb;
+                }
+            }
+            if key.eq_ignore_ascii_case("shape_cache_persist") {
+                cfg.shape_cache_persist = matches!(
+                    value,
+                    "1" | "true" | "TRUE" | "on" | "ON" | "yes" | "YES"
+                );
+            }
+            if key.eq_ignore_ascii_case("runlog_max_kb") {
+                if let Ok(kb) = value.parse::<u32>() {
+                    cfg.runlog_max_kb = kb;
+                }
+            }
+            if key.eq_ignore_ascii_case("runlog_keep") {
+                if let Ok(keep) = value.parse::<u32>() {
+                    cfg.runlog_keep = keep.min(MAX_RUNLOG_KEEP);
+                }
+            }
+            if key.eq_ignore_ascii_case("frame_times_csv") {
+                cfg.frame_times_csv = matches!(
+                    value,
+                    "1" | "true" | "TRUE" | "on" | "ON" | "yes" | "YES"
+                );
+            }
+            if key.eq_ignore_ascii_case("stroke_dash") {
+                let mut pattern = [0u16; MAX_STROKE_DASH];
+                let mut len = 0usize;
+                let mut valid = true;
+                for part in value.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+                    match part.parse::<u16>() {
+                        Ok(px) if px > 0 && len < MAX_STROKE_DASH => {
+                            pattern[len] = px;
+                            len += 1;
+                        }
+                        _ => valid = false,
+                    }
+                }
+                if valid {
+                    cfg.stroke_dash = pattern;
+                    cfg.stroke_dash_len = len;
+                }
+            }
+            if key.eq_ignore_ascii_case("unsupported_fill_hatch") {
+                cfg.unsupported_fill_hatch = matches!(
+                    value,
+                    "1" | "true" | "TRUE" | "on" | "ON" | "yes" | "YES"
+                );
+            }
+            if key.eq_ignore_ascii_case("fallback_color") {
+                let hex = value.trim_start_matches('#').trim_start_matches("0x");
+                if hex.len() == 6 {
+                    if let Ok(rgb) = u32::from_str_radix(hex, 16) {
+                        cfg.fallback_color = rgb;
+                    }
+                }
+            }
+            if key.eq_ignore_ascii_case("fallback_key_colors") {
+                cfg.fallback_key_colors = matches!(
+                    value,
+                    "1" | "true" | "TRUE" | "on" | "ON" | "yes" | "YES"
+                );
+            }
         }
     }
 
@@ -1,4 +1,5 @@
-use std::sync::Once;
+use std::any::Any;
+use std::sync::{Mutex, Once};
 
 struct ConsoleLogger;
 
@@ -17,10 +18,40 @@ impl log::Log for ConsoleLogger {
 
 static LOGGER: ConsoleLogger = ConsoleLogger;
 static INIT: Once = Once::new();
+/// `file:line` of the most recent panic, filled in by the panic hook.
+static PANIC_LOCATION: Mutex<Option<String>> = Mutex::new(None);
 
 pub fn init_logger() {
     INIT.call_once(|| {
         let _ = log::set_logger(&LOGGER);
         log::set_max_level(log::LevelFilter::Info);
+        install_panic_hook();
     });
 }
+
+/// Keep the default panic output but remember where it happened; the payload caught by
+/// `catch_unwind` doesn't carry the location.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if let (Some(loc), Ok(mut slot)) = (info.location(), PANIC_LOCATION.lock()) {
+            *slot = Some(format!("{}:{}", loc.file(), loc.line()));
+        }
+        default_hook(info);
+    }));
+}
+
+/// Readable text for a caught panic payload, with the location recorded by the hook.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let text = if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    };
+    match PANIC_LOCATION.lock().ok().and_then(|mut slot| slot.take()) {
+        Some(loc) => format!("{} at {}", text, loc),
+        None => text,
+    }
+}
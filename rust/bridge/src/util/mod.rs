@@ -1,2 +1,5 @@
 pub mod logging;
 pub mod config;
+pub mod png;
+pub mod swf_tags;
+pub mod text;
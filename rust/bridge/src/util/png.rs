@@ -0,0 +1,55 @@
+//! Minimal PNG writer for debug screenshots (8-bit RGBA, no interlace, filter type 0).
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+/// Screenshots are rare; favor speed over size on the 3DS CPU.
+const ZLIB_LEVEL: u8 = 3;
+
+/// Encode row-major RGBA8 pixels. `None` if `rgba` doesn't hold exactly `width * height` pixels.
+pub fn encode_rgba(width: u32, height: u32, rgba: &[u8]) -> Option<Vec<u8>> {
+    let stride = width as usize * 4;
+    if width == 0 || height == 0 || rgba.len() != stride * height as usize {
+        return None;
+    }
+
+    // Each scanline is prefixed with its filter type byte (0 = none).
+    let mut raw = Vec::with_capacity((stride + 1) * height as usize);
+    for row in rgba.chunks_exact(stride) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+    let idat = miniz_oxide::deflate::compress_to_vec_zlib(&raw, ZLIB_LEVEL);
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&width.to_be_bytes());
+    ihdr.extend_from_slice(&height.to_be_bytes());
+    // Bit depth 8, color type 6 (RGBA), deflate, adaptive filtering, no interlace.
+    ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut out = Vec::with_capacity(SIGNATURE.len() + idat.len() + 64);
+    out.extend_from_slice(&SIGNATURE);
+    write_chunk(&mut out, b"IHDR", &ihdr);
+    write_chunk(&mut out, b"IDAT", &idat);
+    write_chunk(&mut out, b"IEND", &[]);
+    Some(out)
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// CRC-32 (IEEE, reflected), bitwise; fine for one image per button press.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
@@ -0,0 +1,72 @@
+//! Minimal walk over an SWF tag stream (the decompressed data after the header, as held by
+//! `SwfMovie::data()`), used to turn preloader progress into a byte percentage.
+
+use std::collections::HashMap;
+
+const TAG_END: u16 = 0;
+/// DefineShape, DefineShape2, DefineShape3, DefineShape4.
+const SHAPE_TAGS: [u16; 4] = [2, 22, 32, 83];
+
+/// `(character id, end offset)` for each DefineShape tag, in stream order.
+pub fn shape_tag_ends(data: &[u8]) -> Vec<(u16, u32)> {
+    let mut out = Vec::new();
+    let mut pos = 0usize;
+    while pos + 2 <= data.len() {
+        let header = u16::from_le_bytes([data[pos], data[pos + 1]]);
+        pos += 2;
+        let code = header >> 6;
+        let mut len = (header & 0x3f) as usize;
+        if len == 0x3f {
+            let Some(long) = data.get(pos..pos + 4) else {
+                break;
+            };
+            len = u32::from_le_bytes([long[0], long[1], long[2], long[3]]) as usize;
+            pos += 4;
+        }
+        let end = pos.saturating_add(len).min(data.len());
+        if SHAPE_TAGS.contains(&code) && end >= pos + 2 {
+            out.push((u16::from_le_bytes([data[pos], data[pos + 1]]), end as u32));
+        }
+        if code == TAG_END {
+            break;
+        }
+        pos = end;
+    }
+    out
+}
+
+/// How far the preloader has read, tracked through the shapes it registers.
+///
+/// Ruffle registers each DefineShape as it preloads the tag, so the furthest registered shape
+/// marks the bytes processed so far.
+#[derive(Default)]
+pub struct LoadProgress {
+    shape_ends: HashMap<u16, u32>,
+    total: u32,
+    reached: u32,
+}
+
+impl LoadProgress {
+    pub fn new(data: &[u8]) -> Self {
+        Self {
+            shape_ends: shape_tag_ends(data).into_iter().collect(),
+            total: data.len() as u32,
+            reached: 0,
+        }
+    }
+
+    /// A shape with character id `id` was registered; ids that aren't DefineShape tags are ignored.
+    pub fn note_shape(&mut self, id: u16) {
+        if let Some(&end) = self.shape_ends.get(&id) {
+            self.reached = self.reached.max(end);
+        }
+    }
+
+    /// Percent of the tag stream processed; `None` when the movie defines no shapes to track.
+    pub fn percent(&self) -> Option<u8> {
+        if self.shape_ends.is_empty() || self.total == 0 {
+            return None;
+        }
+        Some((self.reached as u64 * 100 / self.total as u64).min(100) as u8)
+    }
+}
@@ -0,0 +1,31 @@
+//! Small string helpers shared by the status line, error screens and the C boundary.
+
+/// Truncate to at most `n` bytes without splitting a UTF-8 sequence (non-ASCII SWF paths/warnings).
+pub fn trim_to(s: &str, n: usize) -> &str {
+    if s.len() <= n { return s; }
+    let mut end = n;
+    while end > 0 && !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trim_to_keeps_whole_chars() {
+        // "è" spans bytes 6..8 of the path.
+        let path = "flash/è.swf";
+        assert_eq!(trim_to(path, 8), "flash/è");
+        assert_eq!(trim_to(path, 7), "flash/");
+        assert_eq!(trim_to(path, 6), "flash/");
+        assert_eq!(trim_to(path, 64), path);
+        // Three- and four-byte sequences at the edge.
+        assert_eq!(trim_to("a€", 3), "a");
+        assert_eq!(trim_to("a€", 4), "a€");
+        assert_eq!(trim_to("🎮x", 3), "");
+        assert_eq!(trim_to("🎮x", 4), "🎮");
+    }
+}
@@ -0,0 +1,101 @@
+#include <3ds.h>
+#include <string.h>
+
+#include "audio_ndsp.h"
+
+// Three buffers keep latency low (3 * 512 frames @ 22050 Hz ~= 70 ms) while
+// giving the mixer thread one buffer of slack.
+#define AUDIO_CHANNEL 0
+#define AUDIO_NUM_BUFS 3
+
+static ndspWaveBuf s_bufs[AUDIO_NUM_BUFS];
+static int16_t* s_data = NULL;
+static uint32_t s_frames_per_buf = 0;
+static int s_next = 0;
+static bool s_open = false;
+// Opens are counted: during a movie reload the new player's backend opens before the old
+// one closes, and both must share the channel.
+static int s_refs = 0;
+
+int bridge_audio_open(uint32_t sample_rate, uint32_t frames_per_buffer) {
+    if (s_open) {
+        s_refs++;
+        return 0;
+    }
+    if (sample_rate == 0 || frames_per_buffer == 0) return -1;
+    if (R_FAILED(ndspInit())) return -2;
+
+    size_t bytes = (size_t)frames_per_buffer * 2 * sizeof(int16_t) * AUDIO_NUM_BUFS;
+    s_data = (int16_t*)linearAlloc(bytes);
+    if (!s_data) {
+        ndspExit();
+        return -3;
+    }
+    memset(s_data, 0, bytes);
+    s_frames_per_buf = frames_per_buffer;
+
+    ndspSetOutputMode(NDSP_OUTPUT_STEREO);
+    ndspChnReset(AUDIO_CHANNEL);
+    ndspChnSetInterp(AUDIO_CHANNEL, NDSP_INTERP_LINEAR);
+    ndspChnSetRate(AUDIO_CHANNEL, (float)sample_rate);
+    ndspChnSetFormat(AUDIO_CHANNEL, NDSP_FORMAT_STEREO_PCM16);
+
+    float mix[12];
+    memset(mix, 0, sizeof(mix));
+    mix[0] = 1.0f;
+    mix[1] = 1.0f;
+    ndspChnSetMix(AUDIO_CHANNEL, mix);
+
+    memset(s_bufs, 0, sizeof(s_bufs));
+    for (int i = 0; i < AUDIO_NUM_BUFS; i++) {
+        s_bufs[i].data_vaddr = s_data + (size_t)i * frames_per_buffer * 2;
+        s_bufs[i].nsamples = frames_per_buffer;
+        s_bufs[i].status = NDSP_WBUF_DONE;
+    }
+    s_next = 0;
+    s_open = true;
+    s_refs = 1;
+    return 0;
+}
+
+uint32_t bridge_audio_free_buffers(void) {
+    if (!s_open) return 0;
+    uint32_t n = 0;
+    for (int i = 0; i < AUDIO_NUM_BUFS; i++) {
+        int idx = (s_next + i) % AUDIO_NUM_BUFS;
+        if (s_bufs[idx].status != NDSP_WBUF_DONE) break;
+        n++;
+    }
+    return n;
+}
+
+int bridge_audio_queue(const int16_t* samples, uint32_t frames) {
+    if (!s_open || !samples) return -1;
+    ndspWaveBuf* buf = &s_bufs[s_next];
+    if (buf->status != NDSP_WBUF_DONE) return -2;
+    if (frames > s_frames_per_buf) frames = s_frames_per_buf;
+
+    int16_t* dst = (int16_t*)buf->data_vaddr;
+    memcpy(dst, samples, (size_t)frames * 2 * sizeof(int16_t));
+    buf->nsamples = frames;
+    DSP_FlushDataCache(dst, (size_t)frames * 2 * sizeof(int16_t));
+    ndspChnWaveBufAdd(AUDIO_CHANNEL, buf);
+
+    s_next = (s_next + 1) % AUDIO_NUM_BUFS;
+    return 0;
+}
+
+void bridge_audio_set_paused(uint32_t paused) {
+    if (!s_open) return;
+    ndspChnSetPaused(AUDIO_CHANNEL, paused != 0);
+}
+
+void bridge_audio_close(void) {
+    if (!s_open) return;
+    if (--s_refs > 0) return;
+    ndspChnWaveBufClear(AUDIO_CHANNEL);
+    ndspExit();
+    linearFree(s_data);
+    s_data = NULL;
+    s_open = false;
+}
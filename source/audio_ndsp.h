@@ -0,0 +1,14 @@
+#pragma once
+#include <stdint.h>
+
+// ndsp PCM sink used by the Rust audio backend (feature "audio").
+// All functions are safe to call from the Rust mixer thread.
+
+// Returns 0 on success, negative if ndsp could not be initialised.
+int bridge_audio_open(uint32_t sample_rate, uint32_t frames_per_buffer);
+// Number of wave buffers that finished playing and can be refilled.
+uint32_t bridge_audio_free_buffers(void);
+// Copies `frames` interleaved stereo PCM16 frames into the next free buffer.
+int bridge_audio_queue(const int16_t* samples, uint32_t frames);
+void bridge_audio_set_paused(uint32_t paused);
+void bridge_audio_close(void);
@@ -0,0 +1,87 @@
+#include <3ds.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "http_fetch.h"
+
+#define HTTP_MAX_REDIRECTS 5
+#define HTTP_CHUNK 4096
+
+static bool s_httpc_ready = false;
+
+static bool http_ensure_init(void) {
+    if (s_httpc_ready) return true;
+    if (R_FAILED(httpcInit(0))) return false;
+    s_httpc_ready = true;
+    return true;
+}
+
+int bridge_http_get(const char* url, size_t max_len, unsigned char** out_ptr, size_t* out_len, uint32_t* out_status,
+                    char* out_url, size_t out_url_cap) {
+    if (!url || !out_ptr || !out_len || !out_status) return -1;
+    *out_ptr = NULL;
+    *out_len = 0;
+    *out_status = 0;
+    if (out_url && out_url_cap) out_url[0] = '\0';
+    if (!http_ensure_init()) return -2;
+
+    char current[1024];
+    strncpy(current, url, sizeof(current) - 1);
+    current[sizeof(current) - 1] = '\0';
+
+    for (int hop = 0; hop <= HTTP_MAX_REDIRECTS; hop++) {
+        httpcContext ctx;
+        if (R_FAILED(httpcOpenContext(&ctx, HTTPC_METHOD_GET, current, 1))) return -3;
+        httpcSetSSLOpt(&ctx, SSLCOPT_DisableVerify);
+        httpcSetKeepAlive(&ctx, HTTPC_KEEPALIVE_DISABLED);
+        httpcAddRequestHeaderField(&ctx, "User-Agent", "Ruffle-3DS");
+
+        if (R_FAILED(httpcBeginRequest(&ctx))) { httpcCloseContext(&ctx); return -4; }
+
+        u32 status = 0;
+        if (R_FAILED(httpcGetResponseStatusCode(&ctx, &status))) { httpcCloseContext(&ctx); return -5; }
+
+        if (status >= 301 && status <= 308 && status != 304) {
+            char location[1024];
+            Result rc = httpcGetResponseHeader(&ctx, "Location", location, sizeof(location));
+            httpcCloseContext(&ctx);
+            if (R_FAILED(rc)) return -6;
+            strncpy(current, location, sizeof(current) - 1);
+            current[sizeof(current) - 1] = '\0';
+            continue;
+        }
+
+        size_t cap = HTTP_CHUNK;
+        size_t len = 0;
+        unsigned char* buf = (unsigned char*)malloc(cap);
+        if (!buf) { httpcCloseContext(&ctx); return -7; }
+
+        for (;;) {
+            if (len == cap) {
+                if (cap >= max_len) { free(buf); httpcCloseContext(&ctx); return -10; }
+                size_t next = cap * 2 > max_len ? max_len : cap * 2;
+                unsigned char* grown = (unsigned char*)realloc(buf, next);
+                if (!grown) { free(buf); httpcCloseContext(&ctx); return -7; }
+                buf = grown;
+                cap = next;
+            }
+            u32 got = 0;
+            Result rc = httpcDownloadData(&ctx, buf + len, (u32)(cap - len), &got);
+            len += got;
+            if (rc == (Result)HTTPC_RESULTCODE_DOWNLOADPENDING) continue;
+            if (R_FAILED(rc)) { free(buf); httpcCloseContext(&ctx); return -8; }
+            break;
+        }
+        httpcCloseContext(&ctx);
+
+        *out_ptr = buf;
+        *out_len = len;
+        *out_status = status;
+        if (out_url && out_url_cap) {
+            strncpy(out_url, current, out_url_cap - 1);
+            out_url[out_url_cap - 1] = '\0';
+        }
+        return 0;
+    }
+    return -9;
+}
@@ -0,0 +1,13 @@
+#pragma once
+#include <stddef.h>
+#include <stdint.h>
+
+// Blocking HTTP(S) GET via httpc, used by the Rust navigator backend (feature "net").
+// Not reentrant: the Rust side serializes calls.
+
+// Returns 0 on success and fills *out_ptr (malloc'd, free with bridge_free_file), *out_len and
+// *out_status. Negative on failure; -10 means the body exceeded max_len.
+// out_url (optional, out_url_cap bytes) receives the URL actually fetched after redirects, as
+// sent in the last Location header (may be relative).
+int bridge_http_get(const char* url, size_t max_len, unsigned char** out_ptr, size_t* out_len, uint32_t* out_status,
+                    char* out_url, size_t out_url_cap);
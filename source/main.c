@@ -68,7 +68,7 @@ static void ui_draw_static(const char* swf_path) {
     printf("\x1b[%d;0H  L: wireframe (hold)", UI_ROW_CONTROLS + 1);
     printf("\x1b[%d;0H  Y: write diag snapshot", UI_ROW_CONTROLS + 2);
     printf("\x1b[%d;0H  X: dump frame cmds", UI_ROW_CONTROLS + 3);
-    printf("\x1b[%d;0H  L+R+X: affine overlay", UI_ROW_CONTROLS + 4);
+    printf("\x1b[%d;0H  L+R+X: affine  L+R+Y: stats", UI_ROW_CONTROLS + 4);
     printf("\x1b[%d;0H  A: click  B: esc", UI_ROW_CONTROLS + 5);
     printf("\x1b[%d;0H  START: pause  SELECT: back", UI_ROW_CONTROLS + 6);
     printf("\x1b[%d;0HLogs:", UI_ROW_LOG_LABEL);
@@ -245,6 +245,17 @@ static void hud_draw(void* ctx) {
     printf("\x1b[%d;0H%-40s", UI_ROW_HUD, line);
 }
 
+// Text fields that gain focus ask for the keyboard; Rust calls this from bridge_engine_tick.
+static uint32_t swkbd_text_input(void* user, const char* initial, char* out, size_t cap) {
+    (void)user;
+    SwkbdState swkbd;
+    swkbdInit(&swkbd, SWKBD_TYPE_NORMAL, 2, -1);
+    swkbdSetInitialText(&swkbd, initial);
+    swkbdSetHintText(&swkbd, "Enter text");
+    SwkbdButton button = swkbdInputText(&swkbd, out, cap);
+    return button == SWKBD_BUTTON_CONFIRM ? 1 : 0;
+}
+
 int main(int argc, char* argv[]) {
     (void)argc; (void)argv;
 
@@ -297,12 +308,26 @@ int main(int argc, char* argv[]) {
             continue;
         }
 
+        bridge_engine_set_text_input_callback(ctx, swkbd_text_input, NULL);
+
         ui_reset_log_state();
         ui_draw_static(swf_path);
 
+        bridge_movie_info_t info;
+        if (bridge_engine_get_movie_info(ctx, &info)) {
+            char msg[41];
+            snprintf(msg, sizeof(msg), "%.0ffps, %lux%lu, %lu frames", (double)info.frame_rate,
+                     (unsigned long)info.width, (unsigned long)info.height, (unsigned long)info.num_frames);
+            ui_set_notice(msg, 180);
+        }
+
+        // Stereo needs the right-eye top buffer; only enable it when renderer.cfg asks for it.
+        bool stereo = bridge_engine_stereo_enabled(ctx) != 0;
+        gfxSet3D(stereo);
+
         // Playback loop
-        bool touch_down = false;
         bool pause_overlay = false;
+        int wheel_cooldown = 0;
         u64 last_frame_ms = osGetTime();
         while (aptMainLoop()) {
             hidScanInput();
@@ -313,13 +338,17 @@ int main(int argc, char* argv[]) {
             if (down & KEY_SELECT) {
                 // Back to file selector
                 bridge_engine_destroy(ctx);
+                gfxSet3D(false);
                 clear_top_black_double();
                 break;
             }
 
             // Hold L to show triangle edges continuously.
             bridge_set_wireframe_hold_ctx(ctx, (held & KEY_L) ? 1 : 0);
-            if (down & KEY_Y) {
+            if ((down & KEY_Y) && (held & KEY_L) && (held & KEY_R)) {
+                uint32_t enabled = bridge_engine_toggle_stats_overlay(ctx);
+                ui_set_notice(enabled ? "stats overlay on" : "stats overlay off", 60);
+            } else if (down & KEY_Y) {
                 // Write an SD snapshot so we can debug freezes later.
                 u64 now_ms = osGetTime();
                 if (now_ms - g_last_snapshot_ms >= 500) {
@@ -343,47 +372,59 @@ int main(int argc, char* argv[]) {
             if (down & KEY_START) {
                 pause_overlay = !pause_overlay;
                 ui_set_notice(pause_overlay ? "pause overlay on" : "pause overlay off", 60);
-                bridge_engine_key(ctx, KEYCODE_PAUSE, true);
             }
-            if (up & KEY_START) {
-                bridge_engine_key(ctx, KEYCODE_PAUSE, false);
-            }
-
-            if (down & KEY_DUP) bridge_engine_key(ctx, KEYCODE_UP, true);
-            if (down & KEY_DDOWN) bridge_engine_key(ctx, KEYCODE_DOWN, true);
-            if (down & KEY_DLEFT) bridge_engine_key(ctx, KEYCODE_LEFT, true);
-            if (down & KEY_DRIGHT) bridge_engine_key(ctx, KEYCODE_RIGHT, true);
 
-            if (up & KEY_DUP) bridge_engine_key(ctx, KEYCODE_UP, false);
-            if (up & KEY_DDOWN) bridge_engine_key(ctx, KEYCODE_DOWN, false);
-            if (up & KEY_DLEFT) bridge_engine_key(ctx, KEYCODE_LEFT, false);
-            if (up & KEY_DRIGHT) bridge_engine_key(ctx, KEYCODE_RIGHT, false);
-
-            if (down & KEY_B) bridge_engine_key(ctx, KEYCODE_ESCAPE, true);
-            if (up & KEY_B) bridge_engine_key(ctx, KEYCODE_ESCAPE, false);
+            // Game buttons go through the engine's remappable table (renderer.cfg `button_*`).
+            // X/Y/L/R are still reported; they are unmapped by default because of the debug combos above.
+            static const struct { u32 key; uint32_t id; } k_buttons[] = {
+                { KEY_A, BRIDGE_BUTTON_A },         { KEY_B, BRIDGE_BUTTON_B },
+                { KEY_X, BRIDGE_BUTTON_X },         { KEY_Y, BRIDGE_BUTTON_Y },
+                { KEY_L, BRIDGE_BUTTON_L },         { KEY_R, BRIDGE_BUTTON_R },
+                { KEY_START, BRIDGE_BUTTON_START }, { KEY_DUP, BRIDGE_BUTTON_DUP },
+                { KEY_DDOWN, BRIDGE_BUTTON_DDOWN }, { KEY_DLEFT, BRIDGE_BUTTON_DLEFT },
+                { KEY_DRIGHT, BRIDGE_BUTTON_DRIGHT }, { KEY_ZL, BRIDGE_BUTTON_ZL },
+                { KEY_ZR, BRIDGE_BUTTON_ZR },
+            };
+            for (size_t i = 0; i < sizeof(k_buttons) / sizeof(k_buttons[0]); i++) {
+                if (down & k_buttons[i].key) bridge_engine_button(ctx, k_buttons[i].id, true);
+                if (up & k_buttons[i].key) bridge_engine_button(ctx, k_buttons[i].id, false);
+            }
 
-            if (down & KEY_A) bridge_engine_mouse_button(ctx, 0, true);
-            if (up & KEY_A) bridge_engine_mouse_button(ctx, 0, false);
+            circlePosition pad;
+            hidCircleRead(&pad);
+            bridge_engine_pad_axis(ctx, pad.dx, pad.dy);
+
+            // C-stick (New 3DS) scrolls: one wheel line every few frames while tilted.
+            circlePosition cstick;
+            hidCstickRead(&cstick);
+            if (cstick.dy > 40 || cstick.dy < -40) {
+                if (wheel_cooldown == 0) {
+                    bridge_engine_mouse_wheel(ctx, cstick.dy > 0 ? 1 : -1);
+                    wheel_cooldown = 6;
+                } else {
+                    wheel_cooldown--;
+                }
+            } else {
+                wheel_cooldown = 0;
+            }
 
             if (held & KEY_TOUCH) {
                 touchPosition touch;
                 hidTouchRead(&touch);
-                int x = (touch.px * screen_w) / 320;
-                int y = (touch.py * screen_h) / 240;
-                bridge_engine_mouse_move(ctx, x, y);
-                if (!touch_down) {
-                    bridge_engine_mouse_button(ctx, 0, true);
-                    touch_down = true;
-                }
-            } else if (touch_down) {
-                bridge_engine_mouse_button(ctx, 0, false);
-                touch_down = false;
+                bridge_engine_touch(ctx, touch.px, touch.py, true);
+            } else {
+                // No-op unless a touch was in progress.
+                bridge_engine_touch(ctx, 0, 0, false);
             }
 
             u64 now_ms = osGetTime();
             u64 dt_ms = now_ms - last_frame_ms;
             last_frame_ms = now_ms;
 
+            if (stereo) {
+                bridge_engine_set_stereo_depth(ctx, osGet3DSliderState());
+            }
+
             // Tick+Render
             bridge_engine_tick(ctx, (uint32_t)dt_ms);
 
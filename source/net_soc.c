@@ -0,0 +1,30 @@
+#include <3ds.h>
+#include <malloc.h>
+#include <stdlib.h>
+
+#include "net_soc.h"
+
+#define SOC_ALIGN 0x1000
+#define SOC_BUFFERSIZE 0x100000
+
+static u32* s_soc_buf = NULL;
+
+static void bridge_soc_exit(void) {
+    if (!s_soc_buf) return;
+    socExit();
+    free(s_soc_buf);
+    s_soc_buf = NULL;
+}
+
+int bridge_soc_init(void) {
+    if (s_soc_buf) return 0;
+    u32* buf = (u32*)memalign(SOC_ALIGN, SOC_BUFFERSIZE);
+    if (!buf) return -1;
+    if (R_FAILED(socInit(buf, SOC_BUFFERSIZE))) {
+        free(buf);
+        return -2;
+    }
+    s_soc_buf = buf;
+    atexit(bridge_soc_exit);
+    return 0;
+}
@@ -0,0 +1,5 @@
+#pragma once
+
+// Lazily starts the soc:U service so Rust std::net sockets work (feature "net").
+// Returns 0 on success (or if already started). soc is shut down at exit.
+int bridge_soc_init(void);